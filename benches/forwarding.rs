@@ -0,0 +1,359 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, ConnectionConfig, RenetClient, RenetServer};
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::{
+    BudgetShare, MessageTransforms, OutgoingTransform, ParallelForwarding, RenetChannelsExt,
+    RepliconRenetPlugins, TotalMemoryBudget,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const CLIENT_COUNTS: [usize; 3] = [1, 16, 128];
+const MESSAGE_SIZES: [usize; 2] = [16, 1024];
+const MESSAGES_PER_CLIENT: usize = 8;
+const LOOKUP_CLIENT_COUNTS: [usize; 3] = [16, 256, 1024];
+const BUDGET_CLIENT_COUNTS: [usize; 2] = [16, 128];
+const BUDGET_ENTITIES_COUNT: usize = 1024;
+const PARALLEL_CLIENT_COUNTS: [usize; 2] = [16, 128];
+const PARALLEL_MESSAGE_SIZE: usize = 1024;
+
+fn server_send(c: &mut Criterion) {
+    let mut group = c.benchmark_group("server_send");
+    for clients_count in CLIENT_COUNTS {
+        for message_size in MESSAGE_SIZES {
+            let mut bench = ForwardingBench::new(clients_count);
+            let message = vec![0; message_size];
+
+            group.throughput(Throughput::Bytes(
+                (clients_count * MESSAGES_PER_CLIENT * message_size) as u64,
+            ));
+            group.bench_function(
+                BenchmarkId::new(format!("{clients_count}_clients"), message_size),
+                |b| {
+                    b.iter_custom(|iter| {
+                        let mut elapsed = Duration::ZERO;
+                        for _ in 0..iter {
+                            let mut replicon_server = bench
+                                .server_app
+                                .world_mut()
+                                .resource_mut::<RepliconServer>();
+                            for client_id in 0..clients_count {
+                                for _ in 0..MESSAGES_PER_CLIENT {
+                                    replicon_server.send(
+                                        ClientId::new(client_id as u64),
+                                        bench.server_channel,
+                                        message.clone(),
+                                    );
+                                }
+                            }
+
+                            let instant = Instant::now();
+                            bench.server_app.world_mut().run_schedule(PostUpdate);
+                            elapsed += instant.elapsed();
+
+                            bench.exchange_packets();
+                            for client in &mut bench.clients {
+                                while client.receive_message(bench.server_channel).is_some() {}
+                            }
+                        }
+
+                        elapsed
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn server_receive(c: &mut Criterion) {
+    let mut group = c.benchmark_group("server_receive");
+    for clients_count in CLIENT_COUNTS {
+        for message_size in MESSAGE_SIZES {
+            let mut bench = ForwardingBench::new(clients_count);
+            let message = vec![0; message_size];
+
+            group.throughput(Throughput::Bytes(
+                (clients_count * MESSAGES_PER_CLIENT * message_size) as u64,
+            ));
+            group.bench_function(
+                BenchmarkId::new(format!("{clients_count}_clients"), message_size),
+                |b| {
+                    b.iter_custom(|iter| {
+                        let mut elapsed = Duration::ZERO;
+                        for _ in 0..iter {
+                            for client in &mut bench.clients {
+                                for _ in 0..MESSAGES_PER_CLIENT {
+                                    client.send_message(bench.client_channel, message.clone());
+                                }
+                            }
+                            bench.exchange_packets();
+
+                            let instant = Instant::now();
+                            bench.server_app.world_mut().run_schedule(PreUpdate);
+                            elapsed += instant.elapsed();
+
+                            let mut replicon_server = bench
+                                .server_app
+                                .world_mut()
+                                .resource_mut::<RepliconServer>();
+                            for _ in replicon_server.receive(bench.client_channel) {}
+                        }
+
+                        elapsed
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn budget_share(c: &mut Criterion) {
+    let mut group = c.benchmark_group("budget_share");
+    for clients_count in BUDGET_CLIENT_COUNTS {
+        for share in [BudgetShare::Even, BudgetShare::Visibility] {
+            let mut bench =
+                ForwardingBench::with_visibility(clients_count, VisibilityPolicy::Whitelist);
+            let server_configs = {
+                let channels = bench.server_app.world().resource::<RepliconChannels>();
+                channels.get_server_configs()
+            };
+            bench.server_app.insert_resource(
+                TotalMemoryBudget::new(usize::MAX, &server_configs).with_share(share),
+            );
+
+            // Mixed view sizes: each next client sees a larger part of the world.
+            let world = bench.server_app.world_mut();
+            let entities: Vec<_> = (0..BUDGET_ENTITIES_COUNT)
+                .map(|_| world.spawn(Replicated).id())
+                .collect();
+            let mut replicated_clients = world.resource_mut::<ReplicatedClients>();
+            for client_id in 0..clients_count {
+                let visible_count = BUDGET_ENTITIES_COUNT * (client_id + 1) / clients_count;
+                let visibility = replicated_clients
+                    .client_mut(ClientId::new(client_id as u64))
+                    .visibility_mut();
+                for &entity in &entities[..visible_count] {
+                    visibility.set_visibility(entity, true);
+                }
+            }
+            bench.server_app.update();
+            bench.exchange_packets();
+
+            group.bench_function(
+                BenchmarkId::new(format!("{clients_count}_clients"), format!("{share:?}")),
+                |b| {
+                    b.iter_custom(|iter| {
+                        let mut elapsed = Duration::ZERO;
+                        for _ in 0..iter {
+                            let mut replicon_server = bench
+                                .server_app
+                                .world_mut()
+                                .resource_mut::<RepliconServer>();
+                            for client_id in 0..clients_count {
+                                replicon_server.send(
+                                    ClientId::new(client_id as u64),
+                                    bench.server_channel,
+                                    vec![0; 16],
+                                );
+                            }
+
+                            let instant = Instant::now();
+                            bench.server_app.world_mut().run_schedule(PostUpdate);
+                            elapsed += instant.elapsed();
+
+                            bench.exchange_packets();
+                            for client in &mut bench.clients {
+                                while client.receive_message(bench.server_channel).is_some() {}
+                            }
+                        }
+
+                        elapsed
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn parallel_forwarding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel_forwarding");
+    for clients_count in PARALLEL_CLIENT_COUNTS {
+        for mode in ["sync", "parallel", "background"] {
+            let mut bench = ForwardingBench::new(clients_count);
+            bench
+                .server_app
+                .world_mut()
+                .resource_mut::<MessageTransforms>()
+                .add_outgoing(Checksum);
+            if mode != "sync" {
+                bench.server_app.insert_resource(
+                    ParallelForwarding::default().with_background(mode == "background"),
+                );
+            }
+            let message = vec![0; PARALLEL_MESSAGE_SIZE];
+
+            group.throughput(Throughput::Bytes(
+                (clients_count * MESSAGES_PER_CLIENT * PARALLEL_MESSAGE_SIZE) as u64,
+            ));
+            group.bench_function(
+                BenchmarkId::new(format!("{clients_count}_clients"), mode),
+                |b| {
+                    b.iter_custom(|iter| {
+                        let mut elapsed = Duration::ZERO;
+                        for _ in 0..iter {
+                            let mut replicon_server = bench
+                                .server_app
+                                .world_mut()
+                                .resource_mut::<RepliconServer>();
+                            for client_id in 0..clients_count {
+                                for _ in 0..MESSAGES_PER_CLIENT {
+                                    replicon_server.send(
+                                        ClientId::new(client_id as u64),
+                                        bench.server_channel,
+                                        message.clone(),
+                                    );
+                                }
+                            }
+
+                            let instant = Instant::now();
+                            bench.server_app.world_mut().run_schedule(PostUpdate);
+                            elapsed += instant.elapsed();
+
+                            bench.exchange_packets();
+                            for client in &mut bench.clients {
+                                while client.receive_message(bench.server_channel).is_some() {}
+                            }
+                        }
+
+                        elapsed
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn client_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("client_lookup");
+    for clients_count in LOOKUP_CLIENT_COUNTS {
+        let bench = ForwardingBench::new(clients_count);
+        // The last client is the worst case for linear lookups.
+        let client_id = ClientId::new(clients_count as u64 - 1);
+
+        let replicated_clients = bench.server_app.world().resource::<ReplicatedClients>();
+        group.bench_function(BenchmarkId::new("replicated_clients", clients_count), |b| {
+            b.iter(|| black_box(replicated_clients.get_client(black_box(client_id))))
+        });
+
+        let renet_server = bench.server_app.world().resource::<RenetServer>();
+        group.bench_function(BenchmarkId::new("renet_server", clients_count), |b| {
+            b.iter(|| black_box(renet_server.is_connected(black_box(client_id.get()))))
+        });
+    }
+    group.finish();
+}
+
+/// Server with connected local clients.
+///
+/// Clients are plain renet clients without apps to measure only the server side.
+/// Messages go over separate channels, so nothing reads them except the forwarding systems.
+struct ForwardingBench {
+    server_app: App,
+    clients: Vec<RenetClient>,
+    server_channel: u8,
+    client_channel: u8,
+}
+
+impl ForwardingBench {
+    fn new(clients_count: usize) -> Self {
+        Self::with_visibility(clients_count, Default::default())
+    }
+
+    fn with_visibility(clients_count: usize, visibility_policy: VisibilityPolicy) -> Self {
+        let mut server_app = App::new();
+        server_app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                visibility_policy,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ));
+
+        let mut channels = server_app.world_mut().resource_mut::<RepliconChannels>();
+        let server_channel = channels.create_server_channel(ChannelKind::Unreliable.into());
+        let client_channel = channels.create_client_channel(ChannelKind::Unreliable.into());
+
+        let mut server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels.get_server_configs(),
+            client_channels_config: channels.get_client_configs(),
+            ..Default::default()
+        });
+        let clients = (0..clients_count)
+            .map(|client_id| server.new_local_client(client_id as u64))
+            .collect();
+        server_app.insert_resource(server);
+
+        let mut bench = Self {
+            server_app,
+            clients,
+            server_channel,
+            client_channel,
+        };
+        bench.server_app.update();
+        bench.exchange_packets();
+        bench.server_app.update();
+
+        let connected_clients = bench.server_app.world().resource::<ConnectedClients>();
+        assert_eq!(connected_clients.len(), clients_count);
+
+        bench
+    }
+
+    fn exchange_packets(&mut self) {
+        let mut server = self.server_app.world_mut().resource_mut::<RenetServer>();
+        for (client_id, client) in self.clients.iter_mut().enumerate() {
+            server
+                .process_local_client(client_id as u64, client)
+                .expect("local client should be connected");
+        }
+    }
+}
+
+/// CPU-bound transform that imitates the cost of encryption.
+///
+/// Appends a checksum, so the message is copied like with a real transform.
+struct Checksum;
+
+impl OutgoingTransform for Checksum {
+    fn transform(&self, _client_id: ClientId, _channel_id: u8, message: Bytes) -> Bytes {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for _ in 0..16 {
+            for &byte in &message {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(message.len() + size_of::<u64>());
+        bytes.extend_from_slice(&message);
+        bytes.extend_from_slice(&hash.to_le_bytes());
+        bytes.into()
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().warm_up_time(Duration::from_millis(500));
+    targets = server_send, server_receive, budget_share, parallel_forwarding, client_lookup
+}
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! Runs server and client in one process without sockets to showcase replication.
+//! Both apps are driven from `main` and exchange packets directly via renet's local client.
+
+use std::{thread, time::Duration};
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::{
+    renet::{ConnectionConfig, RenetClient, RenetServer},
+    RenetChannelsExt, RepliconRenetPlugins,
+};
+use serde::{Deserialize, Serialize};
+
+fn main() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .replicate::<Counter>();
+    }
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server = RenetServer::new(ConnectionConfig {
+        server_channels_config: channels.get_server_configs(),
+        client_channels_config: channels.get_client_configs(),
+        ..Default::default()
+    });
+    let client = server.new_local_client(CLIENT_ID);
+
+    server_app
+        .insert_resource(server)
+        .add_systems(Startup, spawn_counter)
+        .add_systems(Update, increment_counter);
+    client_app
+        .insert_resource(client)
+        .add_systems(Update, print_counter);
+
+    for _ in 0..10 {
+        server_app.update();
+        exchange_packets(&mut server_app, &mut client_app);
+        client_app.update();
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+const CLIENT_ID: u64 = 1;
+
+/// Passes packets between the server and the local client.
+///
+/// With a real transport it's done by the transport plugins.
+fn exchange_packets(server_app: &mut App, client_app: &mut App) {
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    let mut client = client_app.world_mut().resource_mut::<RenetClient>();
+    server
+        .process_local_client(CLIENT_ID, &mut client)
+        .expect("local client should be connected");
+}
+
+fn spawn_counter(mut commands: Commands) {
+    commands.spawn((Replicated, Counter(0)));
+}
+
+fn increment_counter(mut counters: Query<&mut Counter>) {
+    for mut counter in &mut counters {
+        **counter += 1;
+        println!("server counter: {}", **counter);
+    }
+}
+
+fn print_counter(counters: Query<&Counter, Changed<Counter>>) {
+    for counter in &counters {
+        println!("client counter: {}", **counter);
+    }
+}
+
+#[derive(Component, Deref, DerefMut, Deserialize, Serialize)]
+struct Counter(u32);
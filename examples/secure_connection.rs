@@ -0,0 +1,159 @@
+//! Connects a client to a server with netcode's secure authentication.
+//!
+//! In production connect tokens are issued by a separate authentication service (usually over HTTPS)
+//! after it verifies the player. Here the service is simulated in-process by [`AuthService`].
+//! The private key is known only to the authentication service and game servers, it's never sent to clients.
+//!
+//! The second client receives an already expired token and is ignored by the server.
+
+use std::{
+    error::Error,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::{
+    netcode::{
+        generate_random_bytes, ClientAuthentication, ConnectToken, NetcodeClientTransport,
+        NetcodeServerTransport, ServerAuthentication, ServerConfig, NETCODE_KEY_BYTES,
+    },
+    protocol_id,
+    renet::{ConnectionConfig, RenetClient, RenetServer},
+    RenetChannelsExt, RepliconRenetPlugins,
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Load the key from a secret storage shared with the authentication service instead.
+    let private_key = generate_random_bytes();
+
+    let mut server_app = create_app();
+    let server_addr = start_server(&mut server_app, private_key)?;
+    let auth_service = AuthService {
+        private_key,
+        server_addr,
+    };
+
+    let mut client_app = create_app();
+    let connect_token = auth_service.issue_token(1, SystemTime::now())?;
+    connect(&mut client_app, connect_token)?;
+
+    // Simulate a token that was issued long ago and leaked or wasn't used in time.
+    let mut late_client_app = create_app();
+    let issue_time = SystemTime::now() - Duration::from_secs(2 * TOKEN_EXPIRE_SECS);
+    let expired_token = auth_service.issue_token(2, issue_time)?;
+    connect(&mut late_client_app, expired_token)?;
+
+    for _ in 0..50 {
+        server_app.update();
+        client_app.update();
+        late_client_app.update();
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let client = client_app.world().resource::<RenetClient>();
+    println!("client connected: {}", client.is_connected());
+    let late_client = late_client_app.world().resource::<RenetClient>();
+    println!(
+        "client with expired token connected: {}",
+        late_client.is_connected()
+    );
+
+    Ok(())
+}
+
+/// Issues connect tokens for players.
+struct AuthService {
+    private_key: [u8; NETCODE_KEY_BYTES],
+    server_addr: SocketAddr,
+}
+
+impl AuthService {
+    /// Creates a token for the given client.
+    ///
+    /// In a real service `client_id` is taken from the player's account
+    /// after verifying credentials, so players can't impersonate each other.
+    fn issue_token(
+        &self,
+        client_id: u64,
+        issue_time: SystemTime,
+    ) -> Result<ConnectToken, Box<dyn Error>> {
+        let current_time = issue_time.duration_since(SystemTime::UNIX_EPOCH)?;
+        let token = ConnectToken::generate(
+            current_time,
+            PROTOCOL_ID,
+            TOKEN_EXPIRE_SECS,
+            client_id,
+            TIMEOUT_SECS,
+            vec![self.server_addr],
+            None,
+            &self.private_key,
+        )?;
+
+        Ok(token)
+    }
+}
+
+fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    app
+}
+
+fn start_server(
+    app: &mut App,
+    private_key: [u8; NETCODE_KEY_BYTES],
+) -> Result<SocketAddr, Box<dyn Error>> {
+    let channels = app.world().resource::<RepliconChannels>();
+    let server = RenetServer::new(ConnectionConfig {
+        server_channels_config: channels.get_server_configs(),
+        client_channels_config: channels.get_client_configs(),
+        ..Default::default()
+    });
+
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let server_addr = socket.local_addr()?;
+    let server_config = ServerConfig {
+        current_time: SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?,
+        max_clients: 10,
+        protocol_id: PROTOCOL_ID,
+        // Tokens must contain the same address, otherwise the server will ignore them.
+        public_addresses: vec![server_addr],
+        authentication: ServerAuthentication::Secure { private_key },
+    };
+    let transport = NetcodeServerTransport::new(server_config, socket)?;
+
+    app.insert_resource(server).insert_resource(transport);
+
+    Ok(server_addr)
+}
+
+fn connect(app: &mut App, connect_token: ConnectToken) -> Result<(), Box<dyn Error>> {
+    let channels = app.world().resource::<RepliconChannels>();
+    let client = RenetClient::new(ConnectionConfig {
+        server_channels_config: channels.get_server_configs(),
+        client_channels_config: channels.get_client_configs(),
+        ..Default::default()
+    });
+
+    let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let authentication = ClientAuthentication::Secure { connect_token };
+    let transport = NetcodeClientTransport::new(current_time, authentication, socket)?;
+
+    app.insert_resource(client).insert_resource(transport);
+
+    Ok(())
+}
+
+const PROTOCOL_ID: u64 = protocol_id(env!("CARGO_PKG_VERSION"));
+
+/// How long the token can be used to start a connection.
+///
+/// Keep it short, since anyone who intercepts the token can use it until it expires.
+const TOKEN_EXPIRE_SECS: u64 = 30;
+
+/// How long the connection can stay silent before it's dropped.
+const TIMEOUT_SECS: i32 = 15;
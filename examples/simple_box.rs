@@ -3,7 +3,7 @@
 
 use std::{
     error::Error,
-    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     time::SystemTime,
 };
 
@@ -14,10 +14,12 @@ use bevy::{
 };
 use bevy_replicon::prelude::*;
 use bevy_replicon_renet::{
+    bind_client_socket, bind_dual_stack,
     netcode::{
         ClientAuthentication, NetcodeClientTransport, NetcodeServerTransport, ServerAuthentication,
         ServerConfig,
     },
+    protocol_id,
     renet::{ConnectionConfig, RenetClient, RenetServer},
     RenetChannelsExt, RepliconRenetPlugins,
 };
@@ -84,7 +86,7 @@ impl SimpleBoxPlugin {
                 });
 
                 let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
-                let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+                let socket = bind_dual_stack(port)?;
                 let server_config = ServerConfig {
                     current_time,
                     max_clients: 10,
@@ -120,7 +122,7 @@ impl SimpleBoxPlugin {
                 let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
                 let client_id = current_time.as_millis() as u64;
                 let server_addr = SocketAddr::new(ip, port);
-                let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+                let socket = bind_client_socket(server_addr)?;
                 let authentication = ClientAuthentication::Unsecure {
                     client_id,
                     protocol_id: PROTOCOL_ID,
@@ -221,7 +223,7 @@ impl SimpleBoxPlugin {
 }
 
 const PORT: u16 = 5000;
-const PROTOCOL_ID: u64 = 0;
+const PROTOCOL_ID: u64 = protocol_id(env!("CARGO_PKG_VERSION"));
 
 #[derive(Parser, PartialEq, Resource)]
 enum Cli {
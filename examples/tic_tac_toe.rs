@@ -4,17 +4,19 @@
 use std::{
     error::Error,
     fmt::{self, Formatter},
-    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     time::SystemTime,
 };
 
 use bevy::prelude::*;
 use bevy_replicon::prelude::*;
 use bevy_replicon_renet::{
+    bind_client_socket, bind_dual_stack,
     netcode::{
         ClientAuthentication, NetcodeClientTransport, NetcodeServerTransport, ServerAuthentication,
         ServerConfig,
     },
+    protocol_id,
     renet::{ConnectionConfig, RenetClient, RenetServer},
     RenetChannelsExt, RepliconRenetPlugins,
 };
@@ -92,7 +94,7 @@ const GRID_SIZE: usize = 3;
 
 const BACKGROUND_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
 
-const PROTOCOL_ID: u64 = 0;
+const PROTOCOL_ID: u64 = protocol_id(env!("CARGO_PKG_VERSION"));
 
 // Bottom text defined in two sections, first for text and second for symbols with different font.
 const TEXT_SECTION: usize = 0;
@@ -244,7 +246,7 @@ impl TicTacToePlugin {
                 });
 
                 let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
-                let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+                let socket = bind_dual_stack(port)?;
                 let server_config = ServerConfig {
                     current_time,
                     max_clients: 1,
@@ -271,7 +273,7 @@ impl TicTacToePlugin {
                 let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
                 let client_id = current_time.as_millis() as u64;
                 let server_addr = SocketAddr::new(ip, port);
-                let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+                let socket = bind_client_socket(server_addr)?;
                 let authentication = ClientAuthentication::Unsecure {
                     client_id,
                     protocol_id: PROTOCOL_ID,
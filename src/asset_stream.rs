@@ -0,0 +1,89 @@
+use std::mem;
+
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+
+/// Renet channel used to stream large byte blobs, like maps or mods, from the server to clients.
+///
+/// Insert it on both server and client to enable [`AssetStreams`](crate::AssetStreams)
+/// and [`AssetReceived`](crate::AssetReceived).
+///
+/// Requires a [`SendType::ReliableOrdered`](bevy_renet::renet::SendType::ReliableOrdered)
+/// [`ChannelConfig`](bevy_renet::renet::ChannelConfig) with this ID,
+/// see [channel IDs](crate::ControlChannel#channel-ids).
+///
+/// Since it's a separate renet channel, a running transfer doesn't block replication.
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct AssetStreamChannel(pub u8);
+
+const HEADER_SIZE: usize = 3 * mem::size_of::<u64>();
+
+/// Part of a streamed blob.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Chunk {
+    pub(crate) stream_id: u64,
+    pub(crate) total_len: usize,
+    pub(crate) offset: usize,
+    pub(crate) data: Bytes,
+}
+
+impl Chunk {
+    /// Returns the size of the encoded chunk with the given data length.
+    #[cfg(feature = "server")]
+    pub(crate) fn encoded_size(data_len: usize) -> usize {
+        HEADER_SIZE + data_len
+    }
+
+    /// Prepends stream ID, total blob length and offset of the data.
+    #[cfg(feature = "server")]
+    pub(crate) fn encode(&self) -> Bytes {
+        let mut bytes = Vec::with_capacity(Self::encoded_size(self.data.len()));
+        bytes.extend_from_slice(&self.stream_id.to_le_bytes());
+        bytes.extend_from_slice(&(self.total_len as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.offset as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes.into()
+    }
+
+    /// Reads a chunk encoded by [`Self::encode`].
+    #[cfg(feature = "client")]
+    pub(crate) fn decode(mut message: Bytes) -> Option<Self> {
+        if message.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let data = message.split_off(HEADER_SIZE);
+        let mut fields = message
+            .chunks_exact(mem::size_of::<u64>())
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+        let stream_id = fields.next()?;
+        let total_len = fields.next()?.try_into().ok()?;
+        let offset = fields.next()?.try_into().ok()?;
+
+        Some(Self {
+            stream_id,
+            total_len,
+            offset,
+            data,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "server", feature = "client"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let chunk = Chunk {
+            stream_id: 1,
+            total_len: 10,
+            offset: 2,
+            data: Bytes::from_static(&[1, 2, 3]),
+        };
+
+        let message = chunk.encode();
+        assert_eq!(message.len(), Chunk::encoded_size(chunk.data.len()));
+        assert_eq!(Chunk::decode(message), Some(chunk));
+    }
+}
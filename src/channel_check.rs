@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+/// Numbers of channels that renet configs were created with.
+///
+/// Renet doesn't allow channels that are missing from its configs, so configs must be created
+/// after all events are registered. In debug builds, the server and client plugins compare this
+/// resource with [`RepliconChannels`] once [`RenetServer`](bevy_renet::renet::RenetServer) or
+/// [`RenetClient`](bevy_renet::renet::RenetClient) is inserted and log an error if channels
+/// were registered after the configs were created. Without this resource, the check is skipped.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{
+///     renet::{ConnectionConfig, RenetServer},
+///     ConfiguredChannels, RenetChannelsExt,
+/// };
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let configured = ConfiguredChannels::new(channels);
+/// let server = RenetServer::new(ConnectionConfig {
+///     server_channels_config: channels.get_server_configs(),
+///     client_channels_config: channels.get_client_configs(),
+///     ..Default::default()
+/// });
+///
+/// app.insert_resource(server).insert_resource(configured);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfiguredChannels {
+    server: usize,
+    client: usize,
+}
+
+impl ConfiguredChannels {
+    /// Remembers the current numbers of server and client channels.
+    pub fn new(channels: &RepliconChannels) -> Self {
+        Self {
+            server: channels.server_channels().len(),
+            client: channels.client_channels().len(),
+        }
+    }
+
+    /// Returns recorded and current counts that differ.
+    #[cfg(any(debug_assertions, test))]
+    fn mismatches(&self, channels: &RepliconChannels) -> Vec<(&'static str, usize, usize)> {
+        let mut mismatches = Vec::new();
+        for (kind, configured, current) in [
+            ("server", self.server, channels.server_channels().len()),
+            ("client", self.client, channels.client_channels().len()),
+        ] {
+            if configured != current {
+                mismatches.push((kind, configured, current));
+            }
+        }
+
+        mismatches
+    }
+}
+
+/// Logs an error if channels were registered after configs for the transport were created.
+///
+/// Should run once the transport is inserted. Does nothing without [`ConfiguredChannels`].
+#[cfg(debug_assertions)]
+pub(crate) fn check_channels(
+    channels: Res<RepliconChannels>,
+    configured: Option<Res<ConfiguredChannels>>,
+) {
+    let Some(configured) = configured else {
+        return;
+    };
+
+    for (kind, configured, current) in configured.mismatches(&channels) {
+        error!(
+            "renet configs were created with {configured} {kind} channels, but `RepliconChannels` \
+            now has {current}; register all replicated components and events before creating the transport"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatch() {
+        let mut channels = RepliconChannels::default();
+        let configured = ConfiguredChannels::new(&channels);
+        let server_count = channels.server_channels().len();
+
+        channels.create_server_channel(ChannelKind::Ordered.into());
+
+        assert_eq!(
+            configured.mismatches(&channels),
+            [("server", server_count, server_count + 1)]
+        );
+    }
+
+    #[test]
+    fn match_counts() {
+        let channels = RepliconChannels::default();
+        let configured = ConfiguredChannels::new(&channels);
+
+        assert!(configured.mismatches(&channels).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::ChannelConfig;
+
+/// Channels that were closed at runtime to reclaim their memory.
+///
+/// Renet fixes channels when a connection is created, so a channel can't be removed mid-session.
+/// But a game may use some channels only during a specific phase, like a one-time asset transfer
+/// at the beginning of a match. Closing such a channel quiesces it:
+///
+/// 1. Messages over it are no longer passed to renet. Messages that Replicon produced, but that weren't
+///    forwarded yet, are discarded as well, including ones deferred by [`ChannelRateLimit`](crate::ChannelRateLimit).
+/// 2. Messages already passed to renet are still delivered, including ones held back by
+///    [`SendBackpressure`](crate::SendBackpressure) on the server.
+/// 3. Once renet frees all memory of the channel for every peer, which happens after the last message
+///    is sent for unreliable channels or acknowledged for reliable ones, the channel becomes
+///    [`ChannelState::Closed`]. It no longer takes memory and doesn't count in
+///    [`TotalMemoryBudget`](crate::TotalMemoryBudget).
+///
+/// A closed channel can be reopened with [`Self::reopen`] to reuse it in the next phase.
+///
+/// Applies to channels over which this side sends: server channels on the server and client channels
+/// on the client. Channel IDs are IDs of channels from
+/// [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels), the draining is tracked via
+/// [`ChannelRemap`](crate::ChannelRemap). Receiving is not affected, so the peer can close its own side
+/// independently.
+///
+/// Disabled by default, insert this resource to enable it.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{ChannelState, ClosedChannels, RenetChannelsExt};
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let server_configs = channels.get_server_configs();
+/// app.insert_resource(ClosedChannels::new(&server_configs));
+///
+/// // Later, when the asset transfer is finished.
+/// const ASSETS_CHANNEL: u8 = 2;
+/// let mut closed_channels = app.world_mut().resource_mut::<ClosedChannels>();
+/// closed_channels.close(ASSETS_CHANNEL);
+/// assert_eq!(closed_channels.state(ASSETS_CHANNEL), ChannelState::Draining);
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct ClosedChannels {
+    limits: HashMap<u8, usize>,
+    states: HashMap<u8, ChannelState>,
+}
+
+impl ClosedChannels {
+    /// Creates the resource for a peer created with the given channel configs.
+    ///
+    /// Configs are used to know the memory limits of each channel, so they must match the configs of channels
+    /// over which this side sends: [`ConnectionConfig::server_channels_config`](bevy_renet::renet::ConnectionConfig::server_channels_config)
+    /// on the server and [`ConnectionConfig::client_channels_config`](bevy_renet::renet::ConnectionConfig::client_channels_config)
+    /// on the client.
+    pub fn new(configs: &[ChannelConfig]) -> Self {
+        Self {
+            limits: configs
+                .iter()
+                .map(|config| (config.channel_id, config.max_memory_usage_bytes))
+                .collect(),
+            states: Default::default(),
+        }
+    }
+
+    /// Stops sending over the channel and starts draining it.
+    ///
+    /// Does nothing if the channel is already closing or closed.
+    pub fn close(&mut self, channel_id: impl Into<u8>) {
+        self.states
+            .entry(channel_id.into())
+            .or_insert(ChannelState::Draining);
+    }
+
+    /// Allows sending over the channel again.
+    pub fn reopen(&mut self, channel_id: impl Into<u8>) {
+        self.states.remove(&channel_id.into());
+    }
+
+    /// Returns the state of the channel.
+    pub fn state(&self, channel_id: impl Into<u8>) -> ChannelState {
+        self.states
+            .get(&channel_id.into())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if messages over the channel shouldn't be sent.
+    pub(crate) fn is_closed(&self, channel_id: u8) -> bool {
+        self.states.contains_key(&channel_id)
+    }
+
+    /// Marks draining channels as closed if `drained` returns `true` for them.
+    ///
+    /// Channel IDs are translated into wire IDs with `wire_id`. The closure receives the wire ID
+    /// and the memory limit of the renet channel.
+    pub(crate) fn update(
+        &mut self,
+        wire_id: impl Fn(u8) -> u8,
+        mut drained: impl FnMut(u8, usize) -> bool,
+    ) {
+        for (&channel_id, state) in &mut self.states {
+            if *state != ChannelState::Draining {
+                continue;
+            }
+
+            let wire_id = wire_id(channel_id);
+            let limit = self.limits.get(&wire_id).copied().unwrap_or_default();
+            if drained(wire_id, limit) {
+                debug!("channel {channel_id} drained");
+                *state = ChannelState::Closed;
+            }
+        }
+    }
+}
+
+/// State of a channel from [`ClosedChannels`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelState {
+    /// Messages are sent as usual.
+    #[default]
+    Open,
+
+    /// New messages are discarded and renet still delivers previously sent ones.
+    Draining,
+
+    /// All messages are delivered and the channel doesn't take memory anymore.
+    Closed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn states() {
+        let mut closed_channels = ClosedChannels::new(&[]);
+        assert_eq!(closed_channels.state(CHANNEL), ChannelState::Open);
+
+        closed_channels.close(CHANNEL);
+        assert_eq!(closed_channels.state(CHANNEL), ChannelState::Draining);
+        assert!(closed_channels.is_closed(CHANNEL));
+
+        closed_channels.update(|channel_id| channel_id, |_, _| false);
+        assert_eq!(closed_channels.state(CHANNEL), ChannelState::Draining);
+
+        closed_channels.update(|channel_id| channel_id, |_, _| true);
+        assert_eq!(closed_channels.state(CHANNEL), ChannelState::Closed);
+
+        closed_channels.close(CHANNEL);
+        assert_eq!(
+            closed_channels.state(CHANNEL),
+            ChannelState::Closed,
+            "closing again shouldn't restart draining"
+        );
+
+        closed_channels.reopen(CHANNEL);
+        assert_eq!(closed_channels.state(CHANNEL), ChannelState::Open);
+        assert!(!closed_channels.is_closed(CHANNEL));
+    }
+
+    const CHANNEL: u8 = 2;
+}
@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+/// Number of channels that Replicon reserves for replication at the beginning of both server and client channels.
+///
+/// Matches the number of [`ReplicationChannel`](bevy_replicon::core::channels::ReplicationChannel) variants.
+pub(crate) const REPLICATION_CHANNELS_COUNT: usize = 2;
+
+/// Separate defaults for replication and event channels.
+///
+/// Used by [`RenetChannelsExt::get_server_configs_with`](crate::RenetChannelsExt::get_server_configs_with)
+/// and [`RenetChannelsExt::get_client_configs_with`](crate::RenetChannelsExt::get_client_configs_with).
+///
+/// Replicon always creates [`ReplicationChannel::Updates`](bevy_replicon::core::channels::ReplicationChannel::Updates)
+/// and [`ReplicationChannel::Mutations`](bevy_replicon::core::channels::ReplicationChannel::Mutations) first, so the first 2 channels are treated as replication channels and all channels after them
+/// (created for events or via [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels) directly)
+/// as event channels.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{ChannelDefaults, ChannelPolicy, RenetChannelsExt};
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let policy = ChannelPolicy {
+///     replication: ChannelDefaults {
+///         max_bytes: Some(10 * 1024 * 1024),
+///         ..Default::default()
+///     },
+///     events: ChannelDefaults {
+///         resend_time: Some(Duration::from_millis(100)),
+///         ..Default::default()
+///     },
+/// };
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let server_configs = channels.get_server_configs_with(&policy);
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ChannelPolicy {
+    /// Defaults for channels used by Replicon for replication.
+    pub replication: ChannelDefaults,
+    /// Defaults for all other channels.
+    pub events: ChannelDefaults,
+}
+
+impl ChannelPolicy {
+    /// Returns defaults for the channel with the given index.
+    pub(crate) fn defaults(&self, channel_index: usize) -> ChannelDefaults {
+        if channel_index < REPLICATION_CHANNELS_COUNT {
+            self.replication
+        } else {
+            self.events
+        }
+    }
+}
+
+/// Channels from [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels) that have renet configs.
+///
+/// Used by [`RenetChannelsExt::get_server_configs_for`](crate::RenetChannelsExt::get_server_configs_for)
+/// and [`RenetChannelsExt::get_client_configs_for`](crate::RenetChannelsExt::get_client_configs_for)
+/// for apps that don't use some of the registered channels, like apps with replication, but without remote events.
+/// Channels are split the same way as in [`ChannelPolicy`].
+///
+/// Renet panics on access to channels missing from its configs, so insert the same group as a resource on both
+/// server and client. The forwarding systems will skip receiving over channels outside the group and discard
+/// messages that Replicon sends over them with an error.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelGroup {
+    /// All channels.
+    #[default]
+    All,
+    /// Only channels used by Replicon for replication.
+    Replication,
+    /// Only channels after the replication channels.
+    Events,
+}
+
+impl ChannelGroup {
+    /// Returns `true` if the channel with the given ID belongs to the group.
+    pub fn contains(self, channel_id: u8) -> bool {
+        let replication = (channel_id as usize) < REPLICATION_CHANNELS_COUNT;
+        match self {
+            ChannelGroup::All => true,
+            ChannelGroup::Replication => replication,
+            ChannelGroup::Events => !replication,
+        }
+    }
+
+    /// Returns IDs of channels from the group among the first `channels_count` channels.
+    #[cfg(any(feature = "client", feature = "server"))]
+    pub(crate) fn channel_ids(self, channels_count: usize) -> impl Iterator<Item = u8> {
+        (0..channels_count as u8).filter(move |&channel_id| self.contains(channel_id))
+    }
+}
+
+/// Channel settings from [`ChannelPolicy`].
+///
+/// [`None`] means that the value from the corresponding
+/// [`RepliconChannel`](bevy_replicon::prelude::RepliconChannel) will be used.
+///
+/// The fields have different precedence. [`RepliconChannel::resend_time`](bevy_replicon::prelude::RepliconChannel::resend_time)
+/// always has a value, so [`Self::resend_time`] can't act as a fallback and replaces it for every channel
+/// of the group. [`RepliconChannel::max_bytes`](bevy_replicon::prelude::RepliconChannel::max_bytes) is optional,
+/// so [`Self::max_bytes`] applies only to channels that leave it unset, and explicit per-channel limits are kept.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ChannelDefaults {
+    /// Overrides resend time for all reliable channels of the group, including ones with a custom
+    /// [`RepliconChannel::resend_time`](bevy_replicon::prelude::RepliconChannel::resend_time).
+    ///
+    /// Renet resends unacknowledged messages at this fixed interval without any backoff, and the
+    /// value can't be changed after the connection is created. Adding a second resend layer on top
+    /// of renet's reliability would only produce duplicates, so pick a value slightly above the
+    /// expected round-trip time instead. For lossy links prefer lower values, since each lost
+    /// packet delays delivery by at least this duration.
+    pub resend_time: Option<Duration>,
+
+    /// Maximum memory usage for channels that don't specify
+    /// [`RepliconChannel::max_bytes`](bevy_replicon::prelude::RepliconChannel::max_bytes).
+    ///
+    /// Explicit per-channel limits take priority over this value. It takes priority over [`RepliconChannels::default_max_bytes`](bevy_replicon::prelude::RepliconChannels::default_max_bytes).
+    pub max_bytes: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_renet::renet::SendType;
+    use bevy_replicon::{core::channels::ReplicationChannel, prelude::*};
+
+    use super::*;
+    use crate::RenetChannelsExt;
+
+    #[test]
+    fn defaults() {
+        let mut channels = RepliconChannels::default();
+        let event_channel = channels.create_server_channel(ChannelKind::Ordered.into());
+        let limited_channel = channels.create_server_channel(RepliconChannel {
+            kind: ChannelKind::Ordered,
+            resend_time: Default::default(),
+            max_bytes: Some(1),
+        });
+
+        let policy = ChannelPolicy {
+            replication: ChannelDefaults {
+                max_bytes: Some(2),
+                ..Default::default()
+            },
+            events: ChannelDefaults {
+                resend_time: Some(Duration::from_secs(1)),
+                max_bytes: Some(3),
+            },
+        };
+        let configs = channels.get_server_configs_with(&policy);
+
+        let updates_config = &configs[ReplicationChannel::Updates as usize];
+        assert_eq!(updates_config.max_memory_usage_bytes, 2);
+        let default_resend_time = channels.server_channels()[0].resend_time;
+        assert!(matches!(
+            updates_config.send_type,
+            SendType::ReliableOrdered { resend_time } if resend_time == default_resend_time
+        ));
+
+        let event_config = &configs[event_channel as usize];
+        assert_eq!(event_config.max_memory_usage_bytes, 3);
+        assert!(matches!(
+            event_config.send_type,
+            SendType::ReliableOrdered { resend_time } if resend_time == Duration::from_secs(1)
+        ));
+
+        let limited_config = &configs[limited_channel as usize];
+        assert_eq!(
+            limited_config.max_memory_usage_bytes, 1,
+            "explicit channel limit should take priority"
+        );
+        assert!(
+            matches!(
+                limited_config.send_type,
+                SendType::ReliableOrdered { resend_time } if resend_time == Duration::from_secs(1)
+            ),
+            "policy resend time should replace the channel's value"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let mut channels = RepliconChannels::default();
+        let event_channel = channels.create_server_channel(ChannelKind::Ordered.into());
+
+        let replication_ids: Vec<_> = channels
+            .get_server_configs_for(ChannelGroup::Replication)
+            .iter()
+            .map(|config| config.channel_id)
+            .collect();
+        assert_eq!(
+            replication_ids,
+            [
+                ReplicationChannel::Updates as u8,
+                ReplicationChannel::Mutations as u8
+            ]
+        );
+
+        let event_ids: Vec<_> = channels
+            .get_server_configs_for(ChannelGroup::Events)
+            .iter()
+            .map(|config| config.channel_id)
+            .collect();
+        assert_eq!(event_ids, [event_channel]);
+
+        let all_configs = channels.get_server_configs_for(ChannelGroup::All);
+        assert_eq!(all_configs.len(), channels.server_channels().len());
+    }
+}
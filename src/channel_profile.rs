@@ -0,0 +1,132 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use bevy_renet::renet::{ChannelConfig, SendType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Per-channel settings that can be loaded at runtime instead of being hardcoded.
+///
+/// Applied by [`RenetChannelsExt::get_server_configs_from`](crate::RenetChannelsExt::get_server_configs_from)
+/// and [`RenetChannelsExt::get_client_configs_from`](crate::RenetChannelsExt::get_client_configs_from).
+/// Each entry corresponds to the channel with the same index and must be provided for all channels,
+/// including replication channels, so a profile made for a different set of channels is rejected.
+///
+/// With the `serde` feature the profile can be deserialized from any format, like JSON or RON,
+/// to tune the network without recompiling.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ChannelProfile {
+    /// Settings for server channels.
+    pub server: Vec<ChannelSettings>,
+    /// Settings for client channels.
+    pub client: Vec<ChannelSettings>,
+}
+
+/// Channel settings from [`ChannelProfile`].
+///
+/// [`None`] means that the value from the corresponding
+/// [`RepliconChannel`](bevy_replicon::prelude::RepliconChannel) will be used.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ChannelSettings {
+    /// Resend time for reliable channels. Ignored for unreliable channels.
+    pub resend_time: Option<Duration>,
+    /// Maximum memory usage in bytes.
+    pub max_bytes: Option<usize>,
+}
+
+/// Error returned when [`ChannelProfile`] doesn't match the channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelCountMismatch {
+    /// Number of channels in [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels).
+    pub expected: usize,
+    /// Number of entries in the profile.
+    pub actual: usize,
+}
+
+impl Display for ChannelCountMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "profile contains {} channels, but {} are registered",
+            self.actual, self.expected
+        )
+    }
+}
+
+impl Error for ChannelCountMismatch {}
+
+/// Overrides configs with settings from the profile.
+pub(crate) fn apply(
+    configs: &mut [ChannelConfig],
+    settings: &[ChannelSettings],
+) -> Result<(), ChannelCountMismatch> {
+    if configs.len() != settings.len() {
+        return Err(ChannelCountMismatch {
+            expected: configs.len(),
+            actual: settings.len(),
+        });
+    }
+
+    for (config, settings) in configs.iter_mut().zip(settings) {
+        if let Some(max_bytes) = settings.max_bytes {
+            config.max_memory_usage_bytes = max_bytes;
+        }
+        if let Some(new_resend_time) = settings.resend_time {
+            match &mut config.send_type {
+                SendType::ReliableOrdered { resend_time }
+                | SendType::ReliableUnordered { resend_time } => *resend_time = new_resend_time,
+                SendType::Unreliable => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_replicon::prelude::*;
+
+    use super::*;
+    use crate::RenetChannelsExt;
+
+    #[test]
+    fn overrides() {
+        let channels = RepliconChannels::default();
+        let profile = ChannelProfile {
+            server: vec![
+                ChannelSettings {
+                    resend_time: Some(Duration::from_secs(1)),
+                    max_bytes: Some(1),
+                },
+                ChannelSettings::default(),
+            ],
+            client: Vec::new(),
+        };
+
+        let configs = channels.get_server_configs_from(&profile).unwrap();
+        assert_eq!(configs[0].max_memory_usage_bytes, 1);
+        assert!(matches!(
+            configs[0].send_type,
+            SendType::ReliableOrdered { resend_time } if resend_time == Duration::from_secs(1)
+        ));
+        assert_eq!(
+            configs[1].max_memory_usage_bytes,
+            channels.default_max_bytes
+        );
+
+        let error = channels.get_client_configs_from(&profile).unwrap_err();
+        assert_eq!(
+            error,
+            ChannelCountMismatch {
+                expected: 2,
+                actual: 0
+            }
+        );
+    }
+}
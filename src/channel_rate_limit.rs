@@ -0,0 +1,181 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+/// Caps the send rate of individual channels in bytes per second.
+///
+/// Applied to messages sent by Replicon on both server and client before they are passed to renet,
+/// separately for each client on the server. Each channel has a budget that refills at the configured rate
+/// and can accumulate up to 1 second of traffic for bursts. Once the budget is spent, messages over
+/// unreliable channels are dropped, while messages over reliable channels are deferred to the next frames
+/// in their original order. Useful to prevent a single channel, like chat, from flooding reliable memory.
+///
+/// Replicon replicates over its own channels, which come first in [`RepliconChannels`].
+/// Limiting [`ReplicationChannel::Updates`](bevy_replicon::core::channels::ReplicationChannel::Updates)
+/// delays all replication for the client, and limiting
+/// [`ReplicationChannel::Mutations`](bevy_replicon::core::channels::ReplicationChannel::Mutations) drops
+/// component changes until Replicon sends them again. So usually only event channels should be limited.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::ChannelRateLimit;
+///
+/// # let mut app = App::new();
+/// # let chat_channel = 2;
+/// app.insert_resource(ChannelRateLimit::default().with_channel(chat_channel, 1024));
+/// ```
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ChannelRateLimit {
+    limits: HashMap<u8, usize>,
+    budgets: HashMap<(ClientId, u8), f64>,
+    deferred: HashMap<(ClientId, u8), VecDeque<Bytes>>,
+}
+
+impl ChannelRateLimit {
+    /// Limits the channel to the given number of bytes per second.
+    #[must_use]
+    pub fn with_channel(mut self, channel_id: u8, bytes_per_second: usize) -> Self {
+        self.limits.insert(channel_id, bytes_per_second);
+        self
+    }
+
+    /// Returns the limit of the channel in bytes per second.
+    pub fn limit(&self, channel_id: u8) -> Option<usize> {
+        self.limits.get(&channel_id).copied()
+    }
+
+    /// Returns the number of messages deferred for the peer on the channel.
+    ///
+    /// On the client use [`ClientId::SERVER`] as the peer.
+    pub fn deferred_messages(&self, peer: ClientId, channel_id: u8) -> usize {
+        self.deferred
+            .get(&(peer, channel_id))
+            .map(|messages| messages.len())
+            .unwrap_or_default()
+    }
+
+    /// Refills budgets of all channels for the elapsed time.
+    pub(crate) fn refill(&mut self, delta: Duration) {
+        for (&(_, channel_id), budget) in &mut self.budgets {
+            let limit = self.limits.get(&channel_id).copied().unwrap_or_default() as f64;
+            *budget = (*budget + limit * delta.as_secs_f64()).min(limit);
+        }
+    }
+
+    /// Passes deferred messages to `send` as long as their channels have budget.
+    pub(crate) fn release(&mut self, mut send: impl FnMut(ClientId, u8, Bytes)) {
+        self.deferred.retain(|&(peer, channel_id), messages| {
+            let budget = self.budgets.entry((peer, channel_id)).or_default();
+            while *budget > 0.0 {
+                let Some(message) = messages.pop_front() else {
+                    break;
+                };
+                *budget -= message.len() as f64;
+                send(peer, channel_id, message);
+            }
+
+            !messages.is_empty()
+        });
+    }
+
+    /// Passes the message to `send` if the channel has budget.
+    ///
+    /// Otherwise defers it if `reliable` or drops it.
+    pub(crate) fn send(
+        &mut self,
+        peer: ClientId,
+        channel_id: u8,
+        reliable: bool,
+        message: Bytes,
+        send: impl FnOnce(Bytes),
+    ) {
+        let Some(&limit) = self.limits.get(&channel_id) else {
+            send(message);
+            return;
+        };
+
+        // Keep the order for reliable channels.
+        if let Some(messages) = self.deferred.get_mut(&(peer, channel_id)) {
+            messages.push_back(message);
+            return;
+        }
+
+        let budget = self
+            .budgets
+            .entry((peer, channel_id))
+            .or_insert(limit as f64);
+        if *budget > 0.0 {
+            *budget -= message.len() as f64;
+            send(message);
+        } else if reliable {
+            trace!("deferring message over channel {channel_id} for `{peer:?}` due to rate limit");
+            self.deferred
+                .insert((peer, channel_id), VecDeque::from([message]));
+        } else {
+            trace!("dropping message over channel {channel_id} for `{peer:?}` due to rate limit");
+        }
+    }
+
+    pub(crate) fn remove_peer(&mut self, peer: ClientId) {
+        self.budgets
+            .retain(|&(budget_peer, _), _| budget_peer != peer);
+        self.deferred
+            .retain(|&(deferred_peer, _), _| deferred_peer != peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defer_and_drop() {
+        let mut rate_limit = ChannelRateLimit::default()
+            .with_channel(RELIABLE, 100)
+            .with_channel(UNRELIABLE, 100);
+
+        let mut sent = Vec::new();
+        for channel_id in [RELIABLE, UNRELIABLE] {
+            for index in 0..2 {
+                rate_limit.send(
+                    PEER,
+                    channel_id,
+                    channel_id == RELIABLE,
+                    message(index),
+                    |message| sent.push((channel_id, message[0])),
+                );
+            }
+        }
+        assert_eq!(sent, [(RELIABLE, 0), (UNRELIABLE, 0)]);
+        assert_eq!(rate_limit.deferred_messages(PEER, RELIABLE), 1);
+
+        sent.clear();
+        rate_limit.refill(Duration::from_secs(1));
+        rate_limit.release(|_, channel_id, message| sent.push((channel_id, message[0])));
+        assert_eq!(sent, [(RELIABLE, 1)]);
+        assert_eq!(rate_limit.deferred_messages(PEER, RELIABLE), 0);
+    }
+
+    #[test]
+    fn unlimited() {
+        let mut rate_limit = ChannelRateLimit::default().with_channel(RELIABLE, 100);
+
+        let mut sent = 0;
+        for index in 0..10 {
+            rate_limit.send(PEER, UNRELIABLE, false, message(index), |_| sent += 1);
+        }
+        assert_eq!(sent, 10);
+    }
+
+    const PEER: ClientId = ClientId::SERVER;
+    const RELIABLE: u8 = 2;
+    const UNRELIABLE: u8 = 3;
+
+    fn message(index: u8) -> Bytes {
+        vec![index; 100].into()
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::{prelude::*, utils::HashMap};
+
+/// Maps IDs of channels from [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels)
+/// to IDs of renet channels used on the wire.
+///
+/// Applied by the forwarding systems on both directions. Channels without a mapping use the same ID.
+///
+/// Useful for gradual rollouts when a new version of the game or Replicon changes the order of channels.
+/// The new version can remap its channels to the layout of the old version, so old and new peers
+/// can talk to each other. Channel configs passed to [`ConnectionConfig`](bevy_renet::renet::ConnectionConfig)
+/// must follow the wire layout.
+///
+/// The crate doesn't negotiate the mapping, so both sides must agree on it in advance. Keep a compatibility
+/// matrix that describes the wire layout for each pair of versions you want to support, pick the mapping
+/// on the newer side based on the version of the peer (for example, from the netcode user data
+/// or by connecting to a server with a matching version) and insert it before creating the transport.
+/// Remapping only changes IDs, so messages must have the same format in both versions.
+///
+/// # Examples
+///
+/// Swap two server channels:
+///
+/// ```
+/// use bevy_replicon_renet::ChannelRemap;
+///
+/// let remap = ChannelRemap::default()
+///     .with_server_channel(2, 3)
+///     .with_server_channel(3, 2);
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ChannelRemap {
+    server: HashMap<u8, u8>,
+    client: HashMap<u8, u8>,
+}
+
+impl ChannelRemap {
+    /// Maps a local server channel to a wire channel.
+    #[must_use]
+    pub fn with_server_channel(mut self, local_id: u8, wire_id: u8) -> Self {
+        self.server.insert(local_id, wire_id);
+        self
+    }
+
+    /// Maps a local client channel to a wire channel.
+    #[must_use]
+    pub fn with_client_channel(mut self, local_id: u8, wire_id: u8) -> Self {
+        self.client.insert(local_id, wire_id);
+        self
+    }
+
+    /// Returns the wire ID for a local server channel.
+    pub fn server_wire_id(&self, local_id: u8) -> u8 {
+        self.server.get(&local_id).copied().unwrap_or(local_id)
+    }
+
+    /// Returns the wire ID for a local client channel.
+    pub fn client_wire_id(&self, local_id: u8) -> u8 {
+        self.client.get(&local_id).copied().unwrap_or(local_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping() {
+        let remap = ChannelRemap::default()
+            .with_server_channel(0, 1)
+            .with_client_channel(1, 0);
+
+        assert_eq!(remap.server_wire_id(0), 1);
+        assert_eq!(remap.server_wire_id(2), 2);
+        assert_eq!(remap.client_wire_id(1), 0);
+        assert_eq!(remap.client_wire_id(0), 0);
+    }
+}
@@ -0,0 +1,133 @@
+use std::mem;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::{ChannelConfig, SendType};
+use bevy_replicon::prelude::*;
+
+/// Configuration and statistics of a single channel.
+///
+/// Assembled by [`ServerChannelReport`](crate::ServerChannelReport) and
+/// [`ClientChannelReport`](crate::ClientChannelReport) for debug overlays.
+#[derive(Debug, Clone)]
+pub struct ChannelReport {
+    /// Channel ID in [`RepliconChannels`].
+    ///
+    /// Can differ from the ID used by renet if [`ChannelRemap`](crate::ChannelRemap) is configured.
+    pub channel_id: u8,
+
+    /// Whether the local side sends or receives over this channel.
+    pub direction: ChannelDirection,
+
+    /// Delivery guarantees of the channel.
+    pub send_type: SendType,
+
+    /// Maximum memory renet can use for the channel.
+    pub max_memory_usage_bytes: usize,
+
+    /// Memory currently used by renet for the channel.
+    ///
+    /// Renet tracks memory only for sending, so it's [`None`] for received channels.
+    pub used_bytes: Option<usize>,
+
+    /// Bytes forwarded over the channel between renet and Replicon during the last frame.
+    pub throughput: usize,
+}
+
+impl ChannelReport {
+    pub(crate) fn new(
+        config: ChannelConfig,
+        direction: ChannelDirection,
+        available_memory: Option<usize>,
+        throughput: usize,
+    ) -> Self {
+        Self {
+            channel_id: config.channel_id,
+            direction,
+            used_bytes: available_memory
+                .map(|available| config.max_memory_usage_bytes.saturating_sub(available)),
+            send_type: config.send_type,
+            max_memory_usage_bytes: config.max_memory_usage_bytes,
+            throughput,
+        }
+    }
+}
+
+/// Direction of a channel in [`ChannelReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelDirection {
+    /// The local side sends messages over the channel.
+    Send,
+    /// The local side receives messages over the channel.
+    Receive,
+}
+
+/// Bytes forwarded by the forwarding systems over each channel.
+///
+/// Values for the current frame are accumulated during the frame and moved into
+/// the last frame in [`First`], so readers always see a complete frame.
+#[derive(Resource, Default)]
+pub(crate) struct ChannelThroughput {
+    current: ThroughputCounts,
+    last: ThroughputCounts,
+}
+
+impl ChannelThroughput {
+    pub(crate) fn add(
+        &mut self,
+        peer: ClientId,
+        direction: ChannelDirection,
+        channel_id: u8,
+        bytes: usize,
+    ) {
+        *self
+            .current
+            .entry((peer, direction, channel_id))
+            .or_default() += bytes;
+    }
+
+    /// Returns bytes forwarded so far during the current frame.
+    #[cfg(feature = "test_support")]
+    pub(crate) fn current(
+        &self,
+        peer: ClientId,
+        direction: ChannelDirection,
+        channel_id: u8,
+    ) -> usize {
+        self.current
+            .get(&(peer, direction, channel_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn last(
+        &self,
+        peer: ClientId,
+        direction: ChannelDirection,
+        channel_id: u8,
+    ) -> usize {
+        self.last
+            .get(&(peer, direction, channel_id))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Bytes per peer, direction and channel.
+///
+/// On the client the peer is always [`ClientId::SERVER`].
+type ThroughputCounts = HashMap<(ClientId, ChannelDirection, u8), usize>;
+
+/// Initializes [`ChannelThroughput`] if it wasn't initialized by another plugin.
+pub(crate) fn init(app: &mut App) {
+    if app.world().contains_resource::<ChannelThroughput>() {
+        return;
+    }
+
+    app.init_resource::<ChannelThroughput>()
+        .add_systems(First, finish_frame);
+}
+
+fn finish_frame(mut throughput: ResMut<ChannelThroughput>) {
+    let current = mem::take(&mut throughput.current);
+    throughput.last = current;
+}
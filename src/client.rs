@@ -1,4 +1,35 @@
+pub(super) mod asset_stream;
+pub(super) mod assigned_id;
+pub(super) mod channel_close;
+pub(super) mod channel_rate_limit;
+pub(super) mod channel_report;
+pub(super) mod clock_sync;
+pub(super) mod control;
+pub(super) mod echo;
+#[cfg(feature = "window_focus")]
+pub(super) mod focus_pause;
+pub(super) mod half_open;
+pub(super) mod handshake;
+#[cfg(feature = "init_capture")]
+pub(super) mod init_capture;
+pub(super) mod kick;
+pub(super) mod manual_flush;
+pub(super) mod messaging;
+pub(super) mod network_info;
+pub(super) mod pending_messages;
+pub(super) mod redirect;
+pub(super) mod reliable_receive;
+#[cfg(feature = "renet_netcode")]
+pub(super) mod server_probe;
+pub(super) mod status_history;
+pub(super) mod timestamp;
+#[cfg(feature = "renet_netcode")]
+pub(super) mod transport_error;
+pub(super) mod unknown_channel;
+
 use bevy::prelude::*;
+#[cfg(feature = "diagnostics")]
+use bevy::utils::Instant;
 #[cfg(feature = "renet_netcode")]
 use bevy_renet::netcode::{NetcodeClientPlugin, NetcodeClientTransport};
 #[cfg(feature = "renet_steam")]
@@ -6,55 +37,190 @@ use bevy_renet::steam::SteamClientPlugin;
 use bevy_renet::{self, renet::RenetClient, RenetClientPlugin, RenetReceive, RenetSend};
 use bevy_replicon::prelude::*;
 
+use crate::{
+    channel_report::{ChannelDirection, ChannelThroughput},
+    net_mode::{self, NetModeSet},
+    network_activity,
+    transport::{self, ChannelSide},
+    ChannelGroup, ChannelRemap, ForwardSet, MessageTransforms, NetworkActivity, ReceiveLimit,
+};
+#[cfg(feature = "diagnostics")]
+use crate::{diagnostics::MessageCounts, ForwardingTimings};
+use asset_stream::AssetStreamPlugin;
+use assigned_id::AssignedClientId;
+use channel_close::ChannelClosePlugin;
+use channel_rate_limit::ChannelRateLimitPlugin;
+use clock_sync::ClockSyncPlugin;
+use control::ControlPlugin;
+use echo::EchoPlugin;
+#[cfg(feature = "window_focus")]
+use focus_pause::FocusPausePlugin;
+use half_open::{HalfOpenPlugin, LastReceived};
+use handshake::HandshakePlugin;
+#[cfg(feature = "init_capture")]
+use init_capture::InitCapturePlugin;
+use kick::KickPlugin;
+use manual_flush::ManualFlush;
+use network_info::ClientNetworkInfo;
+use pending_messages::PendingMessagesPlugin;
+use redirect::RedirectPlugin;
+use reliable_receive::ReliableReceivePlugin;
+#[cfg(feature = "renet_netcode")]
+use server_probe::ServerProbePlugin;
+use status_history::{StatusHistory, StatusTransition};
+use timestamp::TimestampWriter;
+#[cfg(feature = "renet_netcode")]
+use transport_error::TransportErrorPlugin;
+use unknown_channel::UnknownChannelPlugin;
+
 /// Adds renet as client messaging backend.
 ///
 /// Initializes [`RenetClientPlugin`] and systems that pass data between
 /// [`RenetClient`] and [`RepliconClient`].
+///
+/// On disconnect all messages that were received, but not yet processed by Replicon, are dropped,
+/// so nothing from the previous session is applied after reconnecting. To reconnect, insert a new
+/// [`RenetClient`] and transport instead of reusing the old ones.
 pub struct RepliconRenetClientPlugin;
 
 impl Plugin for RepliconRenetClientPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(RenetClientPlugin)
-            .configure_sets(PreUpdate, ClientSet::ReceivePackets.after(RenetReceive))
-            .configure_sets(PostUpdate, ClientSet::SendPackets.before(RenetSend))
-            .add_systems(
-                PreUpdate,
-                (
-                    Self::set_connecting.run_if(bevy_renet::client_connecting),
-                    Self::set_disconnected.run_if(bevy_renet::client_just_disconnected),
-                    Self::set_connected.run_if(bevy_renet::client_just_connected),
-                    Self::receive_packets.run_if(bevy_renet::client_connected),
-                )
-                    .chain()
-                    .in_set(ClientSet::ReceivePackets),
+        let receive_packets = Self::receive_packets
+            .in_set(ForwardSet::Receive)
+            .run_if(bevy_renet::client_connected);
+        let send_packets = Self::send_packets
+            .in_set(ForwardSet::Send)
+            .run_if(bevy_renet::client_connected)
+            .run_if(not(resource_exists::<ManualFlush>));
+        #[cfg(feature = "window_focus")]
+        let (receive_packets, send_packets) = (
+            receive_packets.run_if(FocusPausePlugin::forwarding_allowed),
+            send_packets.run_if(FocusPausePlugin::forwarding_allowed),
+        );
+
+        app.add_plugins((
+            RenetClientPlugin,
+            ReliableReceivePlugin,
+            HalfOpenPlugin,
+            ClockSyncPlugin,
+            AssetStreamPlugin,
+            PendingMessagesPlugin,
+            HandshakePlugin,
+            ChannelRateLimitPlugin,
+            ChannelClosePlugin,
+            UnknownChannelPlugin,
+            RedirectPlugin,
+            ControlPlugin,
+            KickPlugin,
+            EchoPlugin,
+        ))
+        .init_resource::<MessageTransforms>()
+        .init_resource::<ChannelRemap>()
+        .init_resource::<ChannelGroup>()
+        .init_resource::<ReceiveLimit>()
+        .init_resource::<StatusHistory>()
+        .init_resource::<AssignedClientId>()
+        .init_resource::<ClientNetworkInfo>()
+        .configure_sets(PreUpdate, ClientSet::ReceivePackets.after(RenetReceive))
+        .configure_sets(PreUpdate, NetModeSet.after(ClientSet::ReceivePackets))
+        .configure_sets(PostUpdate, ClientSet::SendPackets.before(RenetSend))
+        .configure_sets(
+            PreUpdate,
+            ForwardSet::Receive.in_set(ClientSet::ReceivePackets),
+        )
+        .configure_sets(PostUpdate, ForwardSet::Send.in_set(ClientSet::SendPackets))
+        .add_systems(
+            PreUpdate,
+            (
+                Self::set_connecting.run_if(bevy_renet::client_connecting),
+                Self::set_disconnected.run_if(bevy_renet::client_just_disconnected),
+                Self::set_connected.run_if(bevy_renet::client_just_connected),
+                Self::apply_assigned_id.run_if(bevy_renet::client_connected),
+                receive_packets,
             )
-            .add_systems(
-                PostUpdate,
-                Self::send_packets
-                    .in_set(ClientSet::SendPackets)
-                    .run_if(bevy_renet::client_connected),
-            );
+                .chain()
+                .in_set(ClientSet::ReceivePackets),
+        )
+        .add_systems(PostUpdate, send_packets);
+
+        net_mode::init(app);
+        network_activity::init(app);
+        crate::channel_report::init(app);
+
+        #[cfg(debug_assertions)]
+        app.add_systems(
+            PreUpdate,
+            crate::channel_check::check_channels
+                .run_if(resource_added::<RenetClient>)
+                .before(Self::receive_packets)
+                .in_set(ClientSet::ReceivePackets),
+        );
 
+        #[cfg(feature = "diagnostics")]
+        app.init_resource::<ForwardingTimings>()
+            .init_resource::<MessageCounts>();
+
+        #[cfg(feature = "window_focus")]
+        app.add_plugins(FocusPausePlugin);
+        #[cfg(feature = "init_capture")]
+        app.add_plugins(InitCapturePlugin);
         #[cfg(feature = "renet_netcode")]
-        app.add_plugins(NetcodeClientPlugin);
+        app.add_plugins((NetcodeClientPlugin, ServerProbePlugin, TransportErrorPlugin));
         #[cfg(feature = "renet_steam")]
         app.add_plugins(SteamClientPlugin);
     }
 }
 
 impl RepliconRenetClientPlugin {
-    fn set_disconnected(mut client: ResMut<RepliconClient>) {
+    fn set_disconnected(
+        time: Res<Time<Real>>,
+        renet_client: Option<Res<RenetClient>>,
+        mut client: ResMut<RepliconClient>,
+        mut history: ResMut<StatusHistory>,
+        mut assigned_id: ResMut<AssignedClientId>,
+        #[cfg(feature = "renet_netcode")] transport: Option<Res<NetcodeClientTransport>>,
+    ) {
         client.set_status(RepliconClientStatus::Disconnected);
+        assigned_id.clear();
+
+        let reason = renet_client.and_then(|client| client.disconnect_reason());
+        // Renet reports all transport disconnects the same way, so prefer the transport's reason.
+        #[cfg(feature = "renet_netcode")]
+        let transport_reason = transport
+            .and_then(|transport| transport.disconnect_reason())
+            .map(Into::into);
+        #[cfg(not(feature = "renet_netcode"))]
+        let transport_reason = None;
+
+        history.push(StatusTransition {
+            status: RepliconClientStatus::Disconnected,
+            time: time.elapsed(),
+            reason: reason.as_ref().map(|reason| reason.to_string()),
+            disconnect_reason: transport_reason.or(reason.as_ref().map(Into::into)),
+        });
     }
 
-    fn set_connecting(mut client: ResMut<RepliconClient>) {
+    fn set_connecting(
+        time: Res<Time<Real>>,
+        mut client: ResMut<RepliconClient>,
+        mut history: ResMut<StatusHistory>,
+    ) {
         if client.status() != RepliconClientStatus::Connecting {
             client.set_status(RepliconClientStatus::Connecting);
+            history.push(StatusTransition {
+                status: RepliconClientStatus::Connecting,
+                time: time.elapsed(),
+                reason: None,
+                disconnect_reason: None,
+            });
         }
     }
 
     fn set_connected(
+        time: Res<Time<Real>>,
         mut client: ResMut<RepliconClient>,
+        mut history: ResMut<StatusHistory>,
+        mut assigned_id: ResMut<AssignedClientId>,
         #[cfg(feature = "renet_netcode")] transport: Option<Res<NetcodeClientTransport>>,
     ) {
         // In renet only transport knows the ID.
@@ -64,35 +230,159 @@ impl RepliconRenetClientPlugin {
         #[cfg(not(feature = "renet_netcode"))]
         let client_id = None;
 
-        client.set_status(RepliconClientStatus::Connected { client_id });
+        assigned_id.connect(client_id);
+        let status = RepliconClientStatus::Connected { client_id };
+        client.set_status(status);
+        history.push(StatusTransition {
+            status,
+            time: time.elapsed(),
+            reason: None,
+            disconnect_reason: None,
+        });
     }
 
+    fn apply_assigned_id(
+        time: Res<Time<Real>>,
+        assigned_id: Res<AssignedClientId>,
+        mut client: ResMut<RepliconClient>,
+        mut history: ResMut<StatusHistory>,
+    ) {
+        let Some(client_id) = assigned_id.get() else {
+            return;
+        };
+
+        if client.id() != Some(client_id) {
+            let status = RepliconClientStatus::Connected {
+                client_id: Some(client_id),
+            };
+            client.set_status(status);
+            history.push(StatusTransition {
+                status,
+                time: time.elapsed(),
+                reason: None,
+                disconnect_reason: None,
+            });
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn receive_packets(
+        time: Res<Time<Real>>,
         channels: Res<RepliconChannels>,
+        transforms: Res<MessageTransforms>,
+        receive_limit: Res<ReceiveLimit>,
+        remap: Res<ChannelRemap>,
+        group: Res<ChannelGroup>,
         mut renet_client: ResMut<RenetClient>,
         mut replicon_client: ResMut<RepliconClient>,
+        mut network_info: ResMut<ClientNetworkInfo>,
+        mut activity: ResMut<NetworkActivity>,
+        mut throughput: ResMut<ChannelThroughput>,
+        mut last_received: ResMut<LastReceived>,
+        #[cfg(feature = "diagnostics")] mut timings: ResMut<ForwardingTimings>,
+        #[cfg(feature = "diagnostics")] mut counts: ResMut<MessageCounts>,
     ) {
-        for channel_id in 0..channels.server_channels().len() as u8 {
-            while let Some(message) = renet_client.receive_message(channel_id) {
+        #[cfg(feature = "diagnostics")]
+        let start = Instant::now();
+
+        let info = ClientNetworkInfo::from(renet_client.network_info());
+        replicon_client.set_rtt(info.rtt);
+        replicon_client.set_packet_loss(info.packet_loss);
+        replicon_client.set_sent_bps(info.bytes_sent_per_second);
+        replicon_client.set_received_bps(info.bytes_received_per_second);
+        network_info.set_if_neq(info);
+
+        transport::receive_messages(
+            &mut *renet_client,
+            (),
+            group.channel_ids(channels.server_channels().len()),
+            ChannelSide::Server,
+            &remap,
+            *receive_limit,
+            |channel_id, message| {
                 trace!(
                     "forwarding {} received bytes over channel {channel_id}",
                     message.len()
                 );
+                activity.received_this_frame = true;
+                last_received.record(time.elapsed());
+                throughput.add(
+                    ClientId::SERVER,
+                    ChannelDirection::Receive,
+                    channel_id,
+                    message.len(),
+                );
+                #[cfg(feature = "diagnostics")]
+                {
+                    counts.client_received += 1;
+                }
+                let Some(message) =
+                    transforms.apply_incoming(ClientId::SERVER, channel_id, message)
+                else {
+                    debug!("discarding message from server rejected by transform");
+                    return;
+                };
                 replicon_client.insert_received(channel_id, message);
-            }
-        }
+            },
+        );
+
+        #[cfg(feature = "diagnostics")]
+        timings.client_receive.push(start.elapsed());
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn send_packets(
+        transforms: Res<MessageTransforms>,
+        remap: Res<ChannelRemap>,
+        group: Res<ChannelGroup>,
         mut renet_client: ResMut<RenetClient>,
         mut replicon_client: ResMut<RepliconClient>,
+        mut activity: ResMut<NetworkActivity>,
+        mut throughput: ResMut<ChannelThroughput>,
+        timestamps: TimestampWriter,
+        #[cfg(feature = "diagnostics")] mut timings: ResMut<ForwardingTimings>,
+        #[cfg(feature = "diagnostics")] mut counts: ResMut<MessageCounts>,
     ) {
+        #[cfg(feature = "diagnostics")]
+        let start = Instant::now();
+
         for (channel_id, message) in replicon_client.drain_sent() {
+            if !group.contains(channel_id) {
+                error!(
+                    "discarding message over channel {channel_id} outside of `{:?}`",
+                    *group
+                );
+                continue;
+            }
+
+            activity.sent_this_frame = true;
+            #[cfg(feature = "diagnostics")]
+            {
+                counts.client_sent += 1;
+            }
+            let message = timestamps.write(channel_id, message);
+            let message = transforms.apply_outgoing(ClientId::SERVER, channel_id, message);
+            throughput.add(
+                ClientId::SERVER,
+                ChannelDirection::Send,
+                channel_id,
+                message.len(),
+            );
             trace!(
                 "forwarding {} sent bytes over channel {channel_id}",
                 message.len()
             );
-            renet_client.send_message(channel_id, message)
+            transport::send_message(
+                &mut *renet_client,
+                (),
+                ChannelSide::Client,
+                &remap,
+                channel_id,
+                message,
+            );
         }
+
+        #[cfg(feature = "diagnostics")]
+        timings.client_send.push(start.elapsed());
     }
 }
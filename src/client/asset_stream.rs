@@ -0,0 +1,132 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::{Bytes, RenetClient};
+use bevy_replicon::prelude::*;
+
+use crate::asset_stream::{AssetStreamChannel, Chunk};
+
+/// A blob sent by the server via [`AssetStreams`](crate::AssetStreams) that was completely received.
+#[derive(Event, Debug, Clone)]
+pub struct AssetReceived {
+    pub stream_id: u64,
+    pub data: Bytes,
+}
+
+/// An event that indicates that a part of a blob sent via [`AssetStreams`](crate::AssetStreams) was received.
+///
+/// Useful to display download progress.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetProgress {
+    pub stream_id: u64,
+    /// Number of received bytes.
+    pub received: usize,
+    /// Total size of the blob in bytes.
+    pub total: usize,
+}
+
+/// Maximum size of a blob in bytes that the client accepts via [`AssetStreamChannel`].
+///
+/// The size is announced by the server in each chunk. Streams that exceed the limit are discarded
+/// without allocating memory for them, so a misbehaving server can't exhaust the client's memory.
+/// Memory for accepted streams grows with received chunks instead of being reserved upfront.
+///
+/// By default, [`Self::DEFAULT`].
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct MaxAssetSize(pub usize);
+
+impl MaxAssetSize {
+    /// Default value, 64 MiB.
+    pub const DEFAULT: Self = Self(64 * 1024 * 1024);
+}
+
+impl Default for MaxAssetSize {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Blobs that are still being received.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct IncomingStreams(HashMap<u64, Vec<u8>>);
+
+pub(super) struct AssetStreamPlugin;
+
+impl Plugin for AssetStreamPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IncomingStreams>()
+            .init_resource::<MaxAssetSize>()
+            .add_event::<AssetReceived>()
+            .add_event::<AssetProgress>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::reset.run_if(bevy_renet::client_just_disconnected),
+                    Self::receive
+                        .run_if(bevy_renet::client_connected)
+                        .run_if(resource_exists::<AssetStreamChannel>),
+                )
+                    .chain()
+                    .in_set(ClientSet::ReceivePackets),
+            );
+    }
+}
+
+impl AssetStreamPlugin {
+    fn reset(mut streams: ResMut<IncomingStreams>) {
+        streams.clear();
+    }
+
+    fn receive(
+        channel: Res<AssetStreamChannel>,
+        max_size: Res<MaxAssetSize>,
+        mut streams: ResMut<IncomingStreams>,
+        mut renet_client: ResMut<RenetClient>,
+        mut progress_events: EventWriter<AssetProgress>,
+        mut received_events: EventWriter<AssetReceived>,
+    ) {
+        while let Some(message) = renet_client.receive_message(**channel) {
+            let Some(chunk) = Chunk::decode(message) else {
+                debug!("ignoring invalid stream chunk from server");
+                continue;
+            };
+
+            if chunk.total_len > **max_size {
+                debug!(
+                    "discarding stream {} with size {} exceeding the limit",
+                    chunk.stream_id, chunk.total_len
+                );
+                streams.remove(&chunk.stream_id);
+                continue;
+            }
+
+            let data = streams.entry(chunk.stream_id).or_default();
+            if chunk.offset != data.len() || chunk.offset + chunk.data.len() > chunk.total_len {
+                debug!(
+                    "discarding stream {} with unexpected chunk",
+                    chunk.stream_id
+                );
+                streams.remove(&chunk.stream_id);
+                continue;
+            }
+
+            data.extend_from_slice(&chunk.data);
+            progress_events.send(AssetProgress {
+                stream_id: chunk.stream_id,
+                received: data.len(),
+                total: chunk.total_len,
+            });
+
+            if data.len() == chunk.total_len {
+                let data = streams.remove(&chunk.stream_id).unwrap_or_default();
+                debug!(
+                    "received stream {} with {} bytes",
+                    chunk.stream_id,
+                    data.len()
+                );
+                received_events.send(AssetReceived {
+                    stream_id: chunk.stream_id,
+                    data: data.into(),
+                });
+            }
+        }
+    }
+}
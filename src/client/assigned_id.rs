@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+/// Client ID assigned by the application instead of the one from the transport.
+///
+/// By default [`RepliconClient::id`] returns the ID known to the transport (only netcode provides it).
+/// If the server or a login service assigns a canonical player ID, set it here and
+/// [`RepliconRenetClientPlugin`](crate::RepliconRenetClientPlugin) will use it as the client ID
+/// in [`ClientSet::ReceivePackets`] once the client is connected.
+///
+/// The ID can be set only once per session and only while the client is connected.
+/// Other attempts are ignored until the client reconnects, since disconnect clears the assigned ID.
+///
+/// This only changes the client's view of its own ID. The server still identifies the client
+/// by its transport ID, so the mapping between both IDs must be maintained by the server.
+/// The client trusts whatever ID it receives, so never use it for authorization on the server.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct AssignedClientId {
+    assigned_id: Option<ClientId>,
+    transport_id: Option<ClientId>,
+    connected: bool,
+}
+
+impl AssignedClientId {
+    /// Assigns the ID for the current session.
+    ///
+    /// Ignored with a warning if the client isn't connected or an ID was already assigned.
+    pub fn set(&mut self, client_id: ClientId) {
+        if !self.connected {
+            warn!("ignoring attempt to assign `{client_id:?}` to the client that isn't connected");
+            return;
+        }
+        if let Some(assigned_id) = self.assigned_id {
+            warn!("ignoring attempt to assign `{client_id:?}` to the client that already has `{assigned_id:?}`");
+            return;
+        }
+
+        debug!("assigning `{client_id:?}` to the client");
+        self.assigned_id = Some(client_id);
+    }
+
+    /// Returns the assigned ID.
+    pub fn get(&self) -> Option<ClientId> {
+        self.assigned_id
+    }
+
+    /// Returns the ID known to the transport for the current session.
+    ///
+    /// Unlike [`RepliconClient::id`], not replaced by the assigned ID.
+    /// Only netcode provides it.
+    pub fn transport_id(&self) -> Option<ClientId> {
+        self.transport_id
+    }
+
+    pub(super) fn connect(&mut self, transport_id: Option<ClientId>) {
+        self.transport_id = transport_id;
+        self.connected = true;
+    }
+
+    pub(super) fn clear(&mut self) {
+        *self = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set() {
+        let mut assigned_id = AssignedClientId::default();
+        assigned_id.set(ClientId::new(1));
+        assert_eq!(
+            assigned_id.get(),
+            None,
+            "shouldn't assign before connection"
+        );
+
+        assigned_id.connect(Some(ClientId::new(0)));
+        assigned_id.set(ClientId::new(1));
+        assigned_id.set(ClientId::new(2));
+        assert_eq!(assigned_id.get(), Some(ClientId::new(1)));
+        assert_eq!(assigned_id.transport_id(), Some(ClientId::new(0)));
+
+        assigned_id.clear();
+        assert_eq!(assigned_id.get(), None);
+        assert_eq!(assigned_id.transport_id(), None);
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use bevy_replicon::prelude::*;
+
+use super::channel_rate_limit::ChannelRateLimitPlugin;
+use crate::{transport::ChannelSide, ChannelRemap, ClosedChannels};
+
+pub(super) struct ChannelClosePlugin;
+
+impl Plugin for ChannelClosePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::update
+                .after(super::RepliconRenetClientPlugin::receive_packets)
+                .in_set(ClientSet::ReceivePackets)
+                .run_if(bevy_renet::client_connected)
+                .run_if(resource_exists::<ClosedChannels>),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::discard
+                .in_set(ClientSet::SendPackets)
+                .after(ChannelRateLimitPlugin::limit)
+                .before(super::RepliconRenetClientPlugin::send_packets)
+                .run_if(bevy_renet::client_connected)
+                .run_if(resource_exists::<ClosedChannels>),
+        );
+    }
+}
+
+impl ChannelClosePlugin {
+    /// Marks channels as closed once renet frees their memory.
+    fn update(
+        remap: Res<ChannelRemap>,
+        renet_client: Res<RenetClient>,
+        mut closed_channels: ResMut<ClosedChannels>,
+    ) {
+        closed_channels.update(
+            |channel_id| ChannelSide::Client.wire_id(&remap, channel_id),
+            |wire_id, limit| renet_client.channel_available_memory(wire_id) >= limit,
+        );
+    }
+
+    fn discard(closed_channels: Res<ClosedChannels>, mut client: ResMut<RepliconClient>) {
+        let messages: Vec<_> = client.drain_sent().collect();
+        for (channel_id, message) in messages {
+            if closed_channels.is_closed(channel_id) {
+                trace!("discarding message over closed channel {channel_id}");
+                continue;
+            }
+
+            client.send(channel_id, message);
+        }
+    }
+}
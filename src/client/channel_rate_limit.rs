@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+use crate::ChannelRateLimit;
+
+pub(super) struct ChannelRateLimitPlugin;
+
+impl Plugin for ChannelRateLimitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::reset
+                .in_set(ClientSet::ReceivePackets)
+                .run_if(bevy_renet::client_just_disconnected)
+                .run_if(resource_exists::<ChannelRateLimit>),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::limit
+                .in_set(ClientSet::SendPackets)
+                .before(super::RepliconRenetClientPlugin::send_packets)
+                .run_if(bevy_renet::client_connected)
+                .run_if(resource_exists::<ChannelRateLimit>),
+        );
+    }
+}
+
+impl ChannelRateLimitPlugin {
+    fn reset(mut rate_limit: ResMut<ChannelRateLimit>) {
+        rate_limit.remove_peer(ClientId::SERVER);
+    }
+
+    pub(super) fn limit(
+        time: Res<Time<Real>>,
+        channels: Res<RepliconChannels>,
+        mut rate_limit: ResMut<ChannelRateLimit>,
+        mut client: ResMut<RepliconClient>,
+    ) {
+        rate_limit.refill(time.delta());
+
+        let messages: Vec<_> = client.drain_sent().collect();
+        rate_limit.release(|_, channel_id, message| client.send(channel_id, message));
+        for (channel_id, message) in messages {
+            let reliable =
+                channels.client_channels()[channel_id as usize].kind != ChannelKind::Unreliable;
+            rate_limit.send(ClientId::SERVER, channel_id, reliable, message, |message| {
+                client.send(channel_id, message)
+            });
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::{ChannelConfig, RenetClient};
+use bevy_replicon::prelude::*;
+
+use crate::{
+    channel_report::{ChannelDirection, ChannelReport, ChannelThroughput},
+    ChannelGroup, ChannelRemap,
+};
+
+/// Assembles [`ChannelReport`] for each channel of the client.
+///
+/// Configs are derived from [`RepliconChannels`] the same way as in
+/// [`ServerChannelReport`](crate::ServerChannelReport), see its documentation for limitations.
+#[derive(SystemParam)]
+pub struct ClientChannelReport<'w> {
+    channels: Res<'w, RepliconChannels>,
+    remap: Res<'w, ChannelRemap>,
+    group: Res<'w, ChannelGroup>,
+    throughput: Res<'w, ChannelThroughput>,
+    renet_client: Option<Res<'w, RenetClient>>,
+}
+
+impl ClientChannelReport<'_> {
+    /// Returns reports for client channels followed by server channels.
+    ///
+    /// Memory usage is [`None`] if the client is not connected.
+    pub fn channel_report(&self) -> Vec<ChannelReport> {
+        let connected = self
+            .renet_client
+            .as_ref()
+            .filter(|renet_client| renet_client.is_connected());
+
+        let sent = self.configs(self.channels.client_channels()).map(|config| {
+            let available_memory = connected.map(|renet_client| {
+                renet_client.channel_available_memory(self.remap.client_wire_id(config.channel_id))
+            });
+            let throughput =
+                self.throughput
+                    .last(ClientId::SERVER, ChannelDirection::Send, config.channel_id);
+            ChannelReport::new(config, ChannelDirection::Send, available_memory, throughput)
+        });
+
+        let received = self.configs(self.channels.server_channels()).map(|config| {
+            let throughput = self.throughput.last(
+                ClientId::SERVER,
+                ChannelDirection::Receive,
+                config.channel_id,
+            );
+            ChannelReport::new(config, ChannelDirection::Receive, None, throughput)
+        });
+
+        sent.chain(received).collect()
+    }
+
+    fn configs(&self, channels: &[RepliconChannel]) -> impl Iterator<Item = ChannelConfig> {
+        let group = *self.group;
+        crate::create_configs(
+            channels,
+            self.channels.default_max_bytes,
+            &Default::default(),
+        )
+        .into_iter()
+        .filter(move |config| group.contains(config.channel_id))
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use bevy_replicon::prelude::*;
+
+use crate::clock_sync::{self, ClockSyncChannel};
+
+/// Estimated offset between the client and server clocks.
+///
+/// Updated only if [`ClockSyncChannel`] is present. The client periodically sends its time to the server
+/// and the server replies with its own time. Assuming the latency is symmetric, the server time at the
+/// moment of receiving the reply is the reported time plus half of the round-trip time. Each sample is
+/// blended into the current estimate using [`Self::smoothing`] to reduce jitter.
+///
+/// Clocks are [`Time<Real>`] on both sides, so the server time is the time since the server app started.
+///
+/// The estimate is usually accurate within a few milliseconds on stable connections, but it can be
+/// off by up to half of the round-trip time if the latency is asymmetric, which can't be detected
+/// by exchanging timestamps. Samples are exchanged over an unreliable channel, so lost packets just
+/// delay the next update.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ClockSync {
+    /// How often to send timestamp requests.
+    pub interval: Duration,
+
+    /// Weight of a new sample in the estimate, from 0 to 1.
+    ///
+    /// Higher values react to changes faster, but are more affected by jitter.
+    pub smoothing: f64,
+
+    offset: Option<f64>,
+    server_time: Option<Duration>,
+    last_request: Option<Duration>,
+}
+
+impl ClockSync {
+    /// Returns the estimated server time at the beginning of the current frame.
+    ///
+    /// Updated in [`ClientSet::ReceivePackets`]. Returns [`None`] until the first reply is received.
+    pub fn server_time_estimate(&self) -> Option<Duration> {
+        self.server_time
+    }
+
+    /// Returns the estimated difference between the server and client clocks in seconds.
+    ///
+    /// Positive if the server clock is ahead.
+    pub fn offset(&self) -> Option<f64> {
+        self.offset
+    }
+
+    /// Blends a new sample into the estimate.
+    fn add_sample(&mut self, sent: Duration, server_time: Duration, received: Duration) {
+        let rtt = received.saturating_sub(sent);
+        let sample = (server_time + rtt / 2).as_secs_f64() - received.as_secs_f64();
+        let offset = match self.offset {
+            Some(offset) => offset + (sample - offset) * self.smoothing,
+            None => sample,
+        };
+        trace!("updating clock offset to {offset} with RTT {rtt:?}");
+        self.offset = Some(offset);
+    }
+
+    fn update_estimate(&mut self, now: Duration) {
+        self.server_time = self
+            .offset
+            .map(|offset| Duration::from_secs_f64((now.as_secs_f64() + offset).max(0.0)));
+    }
+
+    fn reset(&mut self) {
+        self.offset = None;
+        self.server_time = None;
+        self.last_request = None;
+    }
+}
+
+impl Default for ClockSync {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            smoothing: 0.1,
+            offset: None,
+            server_time: None,
+            last_request: None,
+        }
+    }
+}
+
+pub(super) struct ClockSyncPlugin;
+
+impl Plugin for ClockSyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClockSync>().add_systems(
+            PreUpdate,
+            (
+                Self::reset.run_if(bevy_renet::client_just_disconnected),
+                Self::exchange
+                    .run_if(bevy_renet::client_connected)
+                    .run_if(resource_exists::<ClockSyncChannel>),
+            )
+                .chain()
+                .in_set(ClientSet::ReceivePackets),
+        );
+    }
+}
+
+impl ClockSyncPlugin {
+    fn reset(mut clock_sync: ResMut<ClockSync>) {
+        clock_sync.reset();
+    }
+
+    fn exchange(
+        time: Res<Time<Real>>,
+        channel: Res<ClockSyncChannel>,
+        mut clock_sync: ResMut<ClockSync>,
+        mut renet_client: ResMut<RenetClient>,
+    ) {
+        let now = time.elapsed();
+        while let Some(message) = renet_client.receive_message(**channel) {
+            let Some((sent, server_time)) = clock_sync::decode_reply(&message) else {
+                debug!("ignoring invalid timestamp reply from server");
+                continue;
+            };
+
+            clock_sync.add_sample(sent, server_time, now);
+        }
+        clock_sync.update_estimate(now);
+
+        if clock_sync
+            .last_request
+            .is_none_or(|last_request| now - last_request >= clock_sync.interval)
+        {
+            renet_client.send_message(**channel, clock_sync::encode_time(now).to_vec());
+            clock_sync.last_request = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset() {
+        let mut clock_sync = ClockSync::default();
+        clock_sync.add_sample(
+            Duration::from_secs(10),
+            Duration::from_secs(20),
+            Duration::from_secs(12),
+        );
+        assert_eq!(clock_sync.offset(), Some(9.0));
+
+        clock_sync.update_estimate(Duration::from_secs(13));
+        assert_eq!(
+            clock_sync.server_time_estimate(),
+            Some(Duration::from_secs(22))
+        );
+
+        clock_sync.smoothing = 0.5;
+        clock_sync.add_sample(
+            Duration::from_secs(20),
+            Duration::from_secs(30),
+            Duration::from_secs(20),
+        );
+        assert_eq!(clock_sync.offset(), Some(9.5));
+    }
+}
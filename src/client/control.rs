@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, RenetClient};
+use bevy_replicon::prelude::*;
+
+use crate::control::{self, ControlChannel, ControlKind};
+
+pub(super) struct ControlPlugin;
+
+impl Plugin for ControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControlMessages>().add_systems(
+            PreUpdate,
+            Self::receive
+                .after(super::RepliconRenetClientPlugin::receive_packets)
+                .in_set(ClientSet::ReceivePackets)
+                .run_if(bevy_renet::client_connected)
+                .run_if(resource_exists::<ControlChannel>),
+        );
+    }
+}
+
+impl ControlPlugin {
+    /// Dispatches messages from [`ControlChannel`] by their kinds.
+    ///
+    /// Messages that were not taken by features during the previous frame are dropped.
+    pub(super) fn receive(
+        channel: Res<ControlChannel>,
+        mut messages: ResMut<ControlMessages>,
+        mut renet_client: ResMut<RenetClient>,
+    ) {
+        messages.0.clear();
+        while let Some(message) = renet_client.receive_message(**channel) {
+            match control::decode(message) {
+                Ok((kind, payload)) => {
+                    trace!("received control message `{kind:?}` from server");
+                    messages.0.push((kind, payload));
+                }
+                Err(kind) => {
+                    debug!("ignoring control message of unknown kind `{kind:?}` from server")
+                }
+            }
+        }
+    }
+}
+
+/// Messages received over [`ControlChannel`] during this frame.
+///
+/// Features read them after [`ControlPlugin::receive`].
+#[derive(Resource, Default)]
+pub(super) struct ControlMessages(Vec<(ControlKind, Bytes)>);
+
+impl ControlMessages {
+    /// Removes and returns payloads of the given kind.
+    pub(super) fn take(&mut self, kind: ControlKind) -> Vec<Bytes> {
+        let mut payloads = Vec::new();
+        self.0.retain(|(message_kind, payload)| {
+            if *message_kind != kind {
+                return true;
+            }
+
+            payloads.push(payload.clone());
+            false
+        });
+        payloads
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::{core::replicon_tick::RepliconTick, prelude::*};
+
+use super::control::{ControlMessages, ControlPlugin};
+use crate::{control::ControlKind, echo};
+
+/// An event that indicates that the server echoed a message sent by this client.
+///
+/// Emitted for messages over channels from [`EchoClientEvents`](crate::EchoClientEvents) after receiving
+/// them over [`ControlChannel`](crate::ControlChannel). Echoes arrive in the order the server received
+/// the messages. Use them to reconcile prediction, like dropping inputs acknowledged by the server.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct EventEchoed {
+    /// Client channel over which the message was sent.
+    pub channel_id: u8,
+
+    /// Server tick with the effects of the message.
+    ///
+    /// Compare it with [`ServerUpdateTick`](bevy_replicon::client::ServerUpdateTick) to know
+    /// when the replicated state includes the message.
+    pub tick: RepliconTick,
+
+    /// The message as serialized by Replicon.
+    pub message: Bytes,
+}
+
+pub(super) struct EchoPlugin;
+
+impl Plugin for EchoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EventEchoed>().add_systems(
+            PreUpdate,
+            Self::receive
+                .after(ControlPlugin::receive)
+                .in_set(ClientSet::ReceivePackets),
+        );
+    }
+}
+
+impl EchoPlugin {
+    fn receive(mut messages: ResMut<ControlMessages>, mut echo_events: EventWriter<EventEchoed>) {
+        for payload in messages.take(ControlKind::Echo) {
+            let Some((channel_id, tick, message)) = echo::decode(payload) else {
+                debug!("ignoring invalid echo from server");
+                continue;
+            };
+
+            trace!("received echo over channel {channel_id} for tick {tick:?}");
+            echo_events.send(EventEchoed {
+                channel_id,
+                tick,
+                message,
+            });
+        }
+    }
+}
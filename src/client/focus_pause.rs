@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::Window};
+use bevy_replicon::prelude::*;
+
+/// Throttles forwarding of client messages while no window is focused.
+///
+/// Useful to save power on desktop clients running in the background. While paused, messages are passed
+/// between renet and Replicon only once per [`Self::flush_interval`], so Replicon processes received data in
+/// batches and messages sent by the client accumulate in between. Renet keeps the connection alive and
+/// acknowledges packets as usual. Received messages are still drained regularly, so choose an interval that
+/// doesn't exceed the channel budgets, since renet disconnects when a reliable channel runs out of memory.
+///
+/// [`ReplicationPaused`] and [`ReplicationResumed`] are emitted on transitions. Forwarding also resumes
+/// if this resource is removed. Apps without windows are never paused.
+///
+/// Disabled by default, insert this resource to enable it. Some games, like the examples in this repository,
+/// explicitly keep updating in the background and shouldn't use it.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PauseWhenUnfocused {
+    /// How often messages are forwarded while paused.
+    ///
+    /// By default set to 1 second.
+    pub flush_interval: Duration,
+}
+
+impl Default for PauseWhenUnfocused {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// An event that indicates that forwarding was throttled by [`PauseWhenUnfocused`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicationPaused;
+
+/// An event that indicates that forwarding was resumed after [`ReplicationPaused`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicationResumed;
+
+pub(super) struct FocusPausePlugin;
+
+impl Plugin for FocusPausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FocusState>()
+            .add_event::<ReplicationPaused>()
+            .add_event::<ReplicationResumed>()
+            .add_systems(
+                PreUpdate,
+                Self::update_state.before(ClientSet::ReceivePackets),
+            );
+    }
+}
+
+impl FocusPausePlugin {
+    /// Returns `true` if messages should be forwarded in this frame.
+    pub(super) fn forwarding_allowed(state: Res<FocusState>) -> bool {
+        state.forward
+    }
+
+    fn update_state(
+        time: Res<Time<Real>>,
+        pause: Option<Res<PauseWhenUnfocused>>,
+        windows: Query<&Window>,
+        mut state: ResMut<FocusState>,
+        mut paused_events: EventWriter<ReplicationPaused>,
+        mut resumed_events: EventWriter<ReplicationResumed>,
+    ) {
+        let now = time.elapsed();
+        let unfocused = !windows.is_empty() && windows.iter().all(|window| !window.focused);
+        let paused = pause.is_some() && unfocused;
+        if paused != state.paused {
+            state.paused = paused;
+            if paused {
+                debug!("pausing replication due to lost focus");
+                state.last_forward = now;
+                paused_events.send(ReplicationPaused);
+            } else {
+                debug!("resuming replication");
+                resumed_events.send(ReplicationResumed);
+            }
+        }
+
+        state.forward = match pause {
+            Some(pause) if paused => {
+                let elapsed = now.saturating_sub(state.last_forward);
+                if elapsed >= pause.flush_interval {
+                    state.last_forward = now;
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => true,
+        };
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct FocusState {
+    paused: bool,
+    forward: bool,
+    last_forward: Duration,
+}
+
+impl Default for FocusState {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            forward: true,
+            last_forward: Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_resume() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, FocusPausePlugin))
+            .insert_resource(PauseWhenUnfocused {
+                flush_interval: Duration::MAX,
+            });
+        let window = app
+            .world_mut()
+            .spawn(Window {
+                focused: false,
+                ..Default::default()
+            })
+            .id();
+
+        app.update();
+
+        assert!(!app.world().resource::<FocusState>().forward);
+        let paused_events = app.world().resource::<Events<ReplicationPaused>>();
+        assert_eq!(paused_events.len(), 1);
+
+        app.world_mut().get_mut::<Window>(window).unwrap().focused = true;
+        app.update();
+
+        assert!(app.world().resource::<FocusState>().forward);
+        let resumed_events = app.world().resource::<Events<ReplicationResumed>>();
+        assert_eq!(resumed_events.len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use bevy_replicon::prelude::*;
+
+/// Enables detection of half-open connections on the client.
+///
+/// The connection is considered half-open if [`RenetClient`] is still connected,
+/// but nothing was received from the server for longer than [`Self::timeout`].
+/// This may happen when the server already timed out the client, but packets from the server
+/// are lost (for example, due to a NAT failure). In this case [`SuspectedHalfOpen`] is emitted once.
+/// If [`Self::disconnect_after`] is set, the client will also be disconnected
+/// if the silence continues for this duration after the event.
+///
+/// Activity is tracked by the time of the last message forwarded from renet to Replicon,
+/// so renet's keep-alive packets and acknowledgements don't count. If your game doesn't send
+/// messages from the server regularly, choose a timeout longer than the expected idle period
+/// or send a periodic server event.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HalfOpenDetection {
+    /// Duration of silence after which [`SuspectedHalfOpen`] is emitted.
+    pub timeout: Duration,
+
+    /// Duration of additional silence after [`SuspectedHalfOpen`] after which the client disconnects.
+    pub disconnect_after: Option<Duration>,
+}
+
+impl HalfOpenDetection {
+    /// Creates detection with the given timeout and without automatic disconnection.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            disconnect_after: None,
+        }
+    }
+
+    /// Disconnects the client if the silence continues for the given duration after the event.
+    #[must_use]
+    pub fn with_disconnect_after(mut self, grace_period: Duration) -> Self {
+        self.disconnect_after = Some(grace_period);
+        self
+    }
+}
+
+/// An event that indicates that the connection is likely half-open.
+///
+/// See [`HalfOpenDetection`] for details.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuspectedHalfOpen {
+    /// Time since anything was received from the server.
+    pub silence: Duration,
+}
+
+/// Time of the last message from the server.
+///
+/// Stores [`Time<Real>::elapsed`] at the moment a message was received.
+#[derive(Resource, Default, Debug)]
+pub(super) struct LastReceived(Option<Duration>);
+
+impl LastReceived {
+    pub(super) fn record(&mut self, now: Duration) {
+        self.0 = Some(now);
+    }
+}
+
+pub(super) struct HalfOpenPlugin;
+
+impl Plugin for HalfOpenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SuspectedHalfOpen>()
+            .init_resource::<LastReceived>()
+            .add_systems(
+                PreUpdate,
+                Self::detect
+                    .after(ClientSet::ReceivePackets)
+                    .run_if(resource_exists::<RenetClient>)
+                    .run_if(resource_exists::<HalfOpenDetection>),
+            );
+    }
+}
+
+impl HalfOpenPlugin {
+    fn detect(
+        mut state: Local<SilenceState>,
+        time: Res<Time<Real>>,
+        detection: Res<HalfOpenDetection>,
+        last_received: Res<LastReceived>,
+        mut renet_client: ResMut<RenetClient>,
+        mut half_open_events: EventWriter<SuspectedHalfOpen>,
+    ) {
+        if !renet_client.is_connected() {
+            *state = SilenceState::Unknown;
+            return;
+        }
+
+        let now = time.elapsed();
+        // May be stale from a previous connection, but it's older than the reference
+        // time recorded on connection, so it will be ignored.
+        let received = last_received.0.unwrap_or_default();
+        match *state {
+            SilenceState::Unknown => *state = SilenceState::Receiving { last_received: now },
+            SilenceState::Receiving { last_received } => {
+                let last_received = last_received.max(received);
+                let silence = now.saturating_sub(last_received);
+                if silence > detection.timeout {
+                    warn!("nothing received from the server for {silence:?}, connection may be half-open");
+                    half_open_events.send(SuspectedHalfOpen { silence });
+                    *state = SilenceState::Suspected { since: now };
+                } else {
+                    *state = SilenceState::Receiving { last_received };
+                }
+            }
+            SilenceState::Suspected { since } => {
+                if received > since {
+                    *state = SilenceState::Receiving {
+                        last_received: received,
+                    };
+                } else if let Some(disconnect_after) = detection.disconnect_after {
+                    if now.saturating_sub(since) > disconnect_after {
+                        warn!("disconnecting from the server due to half-open connection");
+                        renet_client.disconnect();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+enum SilenceState {
+    /// Detection just started or the client isn't connected,
+    /// silence will be counted from the next connected frame.
+    #[default]
+    Unknown,
+    Receiving {
+        last_received: Duration,
+    },
+    Suspected {
+        since: Duration,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_renet::renet::ConnectionConfig;
+
+    use super::*;
+
+    #[test]
+    fn disconnect() {
+        let mut app = create_app();
+
+        advance(&mut app, Duration::from_millis(500));
+        let now = app.world().resource::<Time<Real>>().elapsed();
+        app.world_mut().resource_mut::<LastReceived>().record(now);
+        app.update();
+        assert!(app
+            .world()
+            .resource::<Events<SuspectedHalfOpen>>()
+            .is_empty());
+
+        advance(&mut app, Duration::from_millis(1200));
+        let mut half_open_events = app.world_mut().resource_mut::<Events<SuspectedHalfOpen>>();
+        assert_eq!(
+            half_open_events.drain().collect::<Vec<_>>(),
+            [SuspectedHalfOpen {
+                silence: Duration::from_millis(1200)
+            }],
+            "silence should be counted from the last message"
+        );
+        assert!(app.world().resource::<RenetClient>().is_connected());
+
+        advance(&mut app, Duration::from_millis(1200));
+        assert!(app
+            .world()
+            .resource::<Events<SuspectedHalfOpen>>()
+            .is_empty());
+        assert!(app.world().resource::<RenetClient>().is_disconnected());
+    }
+
+    #[test]
+    fn recovery() {
+        let mut app = create_app();
+
+        advance(&mut app, Duration::from_millis(1200));
+        assert_eq!(
+            app.world().resource::<Events<SuspectedHalfOpen>>().len(),
+            1,
+            "silence should be counted from the connection"
+        );
+
+        advance(&mut app, Duration::from_millis(500));
+        let now = app.world().resource::<Time<Real>>().elapsed();
+        app.world_mut().resource_mut::<LastReceived>().record(now);
+        app.update();
+
+        advance(&mut app, Duration::from_millis(800));
+        assert!(app.world().resource::<RenetClient>().is_connected());
+    }
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        let mut renet_client = RenetClient::new(ConnectionConfig::default());
+        renet_client.set_connected();
+        let mut time = Time::<Real>::default();
+        // The first update only records the start instant.
+        time.update_with_duration(Duration::ZERO);
+        app.add_plugins(HalfOpenPlugin)
+            .insert_resource(time)
+            .insert_resource(renet_client)
+            .insert_resource(
+                HalfOpenDetection::new(Duration::from_secs(1))
+                    .with_disconnect_after(Duration::from_secs(1)),
+            );
+
+        app.update();
+
+        app
+    }
+
+    fn advance(app: &mut App, duration: Duration) {
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(duration);
+        app.update();
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, RenetClient};
+use bevy_replicon::prelude::*;
+
+use super::control::{ControlMessages, ControlPlugin};
+use crate::{
+    control::{self, ControlChannel, ControlKind},
+    handshake::{HandshakeExtensions, HandshakeMetadata},
+};
+
+/// Metadata received from the server during the handshake enabled by [`HandshakeMetadata`].
+///
+/// Cleared on disconnect.
+#[derive(Resource, Default, Debug)]
+pub struct ServerMetadata(Option<Bytes>);
+
+impl ServerMetadata {
+    /// Returns the metadata of the server if the handshake is completed.
+    pub fn get(&self) -> Option<&Bytes> {
+        self.0.as_ref()
+    }
+}
+
+pub(super) struct HandshakePlugin;
+
+impl Plugin for HandshakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HandshakeExtensions>()
+            .init_resource::<ServerMetadata>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::reset.run_if(bevy_renet::client_just_disconnected),
+                    Self::send.run_if(bevy_renet::client_just_connected),
+                    Self::receive
+                        .after(ControlPlugin::receive)
+                        .run_if(bevy_renet::client_connected),
+                )
+                    .chain()
+                    .in_set(ClientSet::ReceivePackets)
+                    .run_if(resource_exists::<ControlChannel>)
+                    .run_if(resource_exists::<HandshakeMetadata>),
+            );
+    }
+}
+
+impl HandshakePlugin {
+    fn reset(mut server_metadata: ResMut<ServerMetadata>) {
+        server_metadata.0 = None;
+    }
+
+    fn send(
+        channel: Res<ControlChannel>,
+        metadata: Res<HandshakeMetadata>,
+        extensions: Res<HandshakeExtensions>,
+        mut renet_client: ResMut<RenetClient>,
+    ) {
+        debug!("sending {} bytes of metadata", metadata.len());
+        let message = control::encode(ControlKind::Handshake, &extensions.write(&metadata));
+        renet_client.send_message(**channel, message);
+    }
+
+    fn receive(
+        extensions: Res<HandshakeExtensions>,
+        mut messages: ResMut<ControlMessages>,
+        mut server_metadata: ResMut<ServerMetadata>,
+        mut renet_client: ResMut<RenetClient>,
+    ) {
+        for message in messages.take(ControlKind::Handshake) {
+            if server_metadata.0.is_some() {
+                debug!("ignoring repeated metadata from server");
+                continue;
+            }
+
+            debug!("received {} bytes of metadata from server", message.len());
+            let Some(message) = extensions.read(ClientId::SERVER, message) else {
+                warn!("disconnecting due to invalid handshake from server");
+                renet_client.disconnect();
+                break;
+            };
+
+            server_metadata.0 = Some(message);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy_replicon::{core::channels::ReplicationChannel, prelude::*};
+
+use crate::InitMessageCaptured;
+
+pub(super) struct InitCapturePlugin;
+
+impl Plugin for InitCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<InitMessageCaptured>()
+            .init_resource::<InitCaptured>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::reset.run_if(bevy_renet::client_just_disconnected),
+                    Self::capture
+                        .after(super::RepliconRenetClientPlugin::receive_packets)
+                        .run_if(bevy_renet::client_connected)
+                        .run_if(|captured: Res<InitCaptured>| !captured.0),
+                )
+                    .in_set(ClientSet::ReceivePackets),
+            );
+    }
+}
+
+impl InitCapturePlugin {
+    fn reset(mut captured: ResMut<InitCaptured>) {
+        captured.0 = false;
+    }
+
+    fn capture(
+        mut captured: ResMut<InitCaptured>,
+        mut client: ResMut<RepliconClient>,
+        mut capture_events: EventWriter<InitMessageCaptured>,
+    ) {
+        let messages: Vec<_> = client.receive(ReplicationChannel::Updates).collect();
+        if let Some(message) = messages.first() {
+            capture_events.send(InitMessageCaptured::new(ClientId::SERVER, message));
+            captured.0 = true;
+        }
+
+        for message in messages {
+            client.insert_received(ReplicationChannel::Updates, message);
+        }
+    }
+}
+
+/// Whether the init message of the current connection was already captured.
+#[derive(Resource, Default)]
+struct InitCaptured(bool);
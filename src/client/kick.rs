@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+use super::control::{ControlMessages, ControlPlugin};
+use crate::control::ControlKind;
+
+/// An event that indicates that the server kicked the client with a reason.
+///
+/// Emitted after receiving the reason from [`KickClient`](crate::KickClient) over [`ControlChannel`](crate::ControlChannel).
+/// The server closes the connection shortly after, so the client will be disconnected with
+/// [`RepliconDisconnectReason::Kicked`](crate::RepliconDisconnectReason::Kicked). Store the reason
+/// to show it to the player, like "You were kicked: reason".
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct ClientKicked {
+    /// Game-specific reason sent by the server.
+    pub reason: Bytes,
+}
+
+pub(super) struct KickPlugin;
+
+impl Plugin for KickPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ClientKicked>().add_systems(
+            PreUpdate,
+            Self::receive
+                .after(ControlPlugin::receive)
+                .in_set(ClientSet::ReceivePackets),
+        );
+    }
+}
+
+impl KickPlugin {
+    fn receive(mut messages: ResMut<ControlMessages>, mut kick_events: EventWriter<ClientKicked>) {
+        for reason in messages.take(ControlKind::Kick) {
+            debug!("received kick reason from server");
+            kick_events.send(ClientKicked { reason });
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+
+/// Disables automatic forwarding of client messages to renet.
+///
+/// By default messages sent via [`RepliconClient`](bevy_replicon::prelude::RepliconClient) are passed to
+/// [`RenetClient`] every frame in [`ClientSet::SendPackets`](bevy_replicon::prelude::ClientSet::SendPackets).
+/// While this resource is present, messages accumulate until [`flush_client_messages`] is called,
+/// which gives precise control over input timing, for example, to flush inputs right after each fixed
+/// simulation step.
+///
+/// Flushing only passes messages to renet, the packets are still sent by the transport in [`PostUpdate`].
+/// Accumulated messages are subject to channel budgets when flushed, so flush at least once per frame
+/// on reliable channels to avoid exceeding `max_memory_usage_bytes`, which makes renet disconnect.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct ManualFlush;
+
+/// Passes messages sent via [`RepliconClient`](bevy_replicon::prelude::RepliconClient) to [`RenetClient`].
+///
+/// Does nothing if the client is not connected. Intended for use with [`ManualFlush`],
+/// can be added as a system to any schedule or queued as a command.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::{flush_client_messages, ManualFlush};
+///
+/// # let mut app = App::new();
+/// app.insert_resource(ManualFlush)
+///     .add_systems(FixedPostUpdate, flush_client_messages);
+/// ```
+pub fn flush_client_messages(world: &mut World) {
+    if !world
+        .get_resource::<RenetClient>()
+        .is_some_and(|client| client.is_connected())
+    {
+        return;
+    }
+
+    world
+        .run_system_cached(super::RepliconRenetClientPlugin::send_packets)
+        .expect("forwarding system shouldn't be called recursively");
+}
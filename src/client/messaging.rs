@@ -0,0 +1,25 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::{Bytes, RenetClient};
+
+/// Sends non-Replicon data, like custom protocol messages, over custom renet channels.
+///
+/// Messages are passed to [`RenetClient`] as is, without [`MessageTransforms`](crate::MessageTransforms).
+/// On the server read them directly from [`RenetServer`](bevy_renet::renet::RenetServer).
+/// Use [`ServerMessaging`](crate::ServerMessaging) to send messages in the opposite direction.
+///
+/// Replicon manages channels starting from 0, so custom channel IDs must start after
+/// the last channel from [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels).
+/// Make sure to create all Replicon channels (they are also created when registering events)
+/// before assigning custom IDs and add a [`ChannelConfig`](bevy_renet::renet::ChannelConfig)
+/// for each of them to `client_channels_config`.
+#[derive(SystemParam)]
+pub struct ClientMessaging<'w> {
+    renet_client: ResMut<'w, RenetClient>,
+}
+
+impl ClientMessaging<'_> {
+    /// Sends a message to the server.
+    pub fn send(&mut self, channel_id: u8, message: impl Into<Bytes>) {
+        self.renet_client.send_message(channel_id, message);
+    }
+}
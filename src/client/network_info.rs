@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use bevy_renet::renet::NetworkInfo;
+
+/// Mirror of renet's [`NetworkInfo`] for the client connection.
+///
+/// Updated in [`ClientSet::ReceivePackets`](bevy_replicon::prelude::ClientSet::ReceivePackets)
+/// while connected, together with the statistics of [`RepliconClient`](bevy_replicon::prelude::RepliconClient).
+/// The resource is changed only when the values change, so [`Res::is_changed`] can be used to refresh
+/// network overlays.
+///
+/// Values are measured by renet at the connection level, so they include packet headers,
+/// resends and messages from custom channels.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct ClientNetworkInfo {
+    /// Round-trip time in seconds.
+    pub rtt: f64,
+    /// Ratio of lost packets from 0 to 1.
+    pub packet_loss: f64,
+    /// Bytes per second sent to the server.
+    pub bytes_sent_per_second: f64,
+    /// Bytes per second received from the server.
+    pub bytes_received_per_second: f64,
+}
+
+impl From<NetworkInfo> for ClientNetworkInfo {
+    fn from(info: NetworkInfo) -> Self {
+        Self {
+            rtt: info.rtt,
+            packet_loss: info.packet_loss,
+            bytes_sent_per_second: info.bytes_sent_per_second,
+            bytes_received_per_second: info.bytes_received_per_second,
+        }
+    }
+}
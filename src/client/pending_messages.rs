@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use bevy_replicon::prelude::*;
+
+use crate::{ChannelGroup, ChannelRemap};
+
+/// Free memory of client channels in renet, sampled each frame after sending.
+///
+/// Renet doesn't expose the number of queued messages, only the available memory of each channel.
+/// Memory is occupied by messages that were passed to renet, but not yet sent (for unreliable channels)
+/// or acknowledged (for reliable channels) and limited by `max_memory_usage_bytes` from
+/// [`ChannelConfig`](bevy_renet::renet::ChannelConfig). So decreasing available memory over several
+/// frames means that the app produces messages faster than the connection can carry and game code should
+/// send less.
+///
+/// Updated in [`ClientSet::SendPackets`] while connected and cleared on disconnect.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct PendingMessages {
+    available_memory: Vec<Option<usize>>,
+}
+
+impl PendingMessages {
+    /// Returns the number of bytes that can be queued on the channel before it's full.
+    ///
+    /// Returns [`None`] if the channel wasn't sampled yet or is outside of [`ChannelGroup`].
+    pub fn available_memory(&self, channel_id: u8) -> Option<usize> {
+        self.available_memory
+            .get(channel_id as usize)
+            .copied()
+            .flatten()
+    }
+}
+
+pub(super) struct PendingMessagesPlugin;
+
+impl Plugin for PendingMessagesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingMessages>()
+            .add_systems(
+                PreUpdate,
+                Self::reset
+                    .in_set(ClientSet::ReceivePackets)
+                    .run_if(bevy_renet::client_just_disconnected),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::sample
+                    .after(super::RepliconRenetClientPlugin::send_packets)
+                    .in_set(ClientSet::SendPackets)
+                    .run_if(bevy_renet::client_connected),
+            );
+    }
+}
+
+impl PendingMessagesPlugin {
+    fn reset(mut pending: ResMut<PendingMessages>) {
+        pending.available_memory.clear();
+    }
+
+    fn sample(
+        channels: Res<RepliconChannels>,
+        remap: Res<ChannelRemap>,
+        group: Res<ChannelGroup>,
+        renet_client: Res<RenetClient>,
+        mut pending: ResMut<PendingMessages>,
+    ) {
+        pending.available_memory.clear();
+        for channel_id in 0..channels.client_channels().len() as u8 {
+            let available_memory = group
+                .contains(channel_id)
+                .then(|| renet_client.channel_available_memory(remap.client_wire_id(channel_id)));
+            pending.available_memory.push(available_memory);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::net::SocketAddr;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use bevy_replicon::prelude::*;
+
+use super::{
+    assigned_id::AssignedClientId,
+    control::{ControlMessages, ControlPlugin},
+};
+use crate::{control::ControlKind, redirect};
+
+/// An event that indicates that the server asked the client to reconnect to another address.
+///
+/// Emitted after receiving [`RedirectClient`](crate::RedirectClient) over [`ControlChannel`](crate::ControlChannel).
+/// If [`RedirectConnector`] is inserted, the client disconnects from the current server right away
+/// and the connector is called with the new address once the disconnect is processed.
+/// Without the connector, only this event is emitted and the game decides what to do.
+///
+/// The redirect goes through the regular disconnect, so the guarantees are the same as for manual reconnects:
+///
+/// - [`RepliconClient`] goes through [`RepliconClientStatus::Disconnected`], so Replicon resets
+///   its entity mapping and the new server sends the full world state.
+/// - Replicated entities from the old server are not despawned automatically.
+/// - Messages that were not yet received from or sent to the old server are dropped.
+/// - The client ID is preserved only if the connector uses the same ID for the new connection,
+///   which is why it's passed to the connector.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientRedirected {
+    /// Address of the server to connect to.
+    pub addr: SocketAddr,
+}
+
+/// Creates a connection to the new server on redirect.
+///
+/// Called with the world, the new server address and [`AssignedClientId::transport_id`](crate::AssignedClientId::transport_id)
+/// from the previous connection. Should insert a new [`RenetClient`] and transport, since the crate
+/// doesn't know the authentication and channel configs for the new server.
+///
+/// # Examples
+///
+/// ```
+/// use std::{net::UdpSocket, time::SystemTime};
+///
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{
+///     netcode::{ClientAuthentication, NetcodeClientTransport},
+///     renet::{ConnectionConfig, RenetClient},
+///     RedirectConnector, RenetChannelsExt,
+/// };
+///
+/// # let mut app = App::new();
+/// app.insert_resource(RedirectConnector::new(|world, server_addr, client_id| {
+///     let channels = world.resource::<RepliconChannels>();
+///     let client = RenetClient::new(ConnectionConfig {
+///         server_channels_config: channels.get_server_configs(),
+///         client_channels_config: channels.get_client_configs(),
+///         ..Default::default()
+///     });
+///
+///     let current_time = SystemTime::now()
+///         .duration_since(SystemTime::UNIX_EPOCH)
+///         .unwrap();
+///     let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+///     let authentication = ClientAuthentication::Unsecure {
+///         client_id: client_id.unwrap_or_else(|| current_time.as_millis() as u64),
+///         protocol_id: 0,
+///         server_addr,
+///         user_data: None,
+///     };
+///     let transport = NetcodeClientTransport::new(current_time, authentication, socket).unwrap();
+///
+///     world.insert_resource(client);
+///     world.insert_resource(transport);
+/// }));
+/// ```
+#[derive(Resource)]
+#[allow(clippy::type_complexity)]
+pub struct RedirectConnector(Box<dyn Fn(&mut World, SocketAddr, Option<u64>) + Send + Sync>);
+
+impl RedirectConnector {
+    /// Creates a connector from a function.
+    pub fn new(
+        connect: impl Fn(&mut World, SocketAddr, Option<u64>) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Box::new(connect))
+    }
+}
+
+/// Redirect received from the server, applied once the client is disconnected.
+#[derive(Resource, Default)]
+struct PendingRedirect(Option<(SocketAddr, Option<u64>)>);
+
+pub(super) struct RedirectPlugin;
+
+impl Plugin for RedirectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingRedirect>()
+            .add_event::<ClientRedirected>()
+            .add_systems(
+                PreUpdate,
+                (
+                    // Runs before receiving, so the connector is called only after
+                    // the disconnect is processed on the next frame.
+                    Self::connect
+                        .after(super::RepliconRenetClientPlugin::set_disconnected)
+                        .before(Self::receive)
+                        .run_if(bevy_renet::client_just_disconnected)
+                        .run_if(resource_exists::<RedirectConnector>),
+                    Self::receive
+                        .after(ControlPlugin::receive)
+                        .run_if(bevy_renet::client_connected),
+                )
+                    .in_set(ClientSet::ReceivePackets),
+            );
+    }
+}
+
+impl RedirectPlugin {
+    fn receive(
+        mut messages: ResMut<ControlMessages>,
+        connector: Option<Res<RedirectConnector>>,
+        assigned_id: Res<AssignedClientId>,
+        mut pending: ResMut<PendingRedirect>,
+        mut redirect_events: EventWriter<ClientRedirected>,
+        mut renet_client: ResMut<RenetClient>,
+    ) {
+        for message in messages.take(ControlKind::Redirect) {
+            let Some(addr) = redirect::decode(&message) else {
+                warn!("ignoring malformed redirect from server");
+                continue;
+            };
+
+            debug!("received redirect to {addr}");
+            redirect_events.send(ClientRedirected { addr });
+            if connector.is_some() {
+                pending.0 = Some((
+                    addr,
+                    assigned_id.transport_id().map(|client_id| client_id.get()),
+                ));
+                renet_client.disconnect();
+                break;
+            }
+        }
+    }
+
+    fn connect(world: &mut World) {
+        let Some((addr, client_id)) = world.resource_mut::<PendingRedirect>().0.take() else {
+            return;
+        };
+
+        debug!("connecting to {addr} after redirect");
+        world.resource_scope(|world, connector: Mut<RedirectConnector>| {
+            (connector.0)(world, addr, client_id)
+        });
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, RenetClient};
+use bevy_replicon::prelude::*;
+
+use crate::{
+    control::{self, ControlChannel, ControlKind},
+    reliable_channel::{self, ReliableSendChannel},
+};
+
+/// A message sent by the server via [`ReliableSend`](crate::ReliableSend).
+///
+/// The acknowledgement is sent back automatically over [`ControlChannel`] when the message is received.
+#[derive(Event, Debug, Clone)]
+pub struct ReliableMessage {
+    pub sequence: u64,
+    pub message: Bytes,
+}
+
+pub(super) struct ReliableReceivePlugin;
+
+impl Plugin for ReliableReceivePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ReliableMessage>().add_systems(
+            PreUpdate,
+            Self::receive
+                .in_set(ClientSet::ReceivePackets)
+                .run_if(bevy_renet::client_connected)
+                .run_if(resource_exists::<ReliableSendChannel>),
+        );
+    }
+}
+
+impl ReliableReceivePlugin {
+    fn receive(
+        channel: Res<ReliableSendChannel>,
+        control_channel: Option<Res<ControlChannel>>,
+        mut renet_client: ResMut<RenetClient>,
+        mut reliable_events: EventWriter<ReliableMessage>,
+    ) {
+        while let Some(message) = renet_client.receive_message(**channel) {
+            let Some((sequence, message)) = reliable_channel::decode(message) else {
+                debug!("ignoring invalid reliable message from server");
+                continue;
+            };
+
+            if let Some(control_channel) = &control_channel {
+                let ack = control::encode(ControlKind::Ack, &sequence.to_le_bytes());
+                renet_client.send_message(***control_channel, ack);
+            }
+            reliable_events.send(ReliableMessage { sequence, message });
+        }
+    }
+}
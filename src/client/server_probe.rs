@@ -0,0 +1,353 @@
+use std::{net::SocketAddr, time::Duration};
+
+use bevy::prelude::*;
+use bevy_renet::{
+    netcode::NetcodeClientTransport,
+    renet::{ClientId, RenetClient},
+    RenetReceive,
+};
+
+/// Candidate servers to probe before connecting to one of them.
+///
+/// Matchmaking may return several servers for the same match, for example, in different regions.
+/// With this resource, the client connects to all candidates at once in [`PreUpdate`] and, once a server is
+/// selected according to [`Self::strategy`], inserts its [`RenetClient`] and [`NetcodeClientTransport`]
+/// as resources, so the regular forwarding takes over the already established connection.
+/// Connections to other candidates are closed. The result is reported via [`ServerSelected`] with results of
+/// all probes, and the resource is removed.
+///
+/// Probing is a regular netcode connection, so each candidate sees the client connect and then disconnect
+/// with [`RepliconDisconnectReason::TransportError`](crate::RepliconDisconnectReason::TransportError) if not selected.
+/// Candidates must accept the client, so with secure authentication the client needs a connect token for each of them.
+///
+/// # Timeout and fallback
+///
+/// Candidates that fail to connect, like with a denied or expired token, are excluded from the selection.
+/// If no candidate is selected within [`Self::probe_timeout`], all connections are closed and
+/// [`ServerProbeFailed`] is emitted instead. Nothing is inserted in this case, so the game can fall back,
+/// for example, to retry with new candidates from matchmaking.
+///
+/// [`SelectionStrategy::LowestRtt`] needs time to measure RTT, so it selects the connected candidate with the lowest RTT
+/// once all candidates were connected for at least [`ServerCandidates::RTT_SAMPLE_TIME`] or failed. If some candidates
+/// are still connecting when the timeout is reached, the best of the connected ones is selected.
+///
+/// Don't insert [`RenetClient`] while probing, the selected connection will replace it.
+///
+/// # Examples
+///
+/// ```
+/// use std::{
+///     net::{SocketAddr, UdpSocket},
+///     time::SystemTime,
+/// };
+///
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{
+///     netcode::{ClientAuthentication, NetcodeClientTransport},
+///     renet::{ConnectionConfig, RenetClient},
+///     RenetChannelsExt, SelectionStrategy, ServerCandidates,
+/// };
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let current_time = SystemTime::now()
+///     .duration_since(SystemTime::UNIX_EPOCH)
+///     .unwrap();
+///
+/// let mut candidates = ServerCandidates::new(SelectionStrategy::LowestRtt);
+/// for server_addr in ["127.0.0.1:5000", "127.0.0.1:5001"] {
+///     let server_addr: SocketAddr = server_addr.parse().unwrap();
+///     let client = RenetClient::new(ConnectionConfig {
+///         server_channels_config: channels.get_server_configs(),
+///         client_channels_config: channels.get_client_configs(),
+///         ..Default::default()
+///     });
+///     let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+///     let authentication = ClientAuthentication::Unsecure {
+///         client_id: 0,
+///         protocol_id: 0,
+///         server_addr,
+///         user_data: None,
+///     };
+///     let transport = NetcodeClientTransport::new(current_time, authentication, socket).unwrap();
+///     candidates = candidates.with_candidate(server_addr, client, transport);
+/// }
+///
+/// app.insert_resource(candidates);
+/// ```
+#[derive(Resource)]
+pub struct ServerCandidates {
+    /// How to select the server.
+    pub strategy: SelectionStrategy,
+
+    /// Maximum time for probing.
+    ///
+    /// By default, [`Self::DEFAULT_PROBE_TIMEOUT`].
+    pub probe_timeout: Duration,
+
+    probes: Vec<Probe>,
+    elapsed: Duration,
+}
+
+impl ServerCandidates {
+    /// Default value for [`Self::probe_timeout`].
+    pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Minimum time a candidate should stay connected to measure its RTT for [`SelectionStrategy::LowestRtt`].
+    pub const RTT_SAMPLE_TIME: Duration = Duration::from_millis(500);
+
+    /// Creates an empty list of candidates.
+    pub fn new(strategy: SelectionStrategy) -> Self {
+        Self {
+            strategy,
+            probe_timeout: Self::DEFAULT_PROBE_TIMEOUT,
+            probes: Default::default(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Sets [`Self::probe_timeout`].
+    #[must_use]
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+
+    /// Adds a candidate server.
+    ///
+    /// `client` and `transport` should be created the same way as for a regular connection to `addr`.
+    #[must_use]
+    pub fn with_candidate(
+        mut self,
+        addr: SocketAddr,
+        client: RenetClient,
+        transport: NetcodeClientTransport,
+    ) -> Self {
+        self.probes.push(Probe {
+            addr,
+            client,
+            transport,
+            connected_at: None,
+            failed: false,
+        });
+        self
+    }
+
+    /// Returns the current results of all probes.
+    pub fn results(&self) -> Vec<ProbeResult> {
+        self.probes.iter().map(Probe::result).collect()
+    }
+
+    /// Updates all connections.
+    fn update(&mut self, delta: Duration) {
+        self.elapsed += delta;
+        for probe in &mut self.probes {
+            if probe.failed {
+                continue;
+            }
+
+            probe.client.update(delta);
+            if let Err(e) = probe.transport.update(delta, &mut probe.client) {
+                debug!("probe of `{}` failed: {e}", probe.addr);
+                probe.failed = true;
+                continue;
+            }
+            if probe.client.is_disconnected() {
+                debug!("probe of `{}` was disconnected", probe.addr);
+                probe.failed = true;
+                continue;
+            }
+            if probe.client.is_connected() && probe.connected_at.is_none() {
+                debug!("probe of `{}` connected in {:?}", probe.addr, self.elapsed);
+                probe.connected_at = Some(self.elapsed);
+            }
+
+            // Errors are reported by `update` in the next frame.
+            let _ = probe.transport.send_packets(&mut probe.client);
+        }
+    }
+
+    /// Returns the index of the selected probe if the selection is finished.
+    ///
+    /// Returns `Some(None)` if probing failed.
+    fn select(&self) -> Option<Option<usize>> {
+        let timed_out = self.elapsed >= self.probe_timeout;
+        let connected = self
+            .probes
+            .iter()
+            .enumerate()
+            .filter(|(_, probe)| !probe.failed && probe.connected_at.is_some());
+
+        let selected = match self.strategy {
+            SelectionStrategy::FirstToRespond => connected
+                .min_by_key(|(_, probe)| probe.connected_at)
+                .map(|(index, _)| index),
+            SelectionStrategy::LowestRtt => {
+                let sampled = self.probes.iter().all(|probe| {
+                    probe.failed
+                        || probe.connected_at.is_some_and(|connected_at| {
+                            self.elapsed - connected_at >= Self::RTT_SAMPLE_TIME
+                        })
+                });
+                if !sampled && !timed_out {
+                    return None;
+                }
+
+                connected
+                    .min_by(|(_, a), (_, b)| a.client.rtt().total_cmp(&b.client.rtt()))
+                    .map(|(index, _)| index)
+            }
+        };
+
+        if selected.is_some() || timed_out || self.probes.iter().all(|probe| probe.failed) {
+            Some(selected)
+        } else {
+            None
+        }
+    }
+}
+
+/// Strategy for [`ServerCandidates`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// Select the first candidate that accepted the connection.
+    ///
+    /// Connection time includes the netcode handshake, so it roughly reflects the latency,
+    /// but also the server load.
+    #[default]
+    FirstToRespond,
+
+    /// Select the candidate with the lowest RTT measured by renet.
+    LowestRtt,
+}
+
+/// Result of probing a candidate from [`ServerCandidates`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbeResult {
+    /// Address of the candidate.
+    pub addr: SocketAddr,
+
+    /// Time since the start of probing until the candidate accepted the connection.
+    ///
+    /// [`None`] if the candidate didn't accept the connection.
+    pub connect_time: Option<Duration>,
+
+    /// Round-time trip in seconds measured during probing.
+    ///
+    /// [`None`] if the candidate didn't accept the connection.
+    pub rtt: Option<f64>,
+}
+
+/// An event that indicates that a server from [`ServerCandidates`] was selected and connected.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ServerSelected {
+    /// Address of the selected server.
+    pub addr: SocketAddr,
+
+    /// Results of all probes in the order candidates were added.
+    pub probes: Vec<ProbeResult>,
+}
+
+/// An event that indicates that no server from [`ServerCandidates`] could be selected.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ServerProbeFailed {
+    /// Results of all probes in the order candidates were added.
+    pub probes: Vec<ProbeResult>,
+}
+
+struct Probe {
+    addr: SocketAddr,
+    client: RenetClient,
+    transport: NetcodeClientTransport,
+
+    /// Time since the start of probing when the candidate accepted the connection.
+    connected_at: Option<Duration>,
+
+    failed: bool,
+}
+
+impl Probe {
+    fn result(&self) -> ProbeResult {
+        let connected = !self.failed && self.connected_at.is_some();
+        ProbeResult {
+            addr: self.addr,
+            connect_time: self.connected_at,
+            rtt: connected.then(|| self.client.rtt()),
+        }
+    }
+
+    fn client_id(&self) -> ClientId {
+        self.transport.client_id()
+    }
+
+    fn close(&mut self) {
+        if !self.failed {
+            self.transport.disconnect();
+            let _ = self.transport.send_packets(&mut self.client);
+        }
+    }
+}
+
+pub(super) struct ServerProbePlugin;
+
+impl Plugin for ServerProbePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ServerSelected>()
+            .add_event::<ServerProbeFailed>()
+            .add_systems(
+                PreUpdate,
+                Self::probe
+                    .before(RenetReceive)
+                    .run_if(resource_exists::<ServerCandidates>),
+            );
+    }
+}
+
+impl ServerProbePlugin {
+    fn probe(
+        mut commands: Commands,
+        time: Res<Time<Real>>,
+        mut candidates: ResMut<ServerCandidates>,
+        mut selected_events: EventWriter<ServerSelected>,
+        mut failed_events: EventWriter<ServerProbeFailed>,
+    ) {
+        // Select before updating, so the selected connection is updated
+        // only by the regular renet systems in this frame.
+        let Some(selected) = candidates.select() else {
+            candidates.update(time.delta());
+            return;
+        };
+
+        let probes = candidates.results();
+        commands.remove_resource::<ServerCandidates>();
+
+        let Some(index) = selected else {
+            warn!("unable to select a server from {} candidates", probes.len());
+            for probe in &mut candidates.probes {
+                probe.close();
+            }
+            failed_events.send(ServerProbeFailed { probes });
+            return;
+        };
+
+        let selected = candidates.probes.swap_remove(index);
+        for probe in &mut candidates.probes {
+            probe.close();
+        }
+
+        info!(
+            "selected `{}` from {} candidates as `{}`",
+            selected.addr,
+            probes.len(),
+            selected.client_id(),
+        );
+        commands.insert_resource(selected.client);
+        commands.insert_resource(selected.transport);
+        selected_events.send(ServerSelected {
+            addr: selected.addr,
+            probes,
+        });
+    }
+}
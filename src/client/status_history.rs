@@ -0,0 +1,105 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+use crate::RepliconDisconnectReason;
+
+/// Recent transitions of [`RepliconClientStatus`] with timestamps.
+///
+/// Updated by [`RepliconRenetClientPlugin`](crate::RepliconRenetClientPlugin) whenever it changes
+/// the client status. Only the last [`Self::capacity`] transitions are kept.
+/// Useful to diagnose reconnect storms and flaky connections.
+#[derive(Resource, Debug, Clone)]
+pub struct StatusHistory {
+    capacity: usize,
+    transitions: VecDeque<StatusTransition>,
+}
+
+impl StatusHistory {
+    /// Creates an empty history that keeps up to `capacity` transitions.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            transitions: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the maximum number of stored transitions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns an iterator over transitions from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &StatusTransition> {
+        self.transitions.iter()
+    }
+
+    /// Returns the most recent transition.
+    pub fn last(&self) -> Option<&StatusTransition> {
+        self.transitions.back()
+    }
+
+    pub(super) fn push(&mut self, transition: StatusTransition) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.transitions.len() == self.capacity {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(transition);
+    }
+}
+
+impl Default for StatusHistory {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+/// A single transition in [`StatusHistory`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusTransition {
+    /// The new status.
+    pub status: RepliconClientStatus,
+    /// Elapsed time since app startup.
+    pub time: Duration,
+    /// Disconnect reason reported by renet, only for [`RepliconClientStatus::Disconnected`].
+    pub reason: Option<String>,
+    /// Structured disconnect reason, only for [`RepliconClientStatus::Disconnected`].
+    ///
+    /// Taken from the transport if it provides one, otherwise from renet.
+    pub disconnect_reason: Option<RepliconDisconnectReason>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity() {
+        let mut history = StatusHistory::new(2);
+        for status in [
+            RepliconClientStatus::Connecting,
+            RepliconClientStatus::Connected { client_id: None },
+            RepliconClientStatus::Disconnected,
+        ] {
+            history.push(StatusTransition {
+                status,
+                time: Duration::ZERO,
+                reason: None,
+                disconnect_reason: None,
+            });
+        }
+
+        let statuses: Vec<_> = history.iter().map(|transition| transition.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                RepliconClientStatus::Connected { client_id: None },
+                RepliconClientStatus::Disconnected
+            ]
+        );
+    }
+}
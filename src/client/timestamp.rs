@@ -0,0 +1,37 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::client::{server_mutate_ticks::ServerMutateTicks, ServerUpdateTick};
+
+use super::clock_sync::ClockSync;
+use crate::timestamp::{self, MessageTimestamps};
+
+/// Tags sent messages over channels from [`MessageTimestamps`].
+#[derive(SystemParam)]
+pub(super) struct TimestampWriter<'w> {
+    timestamps: Option<Res<'w, MessageTimestamps>>,
+    clock_sync: Res<'w, ClockSync>,
+    update_tick: Res<'w, ServerUpdateTick>,
+    mutate_ticks: Option<Res<'w, ServerMutateTicks>>,
+}
+
+impl TimestampWriter<'_> {
+    /// Returns the message with the tag if the channel is tagged or the message as is otherwise.
+    pub(super) fn write(&self, channel_id: u8, message: Bytes) -> Bytes {
+        if !self
+            .timestamps
+            .as_ref()
+            .is_some_and(|timestamps| timestamps.contains(channel_id))
+        {
+            return message;
+        }
+
+        let mut tick = **self.update_tick;
+        if let Some(mutate_ticks) = &self.mutate_ticks {
+            if mutate_ticks.last_tick() > tick {
+                tick = mutate_ticks.last_tick();
+            }
+        }
+
+        timestamp::encode(self.clock_sync.server_time_estimate(), tick, &message)
+    }
+}
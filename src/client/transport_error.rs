@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy_renet::netcode::{NetcodeClientTransport, NetcodeError, NetcodeTransportError};
+use bevy_replicon::prelude::*;
+
+use crate::transport_error;
+
+/// An event that indicates that [`NetcodeClientTransport`] failed to update.
+///
+/// Emitted in [`ClientSet::ReceivePackets`] for each fatal error reported by the transport, for example,
+/// when the socket can no longer send because the network interface went down. Renet keeps
+/// running after such errors, but the client may stop receiving anything, so use this event to alert
+/// and reconnect.
+///
+/// Errors are classified the same way as for [`ServerTransportError`](crate::ServerTransportError):
+/// transient IO errors are logged at the debug level and retried automatically.
+/// Additionally, errors that the transport reports on every update after the client disconnects
+/// are not forwarded, since the disconnect is already reported by [`RepliconClient`]
+/// and recorded in [`StatusHistory`](crate::StatusHistory).
+///
+/// To recover, remove [`RenetClient`](bevy_renet::renet::RenetClient) and [`NetcodeClientTransport`],
+/// bind a new socket and insert a newly created client and transport.
+///
+/// Only available with the `renet_netcode` feature.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct ClientTransportError {
+    pub reason: String,
+}
+
+pub(super) struct TransportErrorPlugin;
+
+impl Plugin for TransportErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ClientTransportError>().add_systems(
+            PreUpdate,
+            Self::forward_errors
+                .in_set(ClientSet::ReceivePackets)
+                .run_if(resource_exists::<NetcodeClientTransport>),
+        );
+    }
+}
+
+impl TransportErrorPlugin {
+    fn forward_errors(
+        mut transport_errors: EventReader<NetcodeTransportError>,
+        mut client_errors: EventWriter<ClientTransportError>,
+    ) {
+        for error in transport_errors.read() {
+            if transport_error::is_transient(error) {
+                debug!("ignoring transient client transport error: {error}");
+                continue;
+            }
+            if is_disconnect(error) {
+                trace!("ignoring client transport error after disconnect: {error}");
+                continue;
+            }
+
+            error!("client transport failed: {error}");
+            client_errors.send(ClientTransportError {
+                reason: error.to_string(),
+            });
+        }
+    }
+}
+
+/// Returns `true` if the error only indicates that the client is disconnected.
+fn is_disconnect(error: &NetcodeTransportError) -> bool {
+    matches!(
+        error,
+        NetcodeTransportError::Renet(_)
+            | NetcodeTransportError::Netcode(NetcodeError::Disconnected(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use bevy_renet::{netcode::NetcodeDisconnectReason, renet::DisconnectReason};
+
+    use super::*;
+
+    #[test]
+    fn disconnect() {
+        assert!(is_disconnect(&NetcodeTransportError::Renet(
+            DisconnectReason::DisconnectedByClient
+        )));
+        assert!(is_disconnect(&NetcodeTransportError::Netcode(
+            NetcodeError::Disconnected(NetcodeDisconnectReason::ConnectionTimedOut)
+        )));
+        assert!(!is_disconnect(&NetcodeTransportError::Netcode(
+            NetcodeError::Expired
+        )));
+        assert!(!is_disconnect(&NetcodeTransportError::IO(
+            io::Error::other("interface is down")
+        )));
+    }
+}
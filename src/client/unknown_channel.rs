@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use bevy_replicon::prelude::*;
+
+use crate::{
+    transport::ChannelSide,
+    unknown_channel::{UnknownChannelAction, UnknownChannels},
+    UnknownChannelPolicy,
+};
+
+pub(super) struct UnknownChannelPlugin;
+
+impl Plugin for UnknownChannelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::drain
+                .after(super::RepliconRenetClientPlugin::receive_packets)
+                .in_set(ClientSet::ReceivePackets)
+                .run_if(bevy_renet::client_connected)
+                .run_if(resource_exists::<UnknownChannelPolicy>),
+        );
+    }
+}
+
+impl UnknownChannelPlugin {
+    fn drain(unknown_channels: UnknownChannels, mut renet_client: ResMut<RenetClient>) {
+        let count = unknown_channels.drain(&mut *renet_client, (), ChannelSide::Server);
+        if count == 0 {
+            return;
+        }
+
+        match unknown_channels.action() {
+            UnknownChannelAction::Discard => {
+                warn!("discarding {count} messages from server over unknown channels");
+            }
+            UnknownChannelAction::Disconnect => {
+                warn!(
+                    "disconnecting from server for sending {count} messages over unknown channels"
+                );
+                renet_client.disconnect();
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::{mem, time::Duration};
+
+use bevy::prelude::*;
+
+/// Renet channel used to exchange timestamps for [`ClockSync`](crate::ClockSync).
+///
+/// Insert it on both server and client to enable clock synchronization.
+///
+/// Requires an unreliable [`ChannelConfig`](bevy_renet::renet::ChannelConfig) with this ID,
+/// see [channel IDs](crate::ControlChannel#channel-ids).
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct ClockSyncChannel(pub u8);
+
+const TIMESTAMP_SIZE: usize = mem::size_of::<u64>();
+
+/// Serializes time as microseconds.
+pub(crate) fn encode_time(time: Duration) -> [u8; TIMESTAMP_SIZE] {
+    (time.as_micros() as u64).to_le_bytes()
+}
+
+/// Reads time serialized by [`encode_time`].
+#[cfg(feature = "client")]
+pub(crate) fn decode_time(bytes: &[u8]) -> Option<Duration> {
+    let bytes = bytes.try_into().ok()?;
+    Some(Duration::from_micros(u64::from_le_bytes(bytes)))
+}
+
+/// Creates a reply with the client time from the request followed by the server time.
+#[cfg(feature = "server")]
+pub(crate) fn encode_reply(request: &[u8], server_time: Duration) -> Option<Vec<u8>> {
+    if request.len() != TIMESTAMP_SIZE {
+        return None;
+    }
+
+    let mut reply = Vec::with_capacity(TIMESTAMP_SIZE * 2);
+    reply.extend_from_slice(request);
+    reply.extend_from_slice(&encode_time(server_time));
+    Some(reply)
+}
+
+/// Splits a reply into the client time from the request and the server time.
+#[cfg(feature = "client")]
+pub(crate) fn decode_reply(reply: &[u8]) -> Option<(Duration, Duration)> {
+    if reply.len() != TIMESTAMP_SIZE * 2 {
+        return None;
+    }
+
+    let (client_time, server_time) = reply.split_at(TIMESTAMP_SIZE);
+    Some((decode_time(client_time)?, decode_time(server_time)?))
+}
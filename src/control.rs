@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, ChannelConfig, SendType};
+use bevy_replicon::prelude::*;
+
+/// Renet channel reserved for this crate's own protocol messages.
+///
+/// Insert it on both server and client to enable features that rely on it:
+///
+/// - [`KickClient`](crate::KickClient) reasons, received as [`ClientKicked`](crate::ClientKicked).
+/// - Messages echoed by [`EchoClientEvents`](crate::EchoClientEvents), received as [`EventEchoed`](crate::EventEchoed).
+/// - Metadata exchange, enabled by [`HandshakeMetadata`](crate::HandshakeMetadata).
+/// - [`RedirectClient`](crate::RedirectClient), received as [`ClientRedirected`](crate::ClientRedirected).
+/// - Acknowledgements of messages sent via [`ReliableSend`](crate::ReliableSend).
+///
+/// Each message starts with a byte that identifies its kind, so all features share a single channel
+/// and don't take channel IDs from the game. Messages of unknown kinds are logged and ignored,
+/// so a peer with a newer version of the crate doesn't break the connection.
+///
+/// Features that don't fit a reliable ordered channel with a small memory budget use their own channels:
+/// [`ClockSyncChannel`](crate::ClockSyncChannel) needs an unreliable channel, since a resent timestamp
+/// would skew the measured round trip, and [`AssetStreamChannel`](crate::AssetStreamChannel) sends
+/// large blobs that would delay other control messages and exhaust the memory of this channel.
+/// The same applies to [`ReliableSendChannel`](crate::ReliableSendChannel), which carries game messages.
+///
+/// # Channel IDs
+///
+/// Channels of this crate are not managed by Replicon, so you need to add their configs to both
+/// `server_channels_config` and `client_channels_config`. Their IDs must not overlap with IDs
+/// of channels from [`RepliconChannels`] and with each other.
+///
+/// For this channel, use [`Self::config`] and [`Self::after`] to reserve the ID right after channels
+/// from [`RepliconChannels`], which is the recommended layout. Other channels should start after it.
+/// Don't send messages over it yourself.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{renet::ConnectionConfig, ControlChannel, RenetChannelsExt};
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let control_channel = ControlChannel::after(channels);
+///
+/// let mut server_channels_config = channels.get_server_configs();
+/// let mut client_channels_config = channels.get_client_configs();
+/// server_channels_config.push(control_channel.config());
+/// client_channels_config.push(control_channel.config());
+///
+/// let connection_config = ConnectionConfig {
+///     server_channels_config,
+///     client_channels_config,
+///     ..Default::default()
+/// };
+/// app.insert_resource(control_channel);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct ControlChannel(pub u8);
+
+impl ControlChannel {
+    /// Memory budget of [`Self::config`].
+    pub const MAX_MEMORY_USAGE_BYTES: usize = 1024 * 1024;
+
+    /// Reserves the first ID after server and client channels from [`RepliconChannels`].
+    ///
+    /// Replicon may have different numbers of server and client channels, so the larger one is used
+    /// to get the same ID on both sides.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of channels doesn't fit into [`u8`].
+    pub fn after(channels: &RepliconChannels) -> Self {
+        let count = channels
+            .server_channels()
+            .len()
+            .max(channels.client_channels().len());
+        let channel_id = u8::try_from(count)
+            .unwrap_or_else(|_| panic!("{count} channels leave no ID for the control channel"));
+        Self(channel_id)
+    }
+
+    /// Returns a [`SendType::ReliableOrdered`] config for this channel.
+    pub fn config(self) -> ChannelConfig {
+        ChannelConfig {
+            channel_id: self.0,
+            max_memory_usage_bytes: Self::MAX_MEMORY_USAGE_BYTES,
+            send_type: SendType::ReliableOrdered {
+                resend_time: Duration::from_millis(300),
+            },
+        }
+    }
+}
+
+/// Kind of a message sent over [`ControlChannel`].
+///
+/// Values are part of the protocol, never reuse them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum ControlKind {
+    /// Reason from [`KickClient`](crate::KickClient), sent by the server.
+    Kick = 0,
+    /// Client message from [`EchoClientEvents`](crate::EchoClientEvents), sent by the server.
+    Echo = 1,
+    /// Serialized [`HandshakeMetadata`](crate::HandshakeMetadata), sent by both sides.
+    Handshake = 2,
+    /// Address from [`RedirectClient`](crate::RedirectClient), sent by the server.
+    Redirect = 3,
+    /// Acknowledgement of a message from [`ReliableSend`](crate::ReliableSend), sent by the client.
+    Ack = 4,
+}
+
+impl TryFrom<u8> for ControlKind {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Kick),
+            1 => Ok(Self::Echo),
+            2 => Ok(Self::Handshake),
+            3 => Ok(Self::Redirect),
+            4 => Ok(Self::Ack),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Prepends the kind to the payload.
+pub(crate) fn encode(kind: ControlKind, payload: &[u8]) -> Bytes {
+    let mut message = Vec::with_capacity(1 + payload.len());
+    message.push(kind as u8);
+    message.extend_from_slice(payload);
+    message.into()
+}
+
+/// Splits a message serialized by [`encode`] into its kind and payload.
+///
+/// Returns the kind byte as an error if it's unknown.
+pub(crate) fn decode(mut message: Bytes) -> Result<(ControlKind, Bytes), Option<u8>> {
+    let Some(&kind) = message.first() else {
+        return Err(None);
+    };
+    let kind = ControlKind::try_from(kind).map_err(Some)?;
+    let payload = message.split_off(1);
+    Ok((kind, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn after() {
+        let mut channels = RepliconChannels::default();
+        let server_count = channels.server_channels().len();
+        assert_eq!(*ControlChannel::after(&channels), server_count as u8);
+
+        for _ in 0..server_count {
+            channels.create_client_channel(ChannelKind::Ordered.into());
+        }
+        assert_eq!(
+            *ControlChannel::after(&channels),
+            channels.client_channels().len() as u8,
+            "should use the larger number of channels"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for kind in [
+            ControlKind::Kick,
+            ControlKind::Echo,
+            ControlKind::Handshake,
+            ControlKind::Redirect,
+            ControlKind::Ack,
+        ] {
+            let message = encode(kind, &[1]);
+            assert_eq!(decode(message), Ok((kind, Bytes::from_static(&[1]))));
+        }
+    }
+
+    #[test]
+    fn decode_unknown() {
+        assert_eq!(decode(Bytes::new()), Err(None));
+        assert_eq!(
+            decode(Bytes::from_static(&[u8::MAX, 1])),
+            Err(Some(u8::MAX))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+};
+
+/// Execution time of the systems that forward messages between renet and Replicon.
+///
+/// Updated every frame in which the corresponding system runs.
+/// Helps to find out whether the networking backend or game logic is the bottleneck.
+#[derive(Resource, Default, Debug)]
+pub struct ForwardingTimings {
+    /// Time spent passing messages from [`RenetServer`](bevy_renet::renet::RenetServer)
+    /// to [`RepliconServer`](bevy_replicon::prelude::RepliconServer).
+    pub server_receive: TimingWindow,
+
+    /// Time spent passing messages from [`RepliconServer`](bevy_replicon::prelude::RepliconServer)
+    /// to [`RenetServer`](bevy_renet::renet::RenetServer).
+    pub server_send: TimingWindow,
+
+    /// Time spent passing messages from [`RenetClient`](bevy_renet::renet::RenetClient)
+    /// to [`RepliconClient`](bevy_replicon::prelude::RepliconClient).
+    pub client_receive: TimingWindow,
+
+    /// Time spent passing messages from [`RepliconClient`](bevy_replicon::prelude::RepliconClient)
+    /// to [`RenetClient`](bevy_renet::renet::RenetClient).
+    pub client_send: TimingWindow,
+}
+
+/// Rolling window of the last [`Self::CAPACITY`] measurements.
+#[derive(Default, Debug, Clone)]
+pub struct TimingWindow {
+    samples: VecDeque<Duration>,
+}
+
+impl TimingWindow {
+    /// Maximum number of stored measurements.
+    pub const CAPACITY: usize = 120;
+
+    /// Returns the shortest measurement in the window.
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().copied()
+    }
+
+    /// Returns the longest measurement in the window.
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    /// Returns the average of measurements in the window.
+    pub fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let sum: Duration = self.samples.iter().sum();
+        Some(sum / self.samples.len() as u32)
+    }
+
+    /// Returns the most recent measurement.
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+
+    pub(crate) fn push(&mut self, duration: Duration) {
+        if self.samples.len() == Self::CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(duration);
+    }
+}
+
+/// Plugin to write [`Diagnostics`] with the number of messages forwarded between renet and Replicon every frame.
+///
+/// Messages are counted in the forwarding loops, so received messages include those that were later
+/// discarded by [`MessageTransforms`](crate::MessageTransforms). Combine with byte counts from
+/// Replicon diagnostics to track the average message size.
+pub struct MessageCountDiagnostics;
+
+impl Plugin for MessageCountDiagnostics {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MessageCounts>()
+            .add_systems(Last, Self::add_measurements)
+            .register_diagnostic(
+                Diagnostic::new(Self::SERVER_SENT)
+                    .with_suffix(" messages")
+                    .with_max_history_length(Self::DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(Self::SERVER_RECEIVED)
+                    .with_suffix(" messages")
+                    .with_max_history_length(Self::DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(Self::CLIENT_SENT)
+                    .with_suffix(" messages")
+                    .with_max_history_length(Self::DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(Self::CLIENT_RECEIVED)
+                    .with_suffix(" messages")
+                    .with_max_history_length(Self::DIAGNOSTIC_HISTORY_LEN),
+            );
+    }
+}
+
+impl MessageCountDiagnostics {
+    /// How many messages the server passed to renet in a frame.
+    pub const SERVER_SENT: DiagnosticPath = DiagnosticPath::const_new("renet/server/messages_sent");
+    /// How many messages the server received from renet in a frame.
+    pub const SERVER_RECEIVED: DiagnosticPath =
+        DiagnosticPath::const_new("renet/server/messages_received");
+    /// How many messages the client passed to renet in a frame.
+    pub const CLIENT_SENT: DiagnosticPath = DiagnosticPath::const_new("renet/client/messages_sent");
+    /// How many messages the client received from renet in a frame.
+    pub const CLIENT_RECEIVED: DiagnosticPath =
+        DiagnosticPath::const_new("renet/client/messages_received");
+
+    /// Max diagnostic history length.
+    pub const DIAGNOSTIC_HISTORY_LEN: usize = 60;
+
+    fn add_measurements(mut diagnostics: Diagnostics, mut counts: ResMut<MessageCounts>) {
+        diagnostics.add_measurement(&Self::SERVER_SENT, || counts.server_sent as f64);
+        diagnostics.add_measurement(&Self::SERVER_RECEIVED, || counts.server_received as f64);
+        diagnostics.add_measurement(&Self::CLIENT_SENT, || counts.client_sent as f64);
+        diagnostics.add_measurement(&Self::CLIENT_RECEIVED, || counts.client_received as f64);
+        *counts = Default::default();
+    }
+}
+
+/// Number of messages forwarded in the current frame.
+///
+/// Updated by the forwarding systems and reset by [`MessageCountDiagnostics`].
+#[derive(Resource, Default)]
+pub(crate) struct MessageCounts {
+    pub(crate) server_sent: usize,
+    pub(crate) server_received: usize,
+    pub(crate) client_sent: usize,
+    pub(crate) client_received: usize,
+}
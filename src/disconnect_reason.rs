@@ -0,0 +1,75 @@
+#[cfg(feature = "renet_netcode")]
+use bevy_renet::netcode::NetcodeDisconnectReason;
+use bevy_renet::renet::DisconnectReason;
+
+/// Backend-agnostic reason of a disconnect.
+///
+/// Maps renet's and transport's reasons into a stable set that games can switch on, for example, to show
+/// a message to the player. Reported via [`ClientDisconnected`](crate::ClientDisconnected) on the server
+/// and [`StatusTransition::disconnect_reason`](crate::StatusTransition::disconnect_reason) on the client.
+///
+/// Renet doesn't distinguish the reasons for which the server closes a connection. So disconnects by the server,
+/// including the ones caused by [`IdleTimeout`](crate::IdleTimeout) or [`SendBackpressure`](crate::SendBackpressure),
+/// are reported as [`Self::Kicked`]. This includes server shutdowns on the client side, since the server
+/// disconnects all clients on exit. Similarly, the netcode server transport doesn't report why it closed
+/// a connection, so on the server client-initiated disconnects and timeouts are reported as [`Self::TransportError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepliconDisconnectReason {
+    /// The other side stopped responding.
+    Timeout,
+    /// The server closed the connection.
+    Kicked,
+    /// The server stopped while the client was connected.
+    ServerShutdown,
+    /// The client closed the connection.
+    ClientRequested,
+    /// The transport failed or rejected the connection.
+    TransportError,
+    /// The other side sent data that couldn't be processed,
+    /// usually caused by different channel configs or versions.
+    ProtocolMismatch,
+}
+
+impl RepliconDisconnectReason {
+    /// Returns the name of the reason in `snake_case`, for example, to use as a metric label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Kicked => "kicked",
+            Self::ServerShutdown => "server_shutdown",
+            Self::ClientRequested => "client_requested",
+            Self::TransportError => "transport_error",
+            Self::ProtocolMismatch => "protocol_mismatch",
+        }
+    }
+}
+
+impl From<&DisconnectReason> for RepliconDisconnectReason {
+    fn from(reason: &DisconnectReason) -> Self {
+        match reason {
+            DisconnectReason::Transport => Self::TransportError,
+            DisconnectReason::DisconnectedByClient => Self::ClientRequested,
+            DisconnectReason::DisconnectedByServer => Self::Kicked,
+            DisconnectReason::PacketSerialization(_)
+            | DisconnectReason::SendChannelError { .. } => Self::TransportError,
+            DisconnectReason::PacketDeserialization(_)
+            | DisconnectReason::ReceivedInvalidChannelId(_)
+            | DisconnectReason::ReceiveChannelError { .. } => Self::ProtocolMismatch,
+        }
+    }
+}
+
+#[cfg(feature = "renet_netcode")]
+impl From<NetcodeDisconnectReason> for RepliconDisconnectReason {
+    fn from(reason: NetcodeDisconnectReason) -> Self {
+        match reason {
+            NetcodeDisconnectReason::ConnectionTimedOut
+            | NetcodeDisconnectReason::ConnectionResponseTimedOut
+            | NetcodeDisconnectReason::ConnectionRequestTimedOut => Self::Timeout,
+            NetcodeDisconnectReason::ConnectTokenExpired
+            | NetcodeDisconnectReason::ConnectionDenied => Self::TransportError,
+            NetcodeDisconnectReason::DisconnectedByClient => Self::ClientRequested,
+            NetcodeDisconnectReason::DisconnectedByServer => Self::Kicked,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::mem;
+
+use bevy_renet::renet::Bytes;
+use bevy_replicon::core::replicon_tick::RepliconTick;
+
+const CHANNEL_SIZE: usize = mem::size_of::<u8>();
+const TICK_SIZE: usize = mem::size_of::<u32>();
+
+/// Prepends the client channel and tick to the echoed message.
+#[cfg(feature = "server")]
+pub(crate) fn encode(channel_id: u8, tick: RepliconTick, message: &[u8]) -> Bytes {
+    let mut payload = Vec::with_capacity(CHANNEL_SIZE + TICK_SIZE + message.len());
+    payload.push(channel_id);
+    payload.extend_from_slice(&tick.get().to_le_bytes());
+    payload.extend_from_slice(message);
+    payload.into()
+}
+
+/// Splits a payload serialized by [`encode`] into its channel, tick and the original message.
+#[cfg(feature = "client")]
+pub(crate) fn decode(mut payload: Bytes) -> Option<(u8, RepliconTick, Bytes)> {
+    if payload.len() < CHANNEL_SIZE + TICK_SIZE {
+        return None;
+    }
+
+    let header = payload.split_to(CHANNEL_SIZE + TICK_SIZE);
+    let (channel_id, tick) = header.split_at(CHANNEL_SIZE);
+    let tick = u32::from_le_bytes(tick.try_into().ok()?);
+
+    Some((channel_id[0], RepliconTick::new(tick), payload))
+}
+
+#[cfg(all(test, feature = "client", feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let tick = RepliconTick::new(42);
+        let payload = encode(3, tick, &[1, 2]);
+        assert_eq!(payload.len(), CHANNEL_SIZE + TICK_SIZE + 2);
+        assert_eq!(
+            decode(payload),
+            Some((3, tick, Bytes::from_static(&[1, 2])))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(decode(Bytes::from_static(&[0; TICK_SIZE])), None);
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+
+use crate::{IncomingTransform, OutgoingTransform};
+
+/// Application-layer encryption of forwarded messages with a pre-shared key.
+///
+/// Each message is encrypted with XChaCha20-Poly1305 using a random nonce, which adds 40 bytes
+/// (24-byte nonce and 16-byte tag) to each message. The channel ID and the direction are
+/// authenticated, so a message can't be moved to another channel or reflected back to its sender.
+/// Messages that fail to decrypt are discarded. There is no replay protection beyond
+/// what renet provides for its channels.
+///
+/// This is independent of the netcode transport encryption, which is only enabled with
+/// [`ServerAuthentication::Secure`](bevy_renet::netcode::ServerAuthentication::Secure).
+/// Use it when connecting with `Unsecure` authentication or with custom transports.
+/// Combining both is possible, but encrypts messages twice.
+///
+/// Register the same instance as both outgoing and incoming transform with the same key on client and server.
+/// Register it after other transforms, like compression, since encrypted data doesn't compress.
+///
+/// Transforms receive local channel IDs, before [`ChannelRemap`](crate::ChannelRemap) maps them
+/// to wire IDs. So the authenticated channel ID is the local one, and both sides must use the same
+/// local channel IDs. With a remap that changes IDs on only one side, like during a gradual rollout,
+/// all messages on remapped channels will fail to decrypt and will be discarded.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::{MessageTransforms, PskEncryption};
+///
+/// # let mut app = App::new();
+/// # app.init_resource::<MessageTransforms>();
+/// let encryption = PskEncryption::new(&[0; 32]); // Load the key from a secure location instead.
+/// app.world_mut()
+///     .resource_mut::<MessageTransforms>()
+///     .add_outgoing(encryption.clone())
+///     .add_incoming(encryption);
+/// ```
+#[derive(Clone)]
+pub struct PskEncryption {
+    cipher: XChaCha20Poly1305,
+}
+
+impl PskEncryption {
+    /// Creates encryption with a 32-byte pre-shared key.
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: XChaCha20Poly1305::new(key.into()),
+        }
+    }
+}
+
+impl OutgoingTransform for PskEncryption {
+    fn transform(&self, client_id: ClientId, channel_id: u8, message: Bytes) -> Bytes {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(channel_id, client_id != ClientId::SERVER);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &message,
+                    aad: &aad,
+                },
+            )
+            .expect("encryption should only fail for messages exceeding the cipher limit");
+
+        let mut bytes = Vec::with_capacity(nonce.len() + ciphertext.len());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        bytes.into()
+    }
+}
+
+impl IncomingTransform for PskEncryption {
+    fn transform(&self, client_id: ClientId, channel_id: u8, message: Bytes) -> Option<Bytes> {
+        const NONCE_SIZE: usize = 24;
+        if message.len() < NONCE_SIZE {
+            debug!("discarding message from `{client_id:?}` that is too short to be encrypted");
+            return None;
+        }
+
+        let (nonce, ciphertext) = message.split_at(NONCE_SIZE);
+        let aad = associated_data(channel_id, client_id == ClientId::SERVER);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .inspect_err(|_| debug!("unable to decrypt message from `{client_id:?}`"))
+            .ok()?;
+
+        Some(plaintext.into())
+    }
+}
+
+/// Returns data that is authenticated together with the message.
+///
+/// `from_server` is used to reject messages reflected back to the sender.
+fn associated_data(channel_id: u8, from_server: bool) -> [u8; 2] {
+    [channel_id, from_server as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let encryption = PskEncryption::new(&KEY);
+        let message = Bytes::from_static(b"message");
+
+        let encrypted =
+            OutgoingTransform::transform(&encryption, CLIENT_ID, CHANNEL_ID, message.clone());
+        assert_ne!(encrypted, message);
+
+        let decrypted =
+            IncomingTransform::transform(&encryption, ClientId::SERVER, CHANNEL_ID, encrypted);
+        assert_eq!(decrypted, Some(message));
+    }
+
+    #[test]
+    fn rejection() {
+        let encryption = PskEncryption::new(&KEY);
+        let encrypted = OutgoingTransform::transform(
+            &encryption,
+            CLIENT_ID,
+            CHANNEL_ID,
+            Bytes::from_static(b"message"),
+        );
+
+        let other_key = PskEncryption::new(&[1; 32]);
+        assert!(IncomingTransform::transform(
+            &other_key,
+            ClientId::SERVER,
+            CHANNEL_ID,
+            encrypted.clone()
+        )
+        .is_none());
+        assert!(
+            IncomingTransform::transform(
+                &encryption,
+                ClientId::SERVER,
+                CHANNEL_ID + 1,
+                encrypted.clone()
+            )
+            .is_none(),
+            "message shouldn't be accepted on another channel"
+        );
+        assert!(
+            IncomingTransform::transform(&encryption, CLIENT_ID, CHANNEL_ID, encrypted).is_none(),
+            "message shouldn't be accepted when reflected to the sender"
+        );
+    }
+
+    const KEY: [u8; 32] = [0; 32];
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const CHANNEL_ID: u8 = 0;
+}
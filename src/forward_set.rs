@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+/// Sets for systems that pass messages between renet and Replicon.
+///
+/// In [`PreUpdate`] [`Self::Receive`] runs inside [`ServerSet::ReceivePackets`](bevy_replicon::prelude::ServerSet::ReceivePackets)
+/// and [`ClientSet::ReceivePackets`](bevy_replicon::prelude::ClientSet::ReceivePackets), which run after
+/// [`RenetReceive`](bevy_renet::RenetReceive). In [`PostUpdate`] [`Self::Send`] runs inside
+/// [`ServerSet::SendPackets`](bevy_replicon::prelude::ServerSet::SendPackets)
+/// and [`ClientSet::SendPackets`](bevy_replicon::prelude::ClientSet::SendPackets), which run before
+/// [`RenetSend`](bevy_renet::RenetSend).
+///
+/// Use them to interleave systems that share the renet connection with Replicon, like voice chat or custom protocols.
+/// For example, to read a custom channel before messages are forwarded, order a system with
+/// `.after(RenetReceive).before(ForwardSet::Receive)`. To send after Replicon's messages, but in the same frame,
+/// use `.after(ForwardSet::Send).before(RenetSend)`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForwardSet {
+    /// Passes messages received by renet to Replicon.
+    Receive,
+    /// Passes messages sent by Replicon to renet.
+    Send,
+}
@@ -0,0 +1,192 @@
+use std::mem;
+
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+/// Serialized metadata that is exchanged right after connecting.
+///
+/// Can contain anything, like the game version, capabilities or preferred settings.
+/// Sent as is, so use any serialization format.
+///
+/// Insert it on both server and client to enable the handshake. Requires [`ControlChannel`](crate::ControlChannel),
+/// which carries the metadata. After connecting, the client sends its metadata and the server replies with its own.
+/// The server emits [`ServerEvent::ClientConnected`](bevy_replicon::prelude::ServerEvent::ClientConnected)
+/// only after receiving the client metadata, so Replicon doesn't start replication before it. Received metadata is available via
+/// [`ClientMetadata`](crate::ClientMetadata) on the server and [`ServerMetadata`](crate::ServerMetadata)
+/// on the client.
+///
+/// Since metadata is received before the client is passed to Replicon, it can be used to decide
+/// whether to authorize the client (see the "Connection events" section in the crate docs)
+/// or to reject it by disconnecting. To let independent plugins negotiate their own data,
+/// register [`HandshakeExtensions`].
+///
+/// Clients that never send metadata (for example, older versions without the handshake) stay connected
+/// in renet, but never reach Replicon, so disconnect them by timeout if needed.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Default, Debug, Clone, Deref, DerefMut)]
+pub struct HandshakeMetadata(pub Bytes);
+
+/// App-specific data exchanged during the handshake alongside [`HandshakeMetadata`].
+///
+/// Registered via [`HandshakeExtensions::add`].
+pub trait HandshakeExtension: Send + Sync + 'static {
+    /// Returns the data that will be sent to the other side.
+    fn write(&self) -> Vec<u8>;
+
+    /// Processes the data received from the other side.
+    ///
+    /// Returning `false` rejects the other side, which disconnects the client.
+    ///
+    /// `client_id` is the sending client on the server and [`ClientId::SERVER`] on the client.
+    fn read(&self, client_id: ClientId, data: &[u8]) -> bool;
+}
+
+/// Extensions that turn the handshake into a negotiation point for independent features.
+///
+/// Unlike [`HandshakeMetadata`], which is owned by the app, extensions can be registered by separate
+/// plugins, like feature flags or protocol capabilities, without knowing about each other. The crate
+/// handles the framing: the message contains the metadata followed by the data of each extension
+/// in the order they were registered, so extensions should be registered identically on both client and server.
+///
+/// The data of a single extension is limited to [`Self::MAX_DATA_SIZE`] bytes. The whole message is sent
+/// as a single reliable message at connect time, so it must also fit into
+/// [`ControlChannel::MAX_MEMORY_USAGE_BYTES`](crate::ControlChannel::MAX_MEMORY_USAGE_BYTES).
+/// Keep it small, since it delays the connection.
+///
+/// Without registered extensions, the metadata is sent as is, so the handshake stays compatible with
+/// peers that don't use extensions.
+#[derive(Resource, Default)]
+pub struct HandshakeExtensions(Vec<Box<dyn HandshakeExtension>>);
+
+impl HandshakeExtensions {
+    /// Maximum size of the data of a single extension.
+    pub const MAX_DATA_SIZE: usize = u16::MAX as usize;
+
+    /// Adds an extension.
+    pub fn add(&mut self, extension: impl HandshakeExtension) -> &mut Self {
+        self.0.push(Box::new(extension));
+        self
+    }
+
+    /// Frames the metadata together with the data of all extensions.
+    ///
+    /// Data that exceeds [`Self::MAX_DATA_SIZE`] is replaced with an empty one.
+    pub(crate) fn write(&self, metadata: &Bytes) -> Bytes {
+        if self.0.is_empty() {
+            return metadata.clone();
+        }
+
+        let mut message = Vec::with_capacity(LEN_SIZE + metadata.len());
+        message.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+        message.extend_from_slice(metadata);
+        for extension in &self.0 {
+            let mut data = extension.write();
+            if data.len() > Self::MAX_DATA_SIZE {
+                error!(
+                    "handshake extension data is {} bytes, but the limit is {}",
+                    data.len(),
+                    Self::MAX_DATA_SIZE
+                );
+                data.clear();
+            }
+            message.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            message.extend_from_slice(&data);
+        }
+
+        message.into()
+    }
+
+    /// Passes the data of each extension to it and returns the metadata.
+    ///
+    /// Returns [`None`] if the message is malformed or rejected by an extension.
+    pub(crate) fn read(&self, client_id: ClientId, message: Bytes) -> Option<Bytes> {
+        if self.0.is_empty() {
+            return Some(message);
+        }
+
+        let (len, rest) = message.split_first_chunk::<LEN_SIZE>()?;
+        let len = u32::from_le_bytes(*len) as usize;
+        let start = LEN_SIZE;
+        let end = start.checked_add(len).filter(|&end| end <= message.len())?;
+        let metadata = message.slice(start..end);
+
+        let mut rest = &rest[len..];
+        for extension in &self.0 {
+            let (len, data) = rest.split_first_chunk::<EXTENSION_LEN_SIZE>()?;
+            let len = u16::from_le_bytes(*len) as usize;
+            if len > data.len() {
+                return None;
+            }
+            let (data, remaining) = data.split_at(len);
+            if !extension.read(client_id, data) {
+                debug!("handshake of `{client_id:?}` rejected by extension");
+                return None;
+            }
+            rest = remaining;
+        }
+
+        Some(metadata)
+    }
+}
+
+const LEN_SIZE: usize = mem::size_of::<u32>();
+const EXTENSION_LEN_SIZE: usize = mem::size_of::<u16>();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions() {
+        let mut extensions = HandshakeExtensions::default();
+        extensions
+            .add(FeatureFlags(vec![1, 2]))
+            .add(FeatureFlags(vec![3]));
+
+        let metadata = Bytes::from_static(&[4, 5]);
+        let message = extensions.write(&metadata);
+        assert_eq!(
+            extensions.read(ClientId::SERVER, message.clone()),
+            Some(metadata)
+        );
+
+        let mut incompatible = HandshakeExtensions::default();
+        incompatible.add(FeatureFlags(vec![6]));
+        assert_eq!(incompatible.read(ClientId::SERVER, message), None);
+    }
+
+    #[test]
+    fn malformed() {
+        let mut extensions = HandshakeExtensions::default();
+        extensions.add(FeatureFlags(Vec::new()));
+
+        for message in [&[][..], &[1, 0, 0, 0], &[0, 0, 0, 0, 1]] {
+            assert_eq!(
+                extensions.read(ClientId::SERVER, Bytes::copy_from_slice(message)),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn without_extensions() {
+        let extensions = HandshakeExtensions::default();
+        let metadata = Bytes::from_static(&[1]);
+        assert_eq!(extensions.write(&metadata), metadata);
+    }
+
+    /// Accepts only peers with the same flags.
+    struct FeatureFlags(Vec<u8>);
+
+    impl HandshakeExtension for FeatureFlags {
+        fn write(&self) -> Vec<u8> {
+            self.0.clone()
+        }
+
+        fn read(&self, _client_id: ClientId, data: &[u8]) -> bool {
+            self.0 == data
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+/// An event with the raw bytes of the first replication message of a connection.
+///
+/// The first message over [`ReplicationChannel::Updates`](bevy_replicon::core::channels::ReplicationChannel::Updates)
+/// contains the initial state of the world for the client, so inspecting it helps to diagnose
+/// clients that join with a wrong initial state. Emitted on the server when the message is passed
+/// to renet for each client and on the client when the message is passed to Replicon, so captures
+/// from both sides can be compared. Bytes are captured as Replicon produced them, before
+/// [`MessageTransforms`](crate::MessageTransforms) on the server and after them on the client.
+///
+/// The message is captured once per connection. To stay bounded in memory, only the first
+/// [`Self::MAX_BYTES`] are kept.
+///
+/// Only available with the `init_capture` feature. It's intended for debugging and should be disabled
+/// in release builds.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct InitMessageCaptured {
+    /// Client to which the message was sent on the server or [`ClientId::SERVER`] on the client.
+    pub peer: ClientId,
+
+    /// Beginning of the message, at most [`Self::MAX_BYTES`].
+    pub message: Bytes,
+
+    /// Size of the whole message.
+    pub len: usize,
+}
+
+impl InitMessageCaptured {
+    /// Maximum number of captured bytes.
+    pub const MAX_BYTES: usize = 64 * 1024;
+
+    /// Returns `true` if the message was larger than [`Self::MAX_BYTES`].
+    pub fn is_truncated(&self) -> bool {
+        self.len > self.message.len()
+    }
+
+    /// Captures the message, copying its beginning if it's too large to avoid keeping the whole buffer alive.
+    pub(crate) fn new(peer: ClientId, message: &Bytes) -> Self {
+        let captured = if message.len() > Self::MAX_BYTES {
+            Bytes::copy_from_slice(&message[..Self::MAX_BYTES])
+        } else {
+            message.clone()
+        };
+
+        debug!(
+            "captured init message of {} bytes for `{peer:?}`",
+            message.len()
+        );
+
+        Self {
+            peer,
+            message: captured,
+            len: message.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated() {
+        let event = InitMessageCaptured::new(ClientId::SERVER, &Bytes::from_static(&[0; 4]));
+        assert_eq!(event.len, 4);
+        assert!(!event.is_truncated());
+
+        let message = vec![0; InitMessageCaptured::MAX_BYTES + 1].into();
+        let event = InitMessageCaptured::new(ClientId::SERVER, &message);
+        assert_eq!(event.message.len(), InitMessageCaptured::MAX_BYTES);
+        assert_eq!(event.len, InitMessageCaptured::MAX_BYTES + 1);
+        assert!(event.is_truncated());
+    }
+}
@@ -28,14 +28,16 @@ It's also possible to do it at runtime via [`PluginGroupBuilder::disable()`].
 For server disable [`RepliconRenetClientPlugin`].
 For client disable [`RepliconRenetServerPlugin`].
 
+To adapt generic code, like a shared lobby crate, to the roles enabled by these features, use [`compiled_roles`].
+
 Plugins in [`RepliconRenetPlugins`] automatically add `renet` plugins, you don't need to add them.
 If the `renet_transport` feature is enabled, netcode plugins will also be automatically added.
 
 ## Server and client creation
 
 To connect to the server or create it, you need to initialize the
-[`RenetClient`](renet::RenetClient) and [`NetcodeClientTransport`](bevy_renet::netcode::NetcodeClientTransport) **or**
-[`RenetServer`](renet::RenetServer) and [`NetcodeServerTransport`](bevy_renet::netcode::NetcodeServerTransport)
+[`RenetClient`](renet::RenetClient) and `NetcodeClientTransport` **or**
+[`RenetServer`](renet::RenetServer) and `NetcodeServerTransport`
 resources from Renet.
 
 For steam transport you need to activate the corresponding and use its transport resource instead.
@@ -65,6 +67,114 @@ let connection_config = ConnectionConfig {
 For a full example of how to initialize a server or client see the example in the
 repository.
 
+## IPv6
+
+With the `renet_netcode` feature the crate provides `bind_dual_stack` to create a server socket
+that accepts both IPv6 and IPv4 clients and `bind_client_socket` to create a client socket
+that matches the family of the server address.
+
+Keep in mind the following platform caveats:
+
+- Dual-stack sockets depend on the `IPV6_V6ONLY` option. `bind_dual_stack` disables it explicitly,
+  but some platforms (for example, OpenBSD) don't support it at all. In this case the function falls back
+  to an IPv4-only socket.
+- IPv4 clients connected to a dual-stack socket are reported by the transport as IPv4-mapped IPv6
+  addresses (`::ffff:a.b.c.d`).
+- With `ServerAuthentication::Secure` the connect token contains the server public addresses,
+  so list both IPv4 and IPv6 addresses if you expect clients from both families.
+
+## Keep-alive
+
+Renet doesn't expose keep-alive tuning. The netcode transport sends a keep-alive packet whenever nothing was sent
+to the peer for 250 ms, which is a constant in netcode and can't be changed at runtime or by recreating the transport.
+This is frequent enough to keep NAT mappings alive while the app is running, so there is nothing to make more aggressive.
+
+What can be tuned is how long a silent peer stays connected: the `timeout_seconds` of the connect token,
+which is fixed at 15 seconds with `ClientAuthentication::Unsecure`.
+For mobile clients that get suspended in the background, issue a `ConnectToken` with a longer timeout
+and reconnect with a new token (by recreating [`RenetClient`](renet::RenetClient) and the transport) if it expires anyway.
+To save battery while the app keeps running, lower the app update rate, since renet sends packets only when updated,
+or use `PauseWhenUnfocused` from the `window_focus` feature to throttle forwarding.
+
+## Connection events
+
+[`RepliconRenetServerPlugin`] translates renet's [`ServerEvent`](renet::ServerEvent) into Replicon's
+[`ServerEvent`] in [`ServerSet::SendEvents`]. Prefer reading Replicon's event in game logic,
+since it's backend-agnostic and lets you switch to another messaging backend without rewriting
+connection handling.
+
+Connections refused by the server (for example, in [`MaintenanceMode`]) never reach Replicon
+and are reported via [`ClientRejected`] instead. To keep connections waiting instead of refusing
+them while the server is overloaded, use [`AdmissionControl`].
+
+To clean up game data associated with a disconnected client, put your system into [`ClientCleanupSet`].
+This guarantees that the client is still present in [`ConnectedClients`] while your system runs.
+If the cleanup needs to happen later in the frame, use [`DisconnectedClients`] to keep a snapshot
+of the client for one frame.
+
+Replicon doesn't separate connected and authorized clients. For a custom authorization handshake,
+disable [`ServerPlugin::replicate_after_connect`] and send [`StartReplication`] once the client
+is authorized. Until then the client is present only in [`ConnectedClients`], and
+[`ReplicatedClients::get_client`] can be used to check whether it was authorized.
+
+Replicon doesn't represent clients as entities: they are stored in [`ConnectedClients`], and the crate keeps
+its per-client state in resources keyed by [`ClientId`]. So there is no client entity to customize on spawn.
+To spawn a game entity for each player with components like a team or a spawn position, read
+[`ServerEvent::ClientConnected`] in a system in [`ClientSetupSet`]. The set runs right after
+the event is emitted and before Replicon processes the connection in [`ServerSet::Receive`],
+so the entity with all its components exists before the client is added to [`ReplicatedClients`]
+and is included in its first replication message if it's [`Replicated`].
+
+To exchange metadata, like the game version or capabilities, before the client reaches Replicon,
+insert [`HandshakeMetadata`]. The handshake completes before [`ServerEvent::ClientConnected`] is emitted,
+so [`ClientMetadata`] is already available when deciding whether to authorize the client.
+
+All connection lifecycle events are also logged by the server, see [`ConnectionLogLevel`]
+to configure the level.
+
+## Failover
+
+Renet connection state (encryption keys, sequence numbers and unacknowledged messages) lives inside
+the transport and can't be transferred, so the crate doesn't mirror connections to a standby server.
+To fail over, let clients reconnect to another server with the same client ID (for netcode,
+issue a new connect token for it). The standby server will see a regular [`ServerEvent::ClientConnected`]
+and Replicon will send the client the full world state, so identity is preserved, but game state must be
+synchronized between servers by your own means.
+
+To move a connected client to another server, like during a zone handoff, send [`RedirectClient`]
+over [`ControlChannel`]. See [`ClientRedirected`] for what is preserved on the client.
+
+## Message transforms
+
+To add custom integrity or obfuscation layers on top of renet, implement [`OutgoingTransform`]
+and [`IncomingTransform`] and register them in the [`MessageTransforms`] resource.
+See its documentation for the order in which transforms are applied.
+
+With the `encryption` feature the crate provides `PskEncryption` to encrypt messages with
+a pre-shared key independently of the transport.
+
+To debug reordering, register [`OutgoingSequenceTags`] and [`IncomingSequenceTags`] to number
+forwarded messages in trace logs.
+
+For lag compensation, insert [`MessageTimestamps`] to tag client messages with the time and tick at which
+they were sent and read them on the server from [`ReceivedTimestamps`].
+
+## Custom channels
+
+To send data that isn't managed by Replicon, like chat messages, use [`ServerMessaging`] on the server
+and [`ClientMessaging`] on the client. Custom channel IDs must not overlap with IDs of channels from
+[`RepliconChannels`], see [`ServerMessaging`] for details.
+
+Some features of this crate exchange their own protocol messages over [`ControlChannel`].
+Reserve its ID right after Replicon's channels via [`ControlChannel::after`] and start custom channels after it.
+See [channel IDs](ControlChannel#channel-ids) for the rules that apply to all channels of this crate.
+
+## System ordering
+
+Messages are passed between renet and Replicon in [`ForwardSet`], which runs right after renet receives packets
+in [`PreUpdate`] and right before it sends them in [`PostUpdate`]. To share the renet connection with other
+systems, like voice chat, order them relative to [`ForwardSet`] and renet's sets.
+
 ## Replicon conditions
 
 The crate updates the running state of [`RepliconServer`] and connection state of [`RepliconClient`]
@@ -75,13 +185,75 @@ This means that [replicon conditions](bevy_replicon::core::common_conditions) wo
 like [`Startup`]. As a workaround, you can directly check if renet's resources are present. This may be resolved
 in the future once we have [observers for resources](https://github.com/bevyengine/bevy/issues/12231)
 to immediately react to changes.
+
+Instead of combining multiple conditions, you can also match on the [`NetMode`] resource,
+which is updated in the same [`PreUpdate`] phase.
 */
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(any(feature = "client", feature = "server"))]
+mod asset_stream;
+#[cfg(any(feature = "client", feature = "server"))]
+mod channel_check;
+#[cfg(any(feature = "client", feature = "server"))]
+mod channel_close;
+mod channel_policy;
+mod channel_profile;
+#[cfg(any(feature = "client", feature = "server"))]
+mod channel_rate_limit;
+mod channel_remap;
+#[cfg(any(feature = "client", feature = "server"))]
+mod channel_report;
 #[cfg(feature = "client")]
 mod client;
+#[cfg(any(feature = "client", feature = "server"))]
+mod clock_sync;
+#[cfg(any(feature = "client", feature = "server"))]
+mod control;
+#[cfg(all(feature = "diagnostics", any(feature = "client", feature = "server")))]
+mod diagnostics;
+mod disconnect_reason;
+#[cfg(any(feature = "client", feature = "server"))]
+mod echo;
+#[cfg(feature = "encryption")]
+mod encryption;
+mod forward_set;
+#[cfg(any(feature = "client", feature = "server"))]
+mod handshake;
+#[cfg(all(feature = "init_capture", any(feature = "client", feature = "server")))]
+mod init_capture;
+#[cfg(all(feature = "metrics", any(feature = "client", feature = "server")))]
+mod metrics_export;
+#[cfg(any(feature = "client", feature = "server"))]
+mod net_mode;
+#[cfg(any(feature = "client", feature = "server"))]
+mod network_activity;
+#[cfg(feature = "renet_netcode")]
+mod protocol_id;
+#[cfg(any(feature = "client", feature = "server"))]
+mod receive_limit;
+#[cfg(any(feature = "client", feature = "server"))]
+mod redirect;
+mod reliable_channel;
+mod roles;
+mod sequence_tag;
 #[cfg(feature = "server")]
 mod server;
+#[cfg(feature = "renet_netcode")]
+mod socket;
+#[cfg(feature = "test_support")]
+mod test_support;
+#[cfg(any(feature = "client", feature = "server"))]
+mod timestamp;
+mod transform;
+#[cfg(any(feature = "client", feature = "server"))]
+mod transport;
+#[cfg(any(feature = "renet_netcode", feature = "renet_steam"))]
+mod transport_condition;
+#[cfg(all(feature = "renet_netcode", any(feature = "client", feature = "server")))]
+mod transport_error;
+#[cfg(any(feature = "client", feature = "server"))]
+mod unknown_channel;
 
 #[cfg(feature = "renet_netcode")]
 pub use bevy_renet::netcode;
@@ -89,10 +261,136 @@ pub use bevy_renet::renet;
 #[cfg(feature = "renet_steam")]
 pub use bevy_renet::steam;
 
+#[cfg(any(feature = "client", feature = "server"))]
+pub use asset_stream::AssetStreamChannel;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use channel_check::ConfiguredChannels;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use channel_close::{ChannelState, ClosedChannels};
+pub use channel_policy::{ChannelDefaults, ChannelGroup, ChannelPolicy};
+pub use channel_profile::{ChannelCountMismatch, ChannelProfile, ChannelSettings};
+#[cfg(any(feature = "client", feature = "server"))]
+pub use channel_rate_limit::ChannelRateLimit;
+pub use channel_remap::ChannelRemap;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use channel_report::{ChannelDirection, ChannelReport};
+#[cfg(all(feature = "client", feature = "window_focus"))]
+pub use client::focus_pause::{PauseWhenUnfocused, ReplicationPaused, ReplicationResumed};
+#[cfg(all(feature = "client", feature = "renet_netcode"))]
+pub use client::server_probe::{
+    ProbeResult, SelectionStrategy, ServerCandidates, ServerProbeFailed, ServerSelected,
+};
+#[cfg(all(feature = "client", feature = "renet_netcode"))]
+pub use client::transport_error::ClientTransportError;
 #[cfg(feature = "client")]
-pub use client::RepliconRenetClientPlugin;
+pub use client::{
+    asset_stream::{AssetProgress, AssetReceived, MaxAssetSize},
+    assigned_id::AssignedClientId,
+    channel_report::ClientChannelReport,
+    clock_sync::ClockSync,
+    echo::EventEchoed,
+    half_open::{HalfOpenDetection, SuspectedHalfOpen},
+    handshake::ServerMetadata,
+    kick::ClientKicked,
+    manual_flush::{flush_client_messages, ManualFlush},
+    messaging::ClientMessaging,
+    network_info::ClientNetworkInfo,
+    pending_messages::PendingMessages,
+    redirect::{ClientRedirected, RedirectConnector},
+    reliable_receive::ReliableMessage,
+    status_history::{StatusHistory, StatusTransition},
+    RepliconRenetClientPlugin,
+};
+#[cfg(any(feature = "client", feature = "server"))]
+pub use clock_sync::ClockSyncChannel;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use control::ControlChannel;
+#[cfg(all(feature = "diagnostics", any(feature = "client", feature = "server")))]
+pub use diagnostics::{ForwardingTimings, MessageCountDiagnostics, TimingWindow};
+pub use disconnect_reason::RepliconDisconnectReason;
+#[cfg(feature = "encryption")]
+pub use encryption::PskEncryption;
+pub use forward_set::ForwardSet;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use handshake::{HandshakeExtension, HandshakeExtensions, HandshakeMetadata};
+#[cfg(all(feature = "init_capture", any(feature = "client", feature = "server")))]
+pub use init_capture::InitMessageCaptured;
+#[cfg(all(feature = "metrics", any(feature = "client", feature = "server")))]
+pub use metrics_export::ConnectionMetrics;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use net_mode::{NetMode, ServerHasLocalPlayer};
+#[cfg(any(feature = "client", feature = "server"))]
+pub use network_activity::NetworkActivity;
+#[cfg(feature = "renet_netcode")]
+pub use protocol_id::protocol_id;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use receive_limit::ReceiveLimit;
+pub use reliable_channel::ReliableSendChannel;
+pub use roles::{compiled_roles, Roles};
+pub use sequence_tag::{IncomingSequenceTags, OutgoingSequenceTags};
 #[cfg(feature = "server")]
-pub use server::RepliconRenetServerPlugin;
+pub use server::{
+    admission::AdmissionControl,
+    asset_stream::AssetStreams,
+    backpressure::SendBackpressure,
+    bandwidth::ServerBandwidth,
+    capacity::{server_full, MaxClients, ServerFull, ServerHasCapacity},
+    channel_report::ServerChannelReport,
+    client_id_map::{ClientIdMap, ClientIdMismatch},
+    client_transport::{ClientTransport, ClientTransports},
+    congestion::CongestionSignal,
+    connection_log::ConnectionLogLevel,
+    connection_phase::{ConnectionPhase, ConnectionPhases},
+    connection_stats::ConnectionStats,
+    disconnect::ClientDisconnected,
+    disconnect_grace::{
+        ClientGraceExpired, ClientReconnected, DisconnectGrace, ReconnectingClients,
+    },
+    disconnected_clients::{DisconnectedClient, DisconnectedClients},
+    echo::EchoClientEvents,
+    fixed_send::SendOnFixedTick,
+    flush_priority::FlushPriority,
+    handshake::ClientMetadata,
+    idle::{IdleDisconnect, IdleTimeout, LastReceived},
+    jitter_buffer::JitterBuffers,
+    kick::KickClient,
+    lobby::LobbyMode,
+    maintenance::MaintenanceMode,
+    max_message_size::{MaxMessageSize, OversizedMessage, OversizedMessageAction},
+    memory_budget::{BudgetShare, TotalMemoryBudget},
+    messaging::ServerMessaging,
+    parallel_forwarding::ParallelForwarding,
+    pending_limit::MaxPendingConnections,
+    pending_messages::ClientPendingMessages,
+    rate_limit::ConnectionRateLimit,
+    redirect::RedirectClient,
+    rejection::{ClientRejected, RejectReason},
+    reliable_send::{MessageAcked, MessageHandle, MessageStatus, ReliableSend},
+    shutdown::{GracefulShutdown, ShutdownReport},
+    strict_send::StrictSend,
+    timestamp::{MessageTimestamp, ReceivedTimestamps},
+    ClientCleanupSet, ClientSetupSet, RepliconRenetServerPlugin,
+};
+#[cfg(all(feature = "server", feature = "renet_netcode"))]
+pub use server::{
+    client_addrs::ClientAddrs, multi_socket::MultiSocketServerTransport,
+    transport_error::ServerTransportError, user_data_auth::AuthorizeByUserData,
+};
+#[cfg(feature = "renet_netcode")]
+pub use socket::{bind_client_socket, bind_dual_stack};
+#[cfg(feature = "test_support")]
+pub use test_support::{
+    assert_converged, assert_converged_local, connect_now, connect_now_local, LOCAL_CONNECT_TICKS,
+};
+#[cfg(any(feature = "client", feature = "server"))]
+pub use timestamp::MessageTimestamps;
+pub use transform::{IncomingTransform, MessageTransforms, OutgoingTransform};
+#[cfg(feature = "renet_netcode")]
+pub use transport_condition::netcode_transport_active;
+#[cfg(feature = "renet_steam")]
+pub use transport_condition::steam_transport_active;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use unknown_channel::{UnknownChannelAction, UnknownChannelPolicy};
 
 use bevy::{app::PluginGroupBuilder, prelude::*};
 use bevy_replicon::prelude::*;
@@ -124,44 +422,163 @@ impl PluginGroup for RepliconRenetPlugins {
 }
 
 /// External trait for [`RepliconChannels`] to provide convenient conversion into renet channel configs.
+///
+/// Each Replicon channel is converted into its own renet channel with a separate memory budget.
+/// Replicon sends entity spawns, insertions and removals (including the full world state
+/// for a newly connected client) over its first server channel, which is reliable, while
+/// component mutations go over a separate unreliable channel. So a large initial download
+/// doesn't share a budget with steady-state mutations.
+///
+/// You can tune the returned configs before creating [`ConnectionConfig`](renet::ConnectionConfig).
+/// For example, to give the reliable replication channel more room on constrained links:
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::RenetChannelsExt;
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let mut server_configs = channels.get_server_configs();
+/// server_configs[0].max_memory_usage_bytes = 10 * 1024 * 1024;
+/// ```
+///
+/// To tune replication and event channels separately without relying on indices,
+/// use [`RenetChannelsExt::get_server_configs_with`] with a [`ChannelPolicy`].
+///
+/// Messages within the same Replicon channel can't be split across renet channels by this crate,
+/// since Replicon decides which channel each message goes to.
+///
+/// This also means that replication can't be striped across multiple parallel ordered channels.
+/// Replicon's reliable replication messages depend on each other (an insertion can reference an entity
+/// spawned in an earlier message), so delivering them out of order would break the client world.
+/// To reduce head-of-line blocking, send large non-replication data, like events, over separate channels
+/// created via [`RepliconChannels::create_server_channel`]. Each of them becomes its own renet channel,
+/// so a stall on one doesn't block the others. Keep in mind that each channel gets its own
+/// `max_memory_usage_bytes`, so the total memory budget grows with the number of channels.
+///
+/// Renet doesn't allow changing channels of an existing connection, and both sides must use the same
+/// channels. Registering replicated components doesn't create channels, only events and direct calls to
+/// [`RepliconChannels`] do. So if new events can be loaded at runtime (for example, with DLCs), register
+/// them at startup or disconnect clients, update configs on both sides and let clients reconnect.
+///
+/// Configs must be created after all events are registered. Insert [`ConfiguredChannels`]
+/// along with the transport to catch channels registered too late in debug builds.
 pub trait RenetChannelsExt {
     /// Returns server channel configs that can be used to create [`ConnectionConfig`](renet::ConnectionConfig).
     fn get_server_configs(&self) -> Vec<ChannelConfig>;
 
     /// Same as [`RenetChannelsExt::get_server_configs`], but for clients.
     fn get_client_configs(&self) -> Vec<ChannelConfig>;
+
+    /// Like [`RenetChannelsExt::get_server_configs`], but applies separate defaults
+    /// for replication and event channels from the policy.
+    fn get_server_configs_with(&self, policy: &ChannelPolicy) -> Vec<ChannelConfig>;
+
+    /// Same as [`RenetChannelsExt::get_server_configs_with`], but for clients.
+    fn get_client_configs_with(&self, policy: &ChannelPolicy) -> Vec<ChannelConfig>;
+
+    /// Like [`RenetChannelsExt::get_server_configs`], but returns configs only for channels from the group.
+    ///
+    /// The same group needs to be inserted as a resource, see [`ChannelGroup`] for details.
+    fn get_server_configs_for(&self, group: ChannelGroup) -> Vec<ChannelConfig>;
+
+    /// Same as [`RenetChannelsExt::get_server_configs_for`], but for clients.
+    fn get_client_configs_for(&self, group: ChannelGroup) -> Vec<ChannelConfig>;
+
+    /// Like [`RenetChannelsExt::get_server_configs`], but overrides settings with
+    /// [`ChannelProfile::server`].
+    ///
+    /// Returns an error if the profile doesn't contain settings for each channel.
+    fn get_server_configs_from(
+        &self,
+        profile: &ChannelProfile,
+    ) -> Result<Vec<ChannelConfig>, ChannelCountMismatch>;
+
+    /// Same as [`RenetChannelsExt::get_server_configs_from`], but for clients.
+    fn get_client_configs_from(
+        &self,
+        profile: &ChannelProfile,
+    ) -> Result<Vec<ChannelConfig>, ChannelCountMismatch>;
 }
 
 impl RenetChannelsExt for RepliconChannels {
     fn get_server_configs(&self) -> Vec<ChannelConfig> {
-        create_configs(self.server_channels(), self.default_max_bytes)
+        self.get_server_configs_with(&Default::default())
     }
 
     fn get_client_configs(&self) -> Vec<ChannelConfig> {
-        create_configs(self.client_channels(), self.default_max_bytes)
+        self.get_client_configs_with(&Default::default())
+    }
+
+    fn get_server_configs_with(&self, policy: &ChannelPolicy) -> Vec<ChannelConfig> {
+        let configs = create_configs(self.server_channels(), self.default_max_bytes, policy);
+        debug!("creating server channel configs `{configs:?}`");
+        configs
+    }
+
+    fn get_client_configs_with(&self, policy: &ChannelPolicy) -> Vec<ChannelConfig> {
+        let configs = create_configs(self.client_channels(), self.default_max_bytes, policy);
+        debug!("creating client channel configs `{configs:?}`");
+        configs
+    }
+
+    fn get_server_configs_for(&self, group: ChannelGroup) -> Vec<ChannelConfig> {
+        let mut configs = self.get_server_configs();
+        configs.retain(|config| group.contains(config.channel_id));
+        configs
+    }
+
+    fn get_client_configs_for(&self, group: ChannelGroup) -> Vec<ChannelConfig> {
+        let mut configs = self.get_client_configs();
+        configs.retain(|config| group.contains(config.channel_id));
+        configs
+    }
+
+    fn get_server_configs_from(
+        &self,
+        profile: &ChannelProfile,
+    ) -> Result<Vec<ChannelConfig>, ChannelCountMismatch> {
+        let mut configs = self.get_server_configs();
+        channel_profile::apply(&mut configs, &profile.server)?;
+        Ok(configs)
+    }
+
+    fn get_client_configs_from(
+        &self,
+        profile: &ChannelProfile,
+    ) -> Result<Vec<ChannelConfig>, ChannelCountMismatch> {
+        let mut configs = self.get_client_configs();
+        channel_profile::apply(&mut configs, &profile.client)?;
+        Ok(configs)
     }
 }
 
 /// Converts replicon channels into renet channel configs.
-fn create_configs(channels: &[RepliconChannel], default_max_bytes: usize) -> Vec<ChannelConfig> {
+pub(crate) fn create_configs(
+    channels: &[RepliconChannel],
+    default_max_bytes: usize,
+    policy: &ChannelPolicy,
+) -> Vec<ChannelConfig> {
     let mut channel_configs = Vec::with_capacity(channels.len());
     for (index, channel) in channels.iter().enumerate() {
+        let defaults = policy.defaults(index);
+        let resend_time = defaults.resend_time.unwrap_or(channel.resend_time);
         let send_type = match channel.kind {
             ChannelKind::Unreliable => SendType::Unreliable,
-            ChannelKind::Unordered => SendType::ReliableUnordered {
-                resend_time: channel.resend_time,
-            },
-            ChannelKind::Ordered => SendType::ReliableOrdered {
-                resend_time: channel.resend_time,
-            },
+            ChannelKind::Unordered => SendType::ReliableUnordered { resend_time },
+            ChannelKind::Ordered => SendType::ReliableOrdered { resend_time },
         };
+        let max_bytes = channel
+            .max_bytes
+            .or(defaults.max_bytes)
+            .unwrap_or(default_max_bytes);
         let config = ChannelConfig {
             channel_id: index as u8,
-            max_memory_usage_bytes: channel.max_bytes.unwrap_or(default_max_bytes),
+            max_memory_usage_bytes: max_bytes,
             send_type,
         };
-
-        debug!("creating channel config `{config:?}`");
         channel_configs.push(config);
     }
     channel_configs
@@ -0,0 +1,342 @@
+use bevy::prelude::*;
+#[cfg(feature = "client")]
+use bevy_renet::renet::RenetClient;
+#[cfg(feature = "server")]
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+#[cfg(feature = "server")]
+use metrics::{counter, describe_counter};
+use metrics::{describe_gauge, gauge, Unit};
+
+#[cfg(feature = "client")]
+use crate::ClientNetworkInfo;
+#[cfg(feature = "server")]
+use crate::{ClientDisconnected, ClientRejected, ServerBandwidth};
+
+/// Plugin to export connection statistics via the [`metrics`] crate.
+///
+/// Values are recorded every frame in [`Last`] through the `metrics` macros, so any installed
+/// exporter, like `metrics-exporter-prometheus`, picks them up. Install the recorder before adding
+/// this plugin, otherwise metric descriptions are lost. Should be added after [`RepliconRenetPlugins`](crate::RepliconRenetPlugins).
+///
+/// Server metrics are recorded while [`RenetServer`] exists:
+///
+/// | Name                                             | Type    | Labels      |
+/// |--------------------------------------------------|---------|-------------|
+/// | `renet_server_connected_clients`                 | gauge   |             |
+/// | `renet_server_sent_bytes_per_second`             | gauge   |             |
+/// | `renet_server_received_bytes_per_second`         | gauge   |             |
+/// | `renet_server_client_rtt_seconds`                | gauge   | `client_id` |
+/// | `renet_server_client_packet_loss`                | gauge   | `client_id` |
+/// | `renet_server_client_sent_bytes_per_second`      | gauge   | `client_id` |
+/// | `renet_server_client_received_bytes_per_second`  | gauge   | `client_id` |
+/// | `renet_server_connections_total`                 | counter |             |
+/// | `renet_server_disconnections_total`              | counter | `reason`    |
+/// | `renet_server_rejections_total`                  | counter |             |
+///
+/// `client_id` is the [`ClientId`] of the connected client. The `metrics` crate has no way to remove
+/// a metric, so per-client metrics of disconnected clients stop updating, but stay in the exporter.
+/// Configure the exporter's idle timeout to drop them. `reason` is [`RepliconDisconnectReason::as_str`](crate::RepliconDisconnectReason::as_str).
+/// For the same counters inside the app, see [`ConnectionStats`](crate::ConnectionStats).
+///
+/// Client metrics are recorded while [`RenetClient`](bevy_renet::renet::RenetClient) is connected:
+///
+/// | Name                                       | Type  |
+/// |--------------------------------------------|-------|
+/// | `renet_client_rtt_seconds`                 | gauge |
+/// | `renet_client_packet_loss`                 | gauge |
+/// | `renet_client_sent_bytes_per_second`       | gauge |
+/// | `renet_client_received_bytes_per_second`   | gauge |
+///
+/// Unlike Bevy diagnostics, these values target external monitoring instead of in-game overlays.
+pub struct ConnectionMetrics;
+
+impl Plugin for ConnectionMetrics {
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "server")]
+        {
+            describe_gauge!(
+                Self::SERVER_CONNECTED_CLIENTS,
+                "Number of connected clients"
+            );
+            describe_gauge!(
+                Self::SERVER_SENT_BPS,
+                "Bytes per second sent to all clients"
+            );
+            describe_gauge!(
+                Self::SERVER_RECEIVED_BPS,
+                "Bytes per second received from all clients"
+            );
+            describe_gauge!(
+                Self::SERVER_CLIENT_RTT,
+                Unit::Seconds,
+                "Round-trip time of the client"
+            );
+            describe_gauge!(
+                Self::SERVER_CLIENT_PACKET_LOSS,
+                "Ratio of lost packets of the client from 0 to 1"
+            );
+            describe_gauge!(
+                Self::SERVER_CLIENT_SENT_BPS,
+                "Bytes per second sent to the client"
+            );
+            describe_gauge!(
+                Self::SERVER_CLIENT_RECEIVED_BPS,
+                "Bytes per second received from the client"
+            );
+            describe_counter!(Self::SERVER_CONNECTIONS, "Number of accepted clients");
+            describe_counter!(
+                Self::SERVER_DISCONNECTIONS,
+                "Number of disconnected clients"
+            );
+            describe_counter!(Self::SERVER_REJECTIONS, "Number of rejected clients");
+
+            app.add_systems(
+                Last,
+                Self::record_server.run_if(resource_exists::<RenetServer>),
+            );
+        }
+
+        #[cfg(feature = "client")]
+        {
+            describe_gauge!(
+                Self::CLIENT_RTT,
+                Unit::Seconds,
+                "Round-trip time to the server"
+            );
+            describe_gauge!(
+                Self::CLIENT_PACKET_LOSS,
+                "Ratio of lost packets from 0 to 1"
+            );
+            describe_gauge!(Self::CLIENT_SENT_BPS, "Bytes per second sent to the server");
+            describe_gauge!(
+                Self::CLIENT_RECEIVED_BPS,
+                "Bytes per second received from the server"
+            );
+
+            app.add_systems(
+                Last,
+                Self::record_client.run_if(resource_exists::<RenetClient>),
+            );
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl ConnectionMetrics {
+    const SERVER_CONNECTED_CLIENTS: &'static str = "renet_server_connected_clients";
+    const SERVER_SENT_BPS: &'static str = "renet_server_sent_bytes_per_second";
+    const SERVER_RECEIVED_BPS: &'static str = "renet_server_received_bytes_per_second";
+    const SERVER_CLIENT_RTT: &'static str = "renet_server_client_rtt_seconds";
+    const SERVER_CLIENT_PACKET_LOSS: &'static str = "renet_server_client_packet_loss";
+    const SERVER_CLIENT_SENT_BPS: &'static str = "renet_server_client_sent_bytes_per_second";
+    const SERVER_CLIENT_RECEIVED_BPS: &'static str =
+        "renet_server_client_received_bytes_per_second";
+    const SERVER_CONNECTIONS: &'static str = "renet_server_connections_total";
+    const SERVER_DISCONNECTIONS: &'static str = "renet_server_disconnections_total";
+    const SERVER_REJECTIONS: &'static str = "renet_server_rejections_total";
+
+    fn record_server(
+        mut server_events: EventReader<ServerEvent>,
+        mut disconnect_events: EventReader<ClientDisconnected>,
+        mut rejected_events: EventReader<ClientRejected>,
+        connected_clients: Res<ConnectedClients>,
+        bandwidth: Res<ServerBandwidth>,
+        renet_server: Res<RenetServer>,
+    ) {
+        gauge!(Self::SERVER_CONNECTED_CLIENTS).set(connected_clients.len() as f64);
+        gauge!(Self::SERVER_SENT_BPS).set(bandwidth.sent_bps);
+        gauge!(Self::SERVER_RECEIVED_BPS).set(bandwidth.received_bps);
+
+        for client in connected_clients.iter() {
+            let Ok(info) = renet_server.network_info(client.id().get()) else {
+                continue;
+            };
+
+            let client_id = client.id().get().to_string();
+            gauge!(Self::SERVER_CLIENT_RTT, "client_id" => client_id.clone()).set(info.rtt);
+            gauge!(Self::SERVER_CLIENT_PACKET_LOSS, "client_id" => client_id.clone())
+                .set(info.packet_loss);
+            gauge!(Self::SERVER_CLIENT_SENT_BPS, "client_id" => client_id.clone())
+                .set(info.bytes_sent_per_second);
+            gauge!(Self::SERVER_CLIENT_RECEIVED_BPS, "client_id" => client_id)
+                .set(info.bytes_received_per_second);
+        }
+
+        for event in server_events.read() {
+            if let ServerEvent::ClientConnected { .. } = event {
+                counter!(Self::SERVER_CONNECTIONS).increment(1);
+            }
+        }
+
+        for event in disconnect_events.read() {
+            counter!(Self::SERVER_DISCONNECTIONS, "reason" => event.reason.as_str()).increment(1);
+        }
+
+        let rejections = rejected_events.read().count();
+        if rejections != 0 {
+            counter!(Self::SERVER_REJECTIONS).increment(rejections as u64);
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl ConnectionMetrics {
+    const CLIENT_RTT: &'static str = "renet_client_rtt_seconds";
+    const CLIENT_PACKET_LOSS: &'static str = "renet_client_packet_loss";
+    const CLIENT_SENT_BPS: &'static str = "renet_client_sent_bytes_per_second";
+    const CLIENT_RECEIVED_BPS: &'static str = "renet_client_received_bytes_per_second";
+
+    fn record_client(client: Res<RepliconClient>, network_info: Res<ClientNetworkInfo>) {
+        if !client.is_connected() {
+            return;
+        }
+
+        gauge!(Self::CLIENT_RTT).set(network_info.rtt);
+        gauge!(Self::CLIENT_PACKET_LOSS).set(network_info.packet_loss);
+        gauge!(Self::CLIENT_SENT_BPS).set(network_info.bytes_sent_per_second);
+        gauge!(Self::CLIENT_RECEIVED_BPS).set(network_info.bytes_received_per_second);
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    };
+
+    use bevy::{ecs::schedule::ExecutorKind, utils::HashMap};
+    use bevy_renet::renet::ConnectionConfig;
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Label, Metadata, Recorder, SharedString,
+    };
+
+    use super::*;
+    use crate::{RejectReason, RepliconDisconnectReason};
+
+    #[test]
+    fn server_counters() {
+        let mut app = App::new();
+        app.add_event::<ServerEvent>()
+            .add_event::<ClientDisconnected>()
+            .add_event::<ClientRejected>()
+            .init_resource::<ConnectedClients>()
+            .init_resource::<ServerBandwidth>()
+            .insert_resource(RenetServer::new(ConnectionConfig::default()))
+            .edit_schedule(Last, |schedule| {
+                // Local recorder is available only on the current thread.
+                schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+            });
+
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || app.add_plugins(ConnectionMetrics));
+
+        app.world_mut().send_event(ServerEvent::ClientConnected {
+            client_id: CLIENT_ID,
+        });
+        app.world_mut().send_event(ClientDisconnected {
+            client_id: CLIENT_ID,
+            reason: RepliconDisconnectReason::Kicked,
+        });
+        app.world_mut().send_event(ClientRejected {
+            client_id: CLIENT_ID,
+            reason: RejectReason::Maintenance,
+        });
+        app.world_mut().send_event(ClientRejected {
+            client_id: CLIENT_ID,
+            reason: RejectReason::Maintenance,
+        });
+
+        metrics::with_local_recorder(&recorder, || app.update());
+
+        assert_eq!(
+            recorder.value(ConnectionMetrics::SERVER_CONNECTIONS, &[]),
+            Some(1)
+        );
+        assert_eq!(
+            recorder.value(
+                ConnectionMetrics::SERVER_DISCONNECTIONS,
+                &[("reason", "kicked")]
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            recorder.value(ConnectionMetrics::SERVER_REJECTIONS, &[]),
+            Some(2)
+        );
+        assert_eq!(
+            recorder.value(ConnectionMetrics::SERVER_CONNECTED_CLIENTS, &[]),
+            Some(0.0f64.to_bits())
+        );
+
+        metrics::with_local_recorder(&recorder, || app.update());
+
+        assert_eq!(
+            recorder.value(ConnectionMetrics::SERVER_CONNECTIONS, &[]),
+            Some(1),
+            "events should be counted only once"
+        );
+    }
+
+    /// Stores raw values of counters and gauges.
+    #[derive(Default)]
+    struct TestRecorder(Mutex<HashMap<Key, Arc<AtomicU64>>>);
+
+    impl TestRecorder {
+        fn value(
+            &self,
+            name: &'static str,
+            labels: &[(&'static str, &'static str)],
+        ) -> Option<u64> {
+            let labels: Vec<_> = labels
+                .iter()
+                .map(|&(key, value)| Label::new(key, value))
+                .collect();
+            let key = Key::from_parts(name, labels);
+            self.0
+                .lock()
+                .unwrap()
+                .get(&key)
+                .map(|value| value.load(Ordering::Relaxed))
+        }
+
+        fn register(&self, key: &Key) -> Arc<AtomicU64> {
+            self.0
+                .lock()
+                .unwrap()
+                .entry(key.clone())
+                .or_default()
+                .clone()
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {
+        }
+
+        fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+        fn describe_histogram(
+            &self,
+            _key: KeyName,
+            _unit: Option<Unit>,
+            _description: SharedString,
+        ) {
+        }
+
+        fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.register(key))
+        }
+
+        fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.register(key))
+        }
+
+        fn register_histogram(&self, _key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetClient, RenetServer};
+
+/// Current networking role of the app.
+///
+/// Derived from the presence and state of renet resources in [`PreUpdate`] right after
+/// the crate updates [`RepliconServer`](bevy_replicon::prelude::RepliconServer)
+/// and [`RepliconClient`](bevy_replicon::prelude::RepliconClient) states.
+/// So, like with Replicon conditions, the value is not up to date in schedules like [`Startup`].
+///
+/// Renet can't tell whether the server also has a local player, so [`NetMode::ListenServer`]
+/// is reported only if the [`ServerHasLocalPlayer`] marker resource is present alongside
+/// [`RenetServer`].
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetMode {
+    /// [`RenetServer`] is present without a local player.
+    Server,
+    /// [`RenetServer`] is present and [`ServerHasLocalPlayer`] is inserted.
+    ListenServer,
+    /// [`RenetClient`] is present and connected.
+    Client,
+    /// Neither [`RenetServer`] nor [`RenetClient`] are present.
+    #[default]
+    SinglePlayer,
+    /// [`RenetClient`] is present, but it's still connecting or the connection was lost.
+    Disconnected,
+}
+
+/// Marker resource that indicates that the server also controls a local player.
+///
+/// Insert it together with [`RenetServer`] to get [`NetMode::ListenServer`] instead of [`NetMode::Server`].
+#[derive(Resource, Default)]
+pub struct ServerHasLocalPlayer;
+
+/// Set for [`update_net_mode`] to let plugins order it after their receive sets.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct NetModeSet;
+
+/// Initializes [`NetMode`] if it wasn't initialized by another plugin.
+pub(crate) fn init(app: &mut App) {
+    if app.world().contains_resource::<NetMode>() {
+        return;
+    }
+
+    app.init_resource::<NetMode>()
+        .add_systems(PreUpdate, update_net_mode.in_set(NetModeSet));
+}
+
+fn update_net_mode(
+    mut net_mode: ResMut<NetMode>,
+    renet_server: Option<Res<RenetServer>>,
+    renet_client: Option<Res<RenetClient>>,
+    local_player: Option<Res<ServerHasLocalPlayer>>,
+) {
+    let mode = if renet_server.is_some() {
+        if local_player.is_some() {
+            NetMode::ListenServer
+        } else {
+            NetMode::Server
+        }
+    } else if let Some(renet_client) = renet_client {
+        if renet_client.is_connected() {
+            NetMode::Client
+        } else {
+            NetMode::Disconnected
+        }
+    } else {
+        NetMode::SinglePlayer
+    };
+
+    net_mode.set_if_neq(mode);
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+/// Indicates whether messages were forwarded between renet and Replicon in the current frame.
+///
+/// Flags are reset in [`First`] and set by the forwarding systems in [`PreUpdate`] (receive)
+/// and [`PostUpdate`] (send). So [`Self::received_this_frame`] is up to date in [`Update`],
+/// while [`Self::sent_this_frame`] can be read only after [`PostUpdate`], for example, in [`Last`].
+///
+/// Useful to reduce the update rate or enter a low-power mode when the network is idle.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkActivity {
+    /// At least one message was sent to renet.
+    pub sent_this_frame: bool,
+    /// At least one message was received from renet.
+    pub received_this_frame: bool,
+}
+
+/// Initializes [`NetworkActivity`] if it wasn't initialized by another plugin.
+pub(crate) fn init(app: &mut App) {
+    if app.world().contains_resource::<NetworkActivity>() {
+        return;
+    }
+
+    app.init_resource::<NetworkActivity>()
+        .add_systems(First, reset_activity);
+}
+
+fn reset_activity(mut activity: ResMut<NetworkActivity>) {
+    activity.set_if_neq(Default::default());
+}
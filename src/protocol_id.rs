@@ -0,0 +1,45 @@
+/// Derives a netcode protocol ID from a version string.
+///
+/// Clients can connect only to servers with the same protocol ID, so deriving it from the game
+/// version prevents connections between incompatible builds without bumping the ID manually.
+/// Uses 64-bit FNV-1a, which is stable across platforms and compiler versions.
+///
+/// Netcode silently drops connection requests with a different protocol ID, so the server can't
+/// report them. Such clients will fail with
+/// [`NetcodeDisconnectReason::ConnectionRequestTimedOut`](bevy_renet::netcode::NetcodeDisconnectReason::ConnectionRequestTimedOut).
+/// Consider checking it to suggest the player to update.
+///
+/// # Examples
+///
+/// ```
+/// use bevy_replicon_renet::protocol_id;
+///
+/// const PROTOCOL_ID: u64 = protocol_id(env!("CARGO_PKG_VERSION"));
+/// ```
+pub const fn protocol_id(version: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let bytes = version.as_bytes();
+    let mut hash = OFFSET_BASIS;
+    let mut index = 0;
+    while index < bytes.len() {
+        hash ^= bytes[index] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        index += 1;
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stability() {
+        assert_eq!(protocol_id(""), 0xcbf29ce484222325);
+        assert_eq!(protocol_id("a"), 0xaf63dc4c8601ec8c);
+        assert_ne!(protocol_id("0.1.0"), protocol_id("0.1.1"));
+    }
+}
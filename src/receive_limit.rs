@@ -0,0 +1,34 @@
+use std::num::NonZeroUsize;
+
+use bevy::prelude::*;
+
+/// Maximum number of messages forwarded from renet to Replicon per channel per frame.
+///
+/// On the server the limit applies to each client separately.
+/// Remaining messages stay buffered in renet and will be forwarded in the next frames,
+/// so the order of messages is preserved. This protects the app from spending unbounded time
+/// draining a flooded channel in a single frame.
+///
+/// The limit is non-zero because a zero limit would never forward anything and stall all channels.
+///
+/// Unbounded by default.
+#[derive(Resource, Default, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct ReceiveLimit(pub Option<NonZeroUsize>);
+
+impl ReceiveLimit {
+    /// Returns the maximum number of messages to drain per channel.
+    pub(crate) fn max_messages(&self) -> usize {
+        self.0.map_or(usize::MAX, NonZeroUsize::get)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_messages() {
+        assert_eq!(ReceiveLimit::default().max_messages(), usize::MAX);
+        assert_eq!(ReceiveLimit(NonZeroUsize::new(3)).max_messages(), 3);
+    }
+}
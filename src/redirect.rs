@@ -0,0 +1,71 @@
+#[cfg(feature = "server")]
+use std::net::IpAddr;
+use std::net::SocketAddr;
+#[cfg(feature = "client")]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+#[cfg(feature = "server")]
+use bevy_renet::renet::Bytes;
+
+const IPV4_TAG: u8 = 4;
+const IPV6_TAG: u8 = 6;
+
+/// Serializes the address as an IP version tag, IP bytes and port.
+#[cfg(feature = "server")]
+pub(crate) fn encode(addr: SocketAddr) -> Bytes {
+    let mut bytes = Vec::with_capacity(1 + 16 + 2);
+    match addr.ip() {
+        IpAddr::V4(ip) => {
+            bytes.push(IPV4_TAG);
+            bytes.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            bytes.push(IPV6_TAG);
+            bytes.extend_from_slice(&ip.octets());
+        }
+    }
+    bytes.extend_from_slice(&addr.port().to_le_bytes());
+    bytes.into()
+}
+
+/// Deserializes the address serialized by [`encode`].
+///
+/// Returns [`None`] if the message is malformed.
+#[cfg(feature = "client")]
+pub(crate) fn decode(message: &[u8]) -> Option<SocketAddr> {
+    let (&tag, rest) = message.split_first()?;
+    let (ip, port) = match tag {
+        IPV4_TAG => {
+            let (ip, port) = rest.split_first_chunk::<4>()?;
+            (Ipv4Addr::from(*ip).into(), port)
+        }
+        IPV6_TAG => {
+            let (ip, port) = rest.split_first_chunk::<16>()?;
+            (Ipv6Addr::from(*ip).into(), port)
+        }
+        _ => return None,
+    };
+    let port: [u8; 2] = port.try_into().ok()?;
+
+    Some(SocketAddr::new(ip, u16::from_le_bytes(port)))
+}
+
+#[cfg(all(test, feature = "server", feature = "client"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for addr in ["127.0.0.1:5000", "[::1]:65535"] {
+            let addr = addr.parse().unwrap();
+            assert_eq!(decode(&encode(addr)), Some(addr));
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(decode(&[]), None);
+        assert_eq!(decode(&[IPV4_TAG, 127, 0, 0]), None);
+        assert_eq!(decode(&[0, 0, 0]), None);
+    }
+}
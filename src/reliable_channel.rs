@@ -0,0 +1,49 @@
+use std::mem;
+
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+
+/// Renet channel used for messages with delivery confirmation.
+///
+/// Insert it on both server and client to enable
+/// [`ReliableSend`](crate::ReliableSend) and [`ReliableMessage`](crate::ReliableMessage).
+///
+/// Renet doesn't report acknowledgement of individual messages, so the crate tags each message with
+/// an 8-byte sequence number and the client replies with an acknowledgement over [`ControlChannel`](crate::ControlChannel).
+/// Without it, messages are delivered, but never acknowledged. Use it only for messages that really
+/// need confirmation, like critical commands.
+///
+/// Requires a reliable [`ChannelConfig`](bevy_renet::renet::ChannelConfig) with this ID,
+/// see [channel IDs](crate::ControlChannel#channel-ids).
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct ReliableSendChannel(pub u8);
+
+const SEQUENCE_SIZE: usize = mem::size_of::<u64>();
+
+/// Prepends sequence number to the message.
+pub(crate) fn encode(sequence: u64, message: &[u8]) -> Bytes {
+    let mut bytes = Vec::with_capacity(SEQUENCE_SIZE + message.len());
+    bytes.extend_from_slice(&sequence.to_le_bytes());
+    bytes.extend_from_slice(message);
+    bytes.into()
+}
+
+/// Splits the message into sequence number and payload.
+pub(crate) fn decode(mut message: Bytes) -> Option<(u64, Bytes)> {
+    if message.len() < SEQUENCE_SIZE {
+        return None;
+    }
+
+    let payload = message.split_off(SEQUENCE_SIZE);
+    let sequence = decode_ack(&message)?;
+
+    Some((sequence, payload))
+}
+
+/// Reads an acknowledgement sent in reply to a message.
+///
+/// The acknowledgement is the sequence number of the message.
+pub(crate) fn decode_ack(message: &[u8]) -> Option<u64> {
+    let bytes = message.try_into().ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
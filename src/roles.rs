@@ -0,0 +1,94 @@
+/// Returns which roles were compiled into this crate.
+///
+/// Roles are controlled by the crate features:
+///
+/// | Features            | [`RepliconRenetClientPlugin`](crate::RepliconRenetClientPlugin) | [`RepliconRenetServerPlugin`](crate::RepliconRenetServerPlugin) |
+/// | ------------------- | --------------------------------------------------------------- | --------------------------------------------------------------- |
+/// | `client`, `server`  | ✓                                                               | ✓                                                               |
+/// | `client`            | ✓                                                               |                                                                 |
+/// | `server`            |                                                                 | ✓                                                               |
+/// | none                |                                                                 |                                                                 |
+///
+/// Both are enabled by default. Features are unified across the dependency graph, so the result reflects
+/// features requested by all crates in the build, not only by the caller. It also doesn't tell which plugins
+/// were actually added, since they can be disabled at runtime via
+/// [`PluginGroupBuilder::disable`](bevy::app::PluginGroupBuilder::disable).
+///
+/// # Examples
+///
+/// ```
+/// use bevy_replicon_renet::compiled_roles;
+///
+/// const ROLES: bevy_replicon_renet::Roles = compiled_roles();
+/// if ROLES.is_server_only() {
+///     // Skip client-only UI, like the server browser.
+/// }
+/// ```
+pub const fn compiled_roles() -> Roles {
+    Roles {
+        client: cfg!(feature = "client"),
+        server: cfg!(feature = "server"),
+    }
+}
+
+/// Roles compiled into this crate, returned by [`compiled_roles`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Roles {
+    /// The `client` feature is enabled.
+    pub client: bool,
+
+    /// The `server` feature is enabled.
+    pub server: bool,
+}
+
+impl Roles {
+    /// Returns `true` if only the client role is available.
+    pub const fn is_client_only(self) -> bool {
+        self.client && !self.server
+    }
+
+    /// Returns `true` if only the server role is available.
+    pub const fn is_server_only(self) -> bool {
+        self.server && !self.client
+    }
+
+    /// Returns `true` if both roles are available, for example, to host a listen server.
+    pub const fn is_both(self) -> bool {
+        self.client && self.server
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features() {
+        let roles = compiled_roles();
+        assert_eq!(roles.client, cfg!(feature = "client"));
+        assert_eq!(roles.server, cfg!(feature = "server"));
+        assert_eq!(
+            roles.is_both(),
+            cfg!(all(feature = "client", feature = "server"))
+        );
+    }
+
+    #[test]
+    fn exclusive() {
+        let client = Roles {
+            client: true,
+            server: false,
+        };
+        assert!(client.is_client_only());
+        assert!(!client.is_server_only());
+        assert!(!client.is_both());
+
+        let server = Roles {
+            client: false,
+            server: true,
+        };
+        assert!(server.is_server_only());
+        assert!(!server.is_client_only());
+        assert!(!server.is_both());
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Mutex;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+use crate::{reliable_channel, IncomingTransform, OutgoingTransform};
+
+/// Prepends a sequence number to each forwarded message for debugging reordering.
+///
+/// Renet doesn't expose its packet sequence numbers, so this transform numbers messages per
+/// client and channel on its own and logs each number at the trace level. Together with
+/// [`IncomingSequenceTags`] on the other side it allows to correlate client and server logs
+/// and detect messages that arrived out of order.
+///
+/// This adds 8 bytes to each message and changes the wire format, so register it only in debug
+/// builds and on both sides. Register it after all other transforms to make it the outermost layer.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::{IncomingSequenceTags, MessageTransforms, OutgoingSequenceTags};
+///
+/// # let mut app = App::new();
+/// # app.init_resource::<MessageTransforms>();
+/// let mut transforms = app.world_mut().resource_mut::<MessageTransforms>();
+/// transforms
+///     .add_outgoing(OutgoingSequenceTags::default())
+///     .add_incoming(IncomingSequenceTags::default());
+/// ```
+#[derive(Default)]
+pub struct OutgoingSequenceTags {
+    next_sequences: Mutex<HashMap<(ClientId, u8), u64>>,
+}
+
+impl OutgoingTransform for OutgoingSequenceTags {
+    fn transform(&self, client_id: ClientId, channel_id: u8, message: Bytes) -> Bytes {
+        let mut next_sequences = self.next_sequences.lock().unwrap();
+        let next_sequence = next_sequences.entry((client_id, channel_id)).or_default();
+        let sequence = *next_sequence;
+        *next_sequence += 1;
+
+        trace!(
+            "tagging message {sequence} with {} bytes for `{client_id:?}` over channel {channel_id}",
+            message.len()
+        );
+
+        reliable_channel::encode(sequence, &message)
+    }
+}
+
+/// Removes sequence numbers added by [`OutgoingSequenceTags`] and logs them.
+///
+/// Messages received with a sequence lower than the last received one on the same channel
+/// are logged at the debug level. Messages without a valid tag are discarded.
+#[derive(Default)]
+pub struct IncomingSequenceTags {
+    last_sequences: Mutex<HashMap<(ClientId, u8), u64>>,
+}
+
+impl IncomingTransform for IncomingSequenceTags {
+    fn transform(&self, client_id: ClientId, channel_id: u8, message: Bytes) -> Option<Bytes> {
+        let Some((sequence, message)) = reliable_channel::decode(message) else {
+            debug!("discarding message from `{client_id:?}` without a sequence tag");
+            return None;
+        };
+
+        trace!(
+            "received message {sequence} with {} bytes from `{client_id:?}` over channel {channel_id}",
+            message.len()
+        );
+
+        let mut last_sequences = self.last_sequences.lock().unwrap();
+        match last_sequences.get_mut(&(client_id, channel_id)) {
+            Some(last_sequence) if sequence < *last_sequence => {
+                debug!(
+                    "message {sequence} from `{client_id:?}` over channel {channel_id} arrived after {last_sequence}"
+                );
+            }
+            Some(last_sequence) => *last_sequence = sequence,
+            None => {
+                last_sequences.insert((client_id, channel_id), sequence);
+            }
+        }
+
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let outgoing = OutgoingSequenceTags::default();
+        let incoming = IncomingSequenceTags::default();
+
+        for index in 0..2 {
+            let message = Bytes::from(vec![index]);
+            let tagged = outgoing.transform(CLIENT_ID, CHANNEL_ID, message.clone());
+            assert_eq!(tagged.len(), message.len() + 8);
+
+            let received = incoming.transform(CLIENT_ID, CHANNEL_ID, tagged);
+            assert_eq!(received, Some(message));
+        }
+
+        let last_sequences = incoming.last_sequences.lock().unwrap();
+        assert_eq!(last_sequences[&(CLIENT_ID, CHANNEL_ID)], 1);
+    }
+
+    #[test]
+    fn untagged() {
+        let incoming = IncomingSequenceTags::default();
+        let received = incoming.transform(CLIENT_ID, CHANNEL_ID, Bytes::from_static(&[0]));
+        assert!(received.is_none());
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const CHANNEL_ID: u8 = 0;
+}
@@ -1,114 +1,584 @@
-use bevy::prelude::*;
+pub(super) mod admission;
+pub(super) mod asset_stream;
+pub(super) mod backpressure;
+pub(super) mod bandwidth;
+pub(super) mod capacity;
+pub(super) mod channel_close;
+pub(super) mod channel_rate_limit;
+pub(super) mod channel_report;
+#[cfg(feature = "renet_netcode")]
+pub(super) mod client_addrs;
+pub(super) mod client_id_map;
+pub(super) mod client_transport;
+pub(super) mod clock_sync;
+pub(super) mod congestion;
+pub(super) mod connection_log;
+pub(super) mod connection_phase;
+pub(super) mod connection_stats;
+pub(super) mod control;
+pub(super) mod disconnect;
+pub(super) mod disconnect_grace;
+pub(super) mod disconnected_clients;
+pub(super) mod echo;
+pub(super) mod fixed_send;
+pub(super) mod flush_priority;
+pub(super) mod handshake;
+pub(super) mod idle;
+#[cfg(feature = "init_capture")]
+pub(super) mod init_capture;
+pub(super) mod jitter_buffer;
+pub(super) mod kick;
+pub(super) mod lobby;
+pub(super) mod maintenance;
+pub(super) mod max_message_size;
+pub(super) mod memory_budget;
+pub(super) mod messaging;
+#[cfg(feature = "renet_netcode")]
+pub(super) mod multi_socket;
+pub(super) mod parallel_forwarding;
+pub(super) mod pending_limit;
+pub(super) mod pending_messages;
+pub(super) mod rate_limit;
+pub(super) mod redirect;
+pub(super) mod rejection;
+pub(super) mod reliable_send;
+pub(super) mod shutdown;
+pub(super) mod strict_send;
+pub(super) mod timestamp;
+#[cfg(feature = "renet_netcode")]
+pub(super) mod transport_error;
+pub(super) mod unknown_channel;
+#[cfg(feature = "renet_netcode")]
+pub(super) mod user_data_auth;
+
+#[cfg(feature = "diagnostics")]
+use bevy::utils::Instant;
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashSet};
 #[cfg(feature = "renet_netcode")]
 use bevy_renet::netcode::NetcodeServerPlugin;
 #[cfg(feature = "renet_steam")]
 use bevy_renet::steam::SteamServerPlugin;
 use bevy_renet::{
-    renet::{self, RenetServer},
+    renet::{self, Bytes, RenetServer},
     RenetReceive, RenetSend, RenetServerPlugin,
 };
 use bevy_replicon::prelude::*;
 
+use crate::{
+    channel_report::{ChannelDirection, ChannelThroughput},
+    net_mode::{self, NetModeSet},
+    network_activity,
+    transport::{self, ChannelSide},
+    ChannelGroup, ChannelRemap, ForwardSet, MessageTransforms, NetworkActivity, ReceiveLimit,
+    RepliconDisconnectReason,
+};
+#[cfg(feature = "diagnostics")]
+use crate::{diagnostics::MessageCounts, ForwardingTimings};
+use admission::{AdmissionPlugin, ConnectionGate};
+use asset_stream::{AssetStreamPlugin, AssetStreams};
+use backpressure::SendBackpressure;
+use bandwidth::BandwidthPlugin;
+use capacity::CapacityPlugin;
+use channel_close::ChannelClosePlugin;
+use channel_rate_limit::ChannelRateLimitPlugin;
+#[cfg(feature = "renet_netcode")]
+use client_addrs::ClientAddrsPlugin;
+use client_transport::ClientTransportPlugin;
+use clock_sync::ClockSyncPlugin;
+use congestion::CongestionPlugin;
+use connection_log::ConnectionLogPlugin;
+use connection_phase::ConnectionPhasePlugin;
+use connection_stats::ConnectionStatsPlugin;
+use control::ControlPlugin;
+use disconnect::ClientDisconnected;
+use disconnect_grace::DisconnectGracePlugin;
+use disconnected_clients::DisconnectedClientsPlugin;
+use echo::{EchoPlugin, EchoRecorder};
+use fixed_send::FixedSendPlugin;
+use flush_priority::{FlushPriority, FlushPriorityPlugin};
+use handshake::{ClientMetadata, HandshakePlugin};
+use idle::{IdlePlugin, LastReceived};
+#[cfg(feature = "init_capture")]
+use init_capture::InitCapturePlugin;
+use jitter_buffer::JitterBuffers;
+use kick::KickPlugin;
+use lobby::{HeldReplication, LobbyPlugin};
+use max_message_size::{OversizedMessage, ReceiveLimits};
+use memory_budget::MemoryBudgetPlugin;
+use messaging::MessagingPlugin;
+#[cfg(feature = "renet_netcode")]
+use multi_socket::MultiSocketPlugin;
+use parallel_forwarding::{ForwardingPipeline, ServerTransforms};
+use pending_messages::PendingMessagesPlugin;
+use redirect::RedirectPlugin;
+use rejection::{ClientRejected, ConnectionFilter};
+use reliable_send::{ReliableSendPlugin, ReliableSendState};
+use shutdown::{ShutdownPlugin, ShutdownStage};
+use strict_send::StrictSend;
+use timestamp::{ReceivedTimestamps, TimestampReader};
+#[cfg(feature = "renet_netcode")]
+use transport_error::TransportErrorPlugin;
+use unknown_channel::UnknownChannelPlugin;
+
 /// Adds renet as server messaging backend.
 ///
 /// Initializes [`RenetServerPlugin`], systems that pass data between [`RenetServer`]
 /// and [`RepliconServer`] and translates renet's server events into replicon's.
+///
+/// Messages are passed to renet in the order Replicon produced them. Renet doesn't share
+/// bandwidth between clients: each connection has its own `available_bytes_per_tick` from
+/// [`ConnectionConfig`](renet::ConnectionConfig) and its own channel memory. So the order in which
+/// clients are processed here doesn't affect how much each client receives and a client with
+/// a lot of traffic can't starve others. To change the order of clients, use [`FlushPriority`].
+/// To prioritize data within a single client, use separate channels, since renet gives earlier
+/// channels priority when packing packets.
 pub struct RepliconRenetServerPlugin;
 
 impl Plugin for RepliconRenetServerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(RenetServerPlugin)
-            .configure_sets(PreUpdate, ServerSet::ReceivePackets.after(RenetReceive))
-            .configure_sets(PostUpdate, ServerSet::SendPackets.before(RenetSend))
-            .add_systems(
-                PreUpdate,
+        app.add_plugins((
+            RenetServerPlugin,
+            ReliableSendPlugin,
+            AdmissionPlugin,
+            ConnectionLogPlugin,
+            LobbyPlugin,
+            ClockSyncPlugin,
+            AssetStreamPlugin,
+            MessagingPlugin,
+            PendingMessagesPlugin,
+            HandshakePlugin,
+            BandwidthPlugin,
+            IdlePlugin,
+            MemoryBudgetPlugin,
+            CapacityPlugin,
+            (
+                ChannelRateLimitPlugin,
+                ChannelClosePlugin,
+                UnknownChannelPlugin,
+                FixedSendPlugin,
+                RedirectPlugin,
+                FlushPriorityPlugin,
+                ShutdownPlugin,
+                DisconnectedClientsPlugin,
+                DisconnectGracePlugin,
+                ControlPlugin,
+                KickPlugin,
+                ClientTransportPlugin,
+                EchoPlugin,
                 (
-                    (
-                        Self::set_running.run_if(resource_added::<RenetServer>),
-                        Self::set_stopped.run_if(resource_removed::<RenetServer>),
-                        Self::receive_packets.run_if(resource_exists::<RenetServer>),
-                    )
-                        .chain()
-                        .in_set(ServerSet::ReceivePackets),
-                    Self::forward_server_events.in_set(ServerSet::SendEvents),
+                    ConnectionPhasePlugin,
+                    ConnectionStatsPlugin,
+                    CongestionPlugin,
                 ),
-            )
-            .add_systems(
-                PostUpdate,
-                Self::send_packets
-                    .in_set(ServerSet::SendPackets)
-                    .run_if(resource_exists::<RenetServer>),
-            );
+            ),
+        ))
+        .init_resource::<MessageTransforms>()
+        .init_resource::<ChannelRemap>()
+        .init_resource::<ChannelGroup>()
+        .init_resource::<ReceiveLimit>()
+        .init_resource::<JitterBuffers>()
+        .init_resource::<ReceivedTimestamps>()
+        .init_resource::<SendBackpressure>()
+        .init_resource::<ForwardingPipeline>()
+        .add_event::<ClientRejected>()
+        .add_event::<ClientDisconnected>()
+        .add_event::<OversizedMessage>()
+        .configure_sets(PreUpdate, ServerSet::ReceivePackets.after(RenetReceive))
+        .configure_sets(PreUpdate, NetModeSet.after(ServerSet::ReceivePackets))
+        .configure_sets(
+            PreUpdate,
+            (ClientSetupSet, ClientCleanupSet)
+                .after(ServerSet::SendEvents)
+                .before(ServerSet::Receive),
+        )
+        .configure_sets(PostUpdate, ServerSet::SendPackets.before(RenetSend))
+        .configure_sets(
+            PreUpdate,
+            ForwardSet::Receive.in_set(ServerSet::ReceivePackets),
+        )
+        .configure_sets(PostUpdate, ForwardSet::Send.in_set(ServerSet::SendPackets))
+        .add_systems(
+            PreUpdate,
+            (
+                (
+                    Self::set_running.run_if(resource_added::<RenetServer>),
+                    Self::set_stopped.run_if(resource_removed::<RenetServer>),
+                    Self::receive_packets
+                        .in_set(ForwardSet::Receive)
+                        .run_if(resource_exists::<RenetServer>),
+                )
+                    .chain()
+                    .in_set(ServerSet::ReceivePackets),
+                Self::forward_server_events.in_set(ServerSet::SendEvents),
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::send_packets
+                .in_set(ForwardSet::Send)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(FixedSendPlugin::send_allowed),
+        );
+
+        net_mode::init(app);
+        network_activity::init(app);
+        crate::channel_report::init(app);
+
+        #[cfg(debug_assertions)]
+        app.add_systems(
+            PreUpdate,
+            crate::channel_check::check_channels
+                .run_if(resource_added::<RenetServer>)
+                .before(Self::receive_packets)
+                .in_set(ServerSet::ReceivePackets),
+        );
 
+        #[cfg(feature = "diagnostics")]
+        app.init_resource::<ForwardingTimings>()
+            .init_resource::<MessageCounts>();
+
+        #[cfg(feature = "init_capture")]
+        app.add_plugins(InitCapturePlugin);
         #[cfg(feature = "renet_netcode")]
-        app.add_plugins(NetcodeServerPlugin);
+        app.add_plugins((
+            NetcodeServerPlugin,
+            MultiSocketPlugin,
+            ClientAddrsPlugin,
+            TransportErrorPlugin,
+        ));
         #[cfg(feature = "renet_steam")]
         app.add_plugins(SteamServerPlugin);
     }
 }
 
+/// Set for systems that clean up game data of disconnected clients.
+///
+/// Runs in [`PreUpdate`] after [`ServerEvent`]s are emitted in [`ServerSet::SendEvents`], but
+/// before Replicon processes them in [`ServerSet::Receive`]. So systems in this set that read
+/// [`ServerEvent::ClientDisconnected`] will still find the client in [`ConnectedClients`]
+/// and [`ReplicatedClients`] along with its replication data, like visibility.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientCleanupSet;
+
+/// Set for systems that set up game data of connected clients.
+///
+/// Runs in [`PreUpdate`] after [`ServerEvent`]s are emitted in [`ServerSet::SendEvents`], but
+/// before Replicon processes them in [`ServerSet::Receive`]. So entities spawned by systems in this set
+/// on [`ServerEvent::ClientConnected`] exist before the client is added to [`ReplicatedClients`]
+/// and are included in its first replication message.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientSetupSet;
+
+/// Passes received messages to [`RepliconServer`] after stripping their tags.
+#[derive(SystemParam)]
+struct ReceivedMessages<'w> {
+    replicon_server: ResMut<'w, RepliconServer>,
+    timestamps: TimestampReader<'w>,
+    echo: EchoRecorder<'w>,
+}
+
+impl ReceivedMessages<'_> {
+    fn insert(&mut self, client_id: ClientId, channel_id: u8, message: Bytes) {
+        let Some(message) = self.timestamps.read(client_id, channel_id, message) else {
+            return;
+        };
+
+        self.echo.record(client_id, channel_id, &message);
+        self.replicon_server
+            .insert_received(client_id, channel_id, message);
+    }
+}
+
 impl RepliconRenetServerPlugin {
     fn set_running(mut server: ResMut<RepliconServer>) {
         server.set_running(true);
     }
 
-    fn set_stopped(mut server: ResMut<RepliconServer>) {
+    #[allow(clippy::too_many_arguments)]
+    fn set_stopped(
+        connected_clients: Res<ConnectedClients>,
+        mut disconnect_events: EventWriter<ClientDisconnected>,
+        mut server: ResMut<RepliconServer>,
+        mut jitter_buffers: ResMut<JitterBuffers>,
+        mut backpressure: ResMut<SendBackpressure>,
+        mut held: ResMut<HeldReplication>,
+        mut streams: ResMut<AssetStreams>,
+        mut client_metadata: ResMut<ClientMetadata>,
+        mut last_received: ResMut<LastReceived>,
+        mut reliable_state: ResMut<ReliableSendState>,
+        mut pipeline: ResMut<ForwardingPipeline>,
+    ) {
+        for client in connected_clients.iter() {
+            disconnect_events.send(ClientDisconnected {
+                client_id: client.id(),
+                reason: RepliconDisconnectReason::ServerShutdown,
+            });
+        }
+
         server.set_running(false);
+        jitter_buffers.clear();
+        backpressure.clear();
+        held.clear();
+        streams.clear();
+        client_metadata.clear();
+        last_received.clear();
+        reliable_state.clear();
+        pipeline.clear();
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn forward_server_events(
+        mut rejected_clients: Local<HashSet<u64>>,
+        mut deferred_clients: Local<Vec<u64>>,
         mut renet_server_events: EventReader<renet::ServerEvent>,
         mut server_events: EventWriter<ServerEvent>,
+        mut rejected_events: EventWriter<ClientRejected>,
+        mut disconnect_events: EventWriter<ClientDisconnected>,
+        mut jitter_buffers: ResMut<JitterBuffers>,
+        mut renet_server: Option<ResMut<RenetServer>>,
+        gate: ConnectionGate,
+        mut filter: ConnectionFilter,
     ) {
+        if renet_server.is_none() {
+            deferred_clients.clear();
+        }
+
+        let overloaded = gate.is_overloaded();
+        if !overloaded {
+            deferred_clients.retain(|&client_id| {
+                if !gate.is_handshake_completed(ClientId::new(client_id)) {
+                    return true;
+                }
+
+                debug!("accepting deferred client `{client_id}`");
+                server_events.send(ServerEvent::ClientConnected {
+                    client_id: ClientId::new(client_id),
+                });
+                false
+            });
+        }
+
         for event in renet_server_events.read() {
-            debug!("forwarding event `{event:?}`");
-            let replicon_event = match event {
-                renet::ServerEvent::ClientConnected { client_id } => ServerEvent::ClientConnected {
-                    client_id: ClientId::new(*client_id),
-                },
-                renet::ServerEvent::ClientDisconnected { client_id, reason } => {
+            let replicon_event = match *event {
+                renet::ServerEvent::ClientConnected { client_id } => {
+                    if let Some(reason) = filter.check(client_id, renet_server.as_deref()) {
+                        debug!("rejecting client `{client_id}`: {reason:?}");
+                        if let Some(renet_server) = &mut renet_server {
+                            renet_server.disconnect(client_id);
+                        }
+                        rejected_clients.insert(client_id);
+                        rejected_events.send(ClientRejected {
+                            client_id: ClientId::new(client_id),
+                            reason,
+                        });
+                        continue;
+                    }
+
+                    rejected_clients.remove(&client_id);
+                    if overloaded {
+                        debug!("deferring client `{client_id}` due to server load");
+                        deferred_clients.push(client_id);
+                        continue;
+                    }
+                    if !gate.is_handshake_completed(ClientId::new(client_id)) {
+                        debug!("deferring client `{client_id}` until handshake");
+                        deferred_clients.push(client_id);
+                        continue;
+                    }
+
+                    ServerEvent::ClientConnected {
+                        client_id: ClientId::new(client_id),
+                    }
+                }
+                renet::ServerEvent::ClientDisconnected {
+                    client_id,
+                    ref reason,
+                } => {
+                    if rejected_clients.remove(&client_id) {
+                        debug!("ignoring disconnect of rejected client `{client_id}`");
+                        continue;
+                    }
+                    if let Some(index) = deferred_clients.iter().position(|&id| id == client_id) {
+                        debug!("ignoring disconnect of deferred client `{client_id}`");
+                        deferred_clients.remove(index);
+                        continue;
+                    }
+
+                    jitter_buffers.remove_client(ClientId::new(client_id));
+                    disconnect_events.send(ClientDisconnected {
+                        client_id: ClientId::new(client_id),
+                        reason: reason.into(),
+                    });
+
                     ServerEvent::ClientDisconnected {
-                        client_id: ClientId::new(*client_id),
+                        client_id: ClientId::new(client_id),
                         reason: reason.to_string(),
                     }
                 }
             };
 
+            debug!("forwarding event `{event:?}`");
             server_events.send(replicon_event);
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn receive_packets(
+        time: Res<Time<Real>>,
         connected_clients: Res<ConnectedClients>,
         channels: Res<RepliconChannels>,
+        mut transforms: ServerTransforms,
+        mut limits: ReceiveLimits,
+        remap: Res<ChannelRemap>,
+        group: Res<ChannelGroup>,
+        mut messages: Local<Vec<(ClientId, u8, Bytes)>>,
+        mut jitter_buffers: ResMut<JitterBuffers>,
         mut renet_server: ResMut<RenetServer>,
-        mut replicon_server: ResMut<RepliconServer>,
+        mut activity: ResMut<NetworkActivity>,
+        mut throughput: ResMut<ChannelThroughput>,
+        mut last_received: ResMut<LastReceived>,
+        mut received: ReceivedMessages,
+        #[cfg(feature = "diagnostics")] mut timings: ResMut<ForwardingTimings>,
+        #[cfg(feature = "diagnostics")] mut counts: ResMut<MessageCounts>,
     ) {
+        #[cfg(feature = "diagnostics")]
+        let start = Instant::now();
+
+        received.timestamps.clear();
         for &client in connected_clients.iter() {
-            for channel_id in 0..channels.client_channels().len() as u8 {
-                while let Some(message) =
-                    renet_server.receive_message(client.id().get(), channel_id)
-                {
+            transport::receive_messages(
+                &mut *renet_server,
+                client.id().get(),
+                group.channel_ids(channels.client_channels().len()),
+                ChannelSide::Client,
+                &remap,
+                limits.receive_limit(),
+                |channel_id, message| {
                     trace!(
                         "forwarding {} received bytes over channel {channel_id}",
                         message.len()
                     );
-                    replicon_server.insert_received(client.id(), channel_id, message);
-                }
+                    activity.received_this_frame = true;
+                    throughput.add(
+                        client.id(),
+                        ChannelDirection::Receive,
+                        channel_id,
+                        message.len(),
+                    );
+                    last_received.record(client.id(), channel_id, time.elapsed());
+                    #[cfg(feature = "diagnostics")]
+                    {
+                        counts.server_received += 1;
+                    }
+                    if limits.check_size(client.id(), channel_id, message.len()) {
+                        messages.push((client.id(), channel_id, message));
+                    }
+                },
+            );
+        }
+        limits.disconnect_violators(&mut renet_server);
+
+        transforms.apply_incoming(&mut messages);
+        // Messages transformed in the background may belong to clients that disconnected since then.
+        messages.retain(|&(client_id, ..)| renet_server.is_connected(client_id.get()));
+        for (client_id, channel_id, message) in messages.drain(..) {
+            if jitter_buffers.is_buffered(channel_id) {
+                jitter_buffers.push(client_id, channel_id, message);
+            } else {
+                received.insert(client_id, channel_id, message);
             }
         }
+
+        jitter_buffers.release(|client_id, channel_id, message| {
+            received.insert(client_id, channel_id, message)
+        });
+
+        #[cfg(feature = "diagnostics")]
+        timings.server_receive.push(start.elapsed());
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn send_packets(
+        strict_send: Option<Res<StrictSend>>,
+        channels: Res<RepliconChannels>,
+        mut transforms: ServerTransforms,
+        remap: Res<ChannelRemap>,
+        group: Res<ChannelGroup>,
+        flush_priority: Res<FlushPriority>,
+        mut messages: Local<Vec<(ClientId, u8, Bytes)>>,
+        mut backpressure: ResMut<SendBackpressure>,
         mut renet_server: ResMut<RenetServer>,
         mut replicon_server: ResMut<RepliconServer>,
+        mut activity: ResMut<NetworkActivity>,
+        mut throughput: ResMut<ChannelThroughput>,
+        mut shutdown_stage: ResMut<ShutdownStage>,
+        #[cfg(feature = "diagnostics")] mut timings: ResMut<ForwardingTimings>,
+        #[cfg(feature = "diagnostics")] mut counts: ResMut<MessageCounts>,
     ) {
-        for (client_id, channel_id, message) in replicon_server.drain_sent() {
+        #[cfg(feature = "diagnostics")]
+        let start = Instant::now();
+
+        backpressure.flush(&mut renet_server);
+
+        messages.extend(replicon_server.drain_sent());
+        flush_priority.sort(&mut messages);
+        messages.retain(|&(client_id, channel_id, _)| {
+            if !renet_server.is_connected(client_id.get()) {
+                if strict_send.is_some() {
+                    panic!("messages should be sent only to connected clients, but `{client_id:?}` is disconnected");
+                }
+                debug!(
+                    "skipping message over channel {channel_id} for disconnected `{client_id:?}`"
+                );
+                return false;
+            }
+            if !group.contains(channel_id) {
+                error!(
+                    "discarding message over channel {channel_id} outside of `{:?}` for `{client_id:?}`",
+                    *group
+                );
+                return false;
+            }
+
+            true
+        });
+
+        transforms.apply_outgoing(&mut messages);
+        messages.retain(|&(client_id, channel_id, _)| {
+            let connected = renet_server.is_connected(client_id.get());
+            if !connected {
+                debug!("skipping transformed message over channel {channel_id} for disconnected `{client_id:?}`");
+            }
+            connected
+        });
+        for (client_id, channel_id, message) in messages.drain(..) {
+            activity.sent_this_frame = true;
+            shutdown_stage.record_flushed(1);
+            #[cfg(feature = "diagnostics")]
+            {
+                counts.server_sent += 1;
+            }
+            throughput.add(client_id, ChannelDirection::Send, channel_id, message.len());
             trace!(
                 "forwarding {} sent bytes over channel {channel_id}",
                 message.len()
             );
-            renet_server.send_message(client_id.get(), channel_id, message)
+            if channels.server_channels()[channel_id as usize].kind == ChannelKind::Unreliable {
+                transport::send_message(
+                    &mut *renet_server,
+                    client_id.get(),
+                    ChannelSide::Server,
+                    &remap,
+                    channel_id,
+                    message,
+                );
+            } else {
+                let wire_id = ChannelSide::Server.wire_id(&remap, channel_id);
+                backpressure.send(&mut renet_server, client_id, wire_id, message);
+            }
         }
+
+        #[cfg(feature = "diagnostics")]
+        timings.server_send.push(start.elapsed());
     }
 }
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_replicon::prelude::*;
+
+use super::handshake::ClientMetadata;
+use crate::{ControlChannel, HandshakeMetadata};
+
+/// Defers new connections while the server is overloaded.
+///
+/// Each accepted client triggers sending of the whole replicated world to it, which makes an
+/// overloaded server even slower. While the average frame time exceeds [`Self::frame_budget`],
+/// newly connected clients are kept in renet, but [`ServerEvent::ClientConnected`] for them is not emitted,
+/// so Replicon doesn't start replication. Deferred clients are passed to Replicon in the order they connected
+/// once the average frame time drops below the budget or this resource is removed.
+///
+/// Unlike rejection via [`MaintenanceMode`](super::maintenance::MaintenanceMode) or
+/// [`ConnectionRateLimit`](super::rate_limit::ConnectionRateLimit), deferred clients stay connected
+/// and eventually join without reconnecting, but they see no world until then. If the load doesn't
+/// subside, they will wait indefinitely and keep their slots in renet, so for sustained load rejecting
+/// connections is preferable, since it lets clients pick another server.
+///
+/// Messages from deferred clients are not read and stay in renet until the client is accepted.
+/// If a deferred client disconnects, no events are emitted for it.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct AdmissionControl {
+    /// Average frame time above which new connections are deferred.
+    pub frame_budget: Duration,
+
+    /// Weight of the last frame time in the average, from 0 to 1.
+    ///
+    /// Higher values react to load changes faster, but are more affected by single slow frames.
+    pub smoothing: f64,
+
+    frame_time: Option<Duration>,
+}
+
+impl AdmissionControl {
+    /// Creates admission control with the given frame time budget.
+    pub fn new(frame_budget: Duration) -> Self {
+        Self {
+            frame_budget,
+            smoothing: 0.1,
+            frame_time: None,
+        }
+    }
+
+    /// Returns the average frame time.
+    ///
+    /// Returns [`None`] until the first frame is measured.
+    pub fn frame_time(&self) -> Option<Duration> {
+        self.frame_time
+    }
+
+    /// Returns `true` if new connections are currently deferred.
+    pub fn is_overloaded(&self) -> bool {
+        self.frame_time
+            .is_some_and(|frame_time| frame_time > self.frame_budget)
+    }
+
+    /// Blends the duration of the last frame into the average.
+    fn add_sample(&mut self, delta: Duration) {
+        let frame_time = match self.frame_time {
+            Some(frame_time) => {
+                let average = frame_time.as_secs_f64();
+                let sample = delta.as_secs_f64();
+                Duration::from_secs_f64(average + (sample - average) * self.smoothing)
+            }
+            None => delta,
+        };
+        self.frame_time = Some(frame_time);
+    }
+}
+
+/// Decides whether a connected client can be passed to Replicon in this frame.
+///
+/// Clients are deferred while the server is overloaded according to [`AdmissionControl`]
+/// or until their [`HandshakeMetadata`] is received.
+#[derive(SystemParam)]
+pub(super) struct ConnectionGate<'w> {
+    admission: Option<Res<'w, AdmissionControl>>,
+    control_channel: Option<Res<'w, ControlChannel>>,
+    handshake_metadata: Option<Res<'w, HandshakeMetadata>>,
+    client_metadata: Res<'w, ClientMetadata>,
+}
+
+impl ConnectionGate<'_> {
+    pub(super) fn is_overloaded(&self) -> bool {
+        self.admission
+            .as_ref()
+            .is_some_and(|admission| admission.is_overloaded())
+    }
+
+    pub(super) fn is_handshake_completed(&self, client_id: ClientId) -> bool {
+        let handshake_enabled = self.control_channel.is_some() && self.handshake_metadata.is_some();
+        !handshake_enabled || self.client_metadata.contains(client_id)
+    }
+}
+
+pub(super) struct AdmissionPlugin;
+
+impl Plugin for AdmissionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::measure
+                .in_set(ServerSet::SendEvents)
+                .before(super::RepliconRenetServerPlugin::forward_server_events)
+                .run_if(resource_exists::<AdmissionControl>),
+        );
+    }
+}
+
+impl AdmissionPlugin {
+    fn measure(time: Res<Time<Real>>, mut admission: ResMut<AdmissionControl>) {
+        admission.add_sample(time.delta());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overload() {
+        let mut admission = AdmissionControl::new(Duration::from_millis(20));
+        assert!(!admission.is_overloaded());
+
+        admission.add_sample(Duration::from_millis(16));
+        assert!(!admission.is_overloaded());
+
+        for _ in 0..30 {
+            admission.add_sample(Duration::from_millis(50));
+        }
+        assert!(admission.is_overloaded());
+
+        for _ in 0..30 {
+            admission.add_sample(Duration::from_millis(10));
+        }
+        assert!(!admission.is_overloaded());
+    }
+}
@@ -0,0 +1,171 @@
+use std::{collections::VecDeque, num::NonZeroUsize};
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_renet::renet::{Bytes, RenetServer};
+use bevy_replicon::prelude::*;
+
+use crate::asset_stream::{AssetStreamChannel, Chunk};
+
+/// Streams large byte blobs, like maps or mods, to clients over [`AssetStreamChannel`].
+///
+/// Blobs are split into chunks of [`Self::chunk_size`] bytes. Chunks are passed to renet only while
+/// the channel has enough free memory (see `max_memory_usage_bytes` in
+/// [`ChannelConfig`](bevy_renet::renet::ChannelConfig)), so a large blob never overflows the channel
+/// and the rest is sent in the next frames. Blobs for the same client are sent one after another.
+///
+/// The client reassembles the blob and emits [`AssetReceived`](crate::AssetReceived) with
+/// [`AssetProgress`](crate::AssetProgress) events along the way. Streams to clients that
+/// disconnect are dropped.
+#[derive(Resource)]
+pub struct AssetStreams {
+    /// Maximum number of blob bytes in a single message.
+    ///
+    /// Should be smaller than `max_memory_usage_bytes` of the channel, otherwise no chunk will fit.
+    pub chunk_size: NonZeroUsize,
+
+    next_id: u64,
+    streams: VecDeque<OutgoingStream>,
+}
+
+impl AssetStreams {
+    /// Queues a blob for sending and returns its stream ID.
+    ///
+    /// The returned ID will be reported by the client in [`AssetReceived`](crate::AssetReceived).
+    pub fn send(&mut self, client_id: ClientId, data: impl Into<Bytes>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let data = data.into();
+        debug!(
+            "queuing stream {id} with {} bytes for `{client_id:?}`",
+            data.len()
+        );
+        self.streams.push_back(OutgoingStream {
+            id,
+            client_id,
+            data,
+            offset: 0,
+            started: false,
+        });
+
+        id
+    }
+
+    /// Returns the number of bytes passed to renet and the total size of the blob.
+    ///
+    /// Returns [`None`] if the stream is completely sent or was dropped.
+    pub fn progress(&self, stream_id: u64) -> Option<(usize, usize)> {
+        self.streams
+            .iter()
+            .find(|stream| stream.id == stream_id)
+            .map(|stream| (stream.offset, stream.data.len()))
+    }
+
+    fn remove_client(&mut self, client_id: ClientId) {
+        self.streams.retain(|stream| stream.client_id != client_id);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.streams.clear();
+    }
+}
+
+impl Default for AssetStreams {
+    fn default() -> Self {
+        Self {
+            chunk_size: NonZeroUsize::new(16 * 1024).unwrap(),
+            next_id: 0,
+            streams: Default::default(),
+        }
+    }
+}
+
+struct OutgoingStream {
+    id: u64,
+    client_id: ClientId,
+    data: Bytes,
+    offset: usize,
+    started: bool,
+}
+
+impl OutgoingStream {
+    fn is_finished(&self) -> bool {
+        self.started && self.offset == self.data.len()
+    }
+}
+
+pub(super) struct AssetStreamPlugin;
+
+impl Plugin for AssetStreamPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetStreams>()
+            .add_systems(
+                PreUpdate,
+                Self::remove_disconnected
+                    .in_set(ServerSet::SendEvents)
+                    .after(super::RepliconRenetServerPlugin::forward_server_events),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::send_chunks
+                    .in_set(ServerSet::SendPackets)
+                    .run_if(resource_exists::<RenetServer>)
+                    .run_if(resource_exists::<AssetStreamChannel>),
+            );
+    }
+}
+
+impl AssetStreamPlugin {
+    fn remove_disconnected(
+        mut server_events: EventReader<ServerEvent>,
+        mut streams: ResMut<AssetStreams>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                streams.remove_client(client_id);
+            }
+        }
+    }
+
+    fn send_chunks(
+        channel: Res<AssetStreamChannel>,
+        mut streams: ResMut<AssetStreams>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        let chunk_size = streams.chunk_size.get();
+        let mut blocked_clients = HashSet::new();
+        streams.streams.retain_mut(|stream| {
+            let client_id = stream.client_id.get();
+            if !renet_server.is_connected(client_id) {
+                debug!("dropping stream {} for disconnected client", stream.id);
+                return false;
+            }
+
+            // Keep order of streams for the same client.
+            if blocked_clients.contains(&client_id) {
+                return true;
+            }
+
+            while !stream.is_finished() {
+                let len = chunk_size.min(stream.data.len() - stream.offset);
+                if !renet_server.can_send_message(client_id, **channel, Chunk::encoded_size(len)) {
+                    blocked_clients.insert(client_id);
+                    return true;
+                }
+
+                let chunk = Chunk {
+                    stream_id: stream.id,
+                    total_len: stream.data.len(),
+                    offset: stream.offset,
+                    data: stream.data.slice(stream.offset..stream.offset + len),
+                };
+                renet_server.send_message(client_id, **channel, chunk.encode());
+                stream.offset += len;
+                stream.started = true;
+            }
+
+            debug!("finished sending stream {}", stream.id);
+            false
+        });
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::{Bytes, RenetServer};
+use bevy_replicon::prelude::*;
+
+/// Holds back messages for reliable channels that don't have enough memory in renet.
+///
+/// When a reliable channel reaches its `max_memory_usage_bytes`, renet disconnects the client.
+/// To avoid this under temporary load, messages that don't fit are queued per client and channel
+/// and passed to renet in the next frames once the client acknowledges previous messages.
+/// The order of messages within a channel is preserved. Unreliable channels are not affected,
+/// renet drops messages on them when there is no memory left.
+///
+/// Replicon doesn't provide a way to pause replication for a client, so this only delays delivery.
+/// Use [`Self::is_congested`] to reduce what your game sends to such clients.
+/// If the queued messages for a client exceed [`Self::max_queued_bytes`], the client is disconnected.
+#[derive(Resource)]
+pub struct SendBackpressure {
+    /// Maximum number of queued bytes per client.
+    ///
+    /// By default set to 10 MiB.
+    pub max_queued_bytes: usize,
+
+    queues: HashMap<(ClientId, u8), VecDeque<Bytes>>,
+}
+
+impl SendBackpressure {
+    /// Returns `true` if messages for the client are currently being held back.
+    pub fn is_congested(&self, client_id: ClientId) -> bool {
+        self.queues
+            .keys()
+            .any(|&(queue_client, _)| queue_client == client_id)
+    }
+
+    /// Returns the number of bytes held back for the client.
+    pub fn queued_bytes(&self, client_id: ClientId) -> usize {
+        self.queues
+            .iter()
+            .filter(|(&(queue_client, _), _)| queue_client == client_id)
+            .flat_map(|(_, messages)| messages)
+            .map(|message| message.len())
+            .sum()
+    }
+
+    /// Returns the number of messages held back for the client on the channel.
+    pub fn queued_messages(&self, client_id: ClientId, channel_id: u8) -> usize {
+        self.queues
+            .get(&(client_id, channel_id))
+            .map(|messages| messages.len())
+            .unwrap_or_default()
+    }
+
+    /// Passes the message to renet or queues it if the channel doesn't have enough memory.
+    pub(crate) fn send(
+        &mut self,
+        renet_server: &mut RenetServer,
+        client_id: ClientId,
+        channel_id: u8,
+        message: Bytes,
+    ) {
+        let queue = self.queues.get_mut(&(client_id, channel_id));
+        if queue.is_none()
+            && renet_server.can_send_message(client_id.get(), channel_id, message.len())
+        {
+            renet_server.send_message(client_id.get(), channel_id, message);
+            return;
+        }
+
+        trace!(
+            "holding back {} bytes for `{client_id:?}` over channel {channel_id}",
+            message.len()
+        );
+        match queue {
+            Some(queue) => queue.push_back(message),
+            None => {
+                self.queues
+                    .insert((client_id, channel_id), VecDeque::from([message]));
+            }
+        }
+
+        if self.queued_bytes(client_id) > self.max_queued_bytes {
+            warn!("disconnecting `{client_id:?}` due to exceeded send queue");
+            renet_server.disconnect(client_id.get());
+            self.remove_client(client_id);
+        }
+    }
+
+    /// Passes queued messages to renet as long as their channels have enough memory.
+    ///
+    /// Removes queues for disconnected clients.
+    pub(crate) fn flush(&mut self, renet_server: &mut RenetServer) {
+        self.queues.retain(|&(client_id, channel_id), queue| {
+            if !renet_server.is_connected(client_id.get()) {
+                return false;
+            }
+
+            while let Some(message) = queue.front() {
+                if !renet_server.can_send_message(client_id.get(), channel_id, message.len()) {
+                    break;
+                }
+                let message = queue.pop_front().unwrap();
+                renet_server.send_message(client_id.get(), channel_id, message);
+            }
+
+            !queue.is_empty()
+        });
+    }
+
+    pub(crate) fn remove_client(&mut self, client_id: ClientId) {
+        self.queues
+            .retain(|&(queue_client, _), _| queue_client != client_id);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.queues.clear();
+    }
+}
+
+impl Default for SendBackpressure {
+    fn default() -> Self {
+        Self {
+            max_queued_bytes: 10 * 1024 * 1024,
+            queues: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_renet::renet::{ChannelConfig, ConnectionConfig, SendType};
+
+    use super::*;
+
+    #[test]
+    fn queueing() {
+        let mut renet_server = create_server();
+        let mut backpressure = SendBackpressure::default();
+
+        backpressure.send(&mut renet_server, CLIENT_ID, CHANNEL_ID, message(0));
+        assert!(!backpressure.is_congested(CLIENT_ID));
+
+        backpressure.send(&mut renet_server, CLIENT_ID, CHANNEL_ID, message(1));
+        assert!(backpressure.is_congested(CLIENT_ID));
+        assert_eq!(backpressure.queued_bytes(CLIENT_ID), MESSAGE_SIZE);
+        assert_eq!(backpressure.queued_messages(CLIENT_ID, CHANNEL_ID), 1);
+
+        backpressure.flush(&mut renet_server);
+        assert!(
+            backpressure.is_congested(CLIENT_ID),
+            "message shouldn't be sent until the channel has memory"
+        );
+        assert!(renet_server.is_connected(CLIENT_ID.get()));
+    }
+
+    #[test]
+    fn exceeded_queue() {
+        let mut renet_server = create_server();
+        let mut backpressure = SendBackpressure {
+            max_queued_bytes: MESSAGE_SIZE,
+            ..Default::default()
+        };
+
+        for index in 0..3 {
+            backpressure.send(&mut renet_server, CLIENT_ID, CHANNEL_ID, message(index));
+        }
+
+        assert!(!backpressure.is_congested(CLIENT_ID));
+        assert!(!renet_server.is_connected(CLIENT_ID.get()));
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const CHANNEL_ID: u8 = 0;
+    const MESSAGE_SIZE: usize = 100;
+
+    /// Creates a server with a connected client and a reliable channel that fits a single message.
+    fn create_server() -> RenetServer {
+        let channels_config = vec![ChannelConfig {
+            channel_id: CHANNEL_ID,
+            max_memory_usage_bytes: MESSAGE_SIZE,
+            send_type: SendType::ReliableOrdered {
+                resend_time: Default::default(),
+            },
+        }];
+        let mut renet_server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels_config.clone(),
+            client_channels_config: channels_config,
+            ..Default::default()
+        });
+        renet_server.add_connection(CLIENT_ID.get());
+
+        renet_server
+    }
+
+    fn message(index: u8) -> Bytes {
+        vec![index; MESSAGE_SIZE].into()
+    }
+}
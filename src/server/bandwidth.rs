@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+/// Total bandwidth of the server across all connected clients.
+///
+/// Sums per-client statistics from renet's [`NetworkInfo`](bevy_renet::renet::NetworkInfo) in
+/// [`ServerSet::ReceivePackets`]. The resource is changed only when the values change, so
+/// [`Res::is_changed`] can be used to refresh dashboards.
+///
+/// Values are measured by renet at the connection level, so they include packet headers,
+/// resends and messages from custom channels.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct ServerBandwidth {
+    /// Bytes per second sent to all clients.
+    pub sent_bps: f64,
+    /// Bytes per second received from all clients.
+    pub received_bps: f64,
+}
+
+pub(super) struct BandwidthPlugin;
+
+impl Plugin for BandwidthPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ServerBandwidth>().add_systems(
+            PreUpdate,
+            Self::update
+                .after(super::RepliconRenetServerPlugin::receive_packets)
+                .in_set(ServerSet::ReceivePackets)
+                .run_if(resource_exists::<RenetServer>),
+        );
+    }
+}
+
+impl BandwidthPlugin {
+    fn update(renet_server: Res<RenetServer>, mut bandwidth: ResMut<ServerBandwidth>) {
+        let mut total = ServerBandwidth::default();
+        for client_id in renet_server.clients_id_iter() {
+            if let Ok(info) = renet_server.network_info(client_id) {
+                total.sent_bps += info.bytes_sent_per_second;
+                total.received_bps += info.bytes_received_per_second;
+            }
+        }
+
+        bandwidth.set_if_neq(total);
+    }
+}
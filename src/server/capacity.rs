@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+/// Maximum number of clients for [`server_full`] and capacity events.
+///
+/// Also limits connections: a new connection is rejected with
+/// [`RejectReason::Full`](super::rejection::RejectReason::Full) if the number of other renet
+/// connections, including ones that haven't reached Replicon yet, already reached the limit.
+///
+/// Unlike transport limits, like `ServerConfig::max_clients`
+/// for netcode, it can be changed at runtime. It's enforced above the transport, so the transport still accepts
+/// the connection and the client is disconnected right after, and the transport limit still applies.
+/// Set the transport limit to the highest value you plan to use. Lowering this value doesn't disconnect
+/// already connected clients.
+#[derive(Resource, Deref, DerefMut, Debug, Clone, Copy)]
+pub struct MaxClients(pub usize);
+
+/// Returns `true` if the number of clients in [`ConnectedClients`] reached [`MaxClients`].
+///
+/// Returns `false` if [`MaxClients`] is not present.
+pub fn server_full(
+    max_clients: Option<Res<MaxClients>>,
+    connected_clients: Res<ConnectedClients>,
+) -> bool {
+    max_clients.is_some_and(|max_clients| connected_clients.len() >= **max_clients)
+}
+
+/// An event that indicates that the server became full according to [`MaxClients`].
+///
+/// Emitted in [`PreUpdate`] after Replicon processes connections in [`ServerSet::Receive`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerFull;
+
+/// An event that indicates that a full server has free slots again.
+///
+/// Also emitted if [`MaxClients`] is increased or removed while the server is full.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerHasCapacity;
+
+pub(super) struct CapacityPlugin;
+
+impl Plugin for CapacityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ServerFull>()
+            .add_event::<ServerHasCapacity>()
+            .add_systems(PreUpdate, Self::detect.after(ServerSet::Receive));
+    }
+}
+
+impl CapacityPlugin {
+    fn detect(
+        mut was_full: Local<bool>,
+        max_clients: Option<Res<MaxClients>>,
+        connected_clients: Res<ConnectedClients>,
+        mut full_events: EventWriter<ServerFull>,
+        mut capacity_events: EventWriter<ServerHasCapacity>,
+    ) {
+        let full = server_full(max_clients, connected_clients);
+        if full == *was_full {
+            return;
+        }
+
+        *was_full = full;
+        if full {
+            debug!("server is full");
+            full_events.send(ServerFull);
+        } else {
+            debug!("server has capacity");
+            capacity_events.send(ServerHasCapacity);
+        }
+    }
+}
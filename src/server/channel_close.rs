@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use super::{backpressure::SendBackpressure, channel_rate_limit::ChannelRateLimitPlugin};
+use crate::{transport::ChannelSide, ChannelRemap, ClosedChannels};
+
+pub(super) struct ChannelClosePlugin;
+
+impl Plugin for ChannelClosePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::update
+                .after(super::RepliconRenetServerPlugin::receive_packets)
+                .in_set(ServerSet::ReceivePackets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<ClosedChannels>),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::discard
+                .in_set(ServerSet::SendPackets)
+                .after(ChannelRateLimitPlugin::limit)
+                .before(super::memory_budget::MemoryBudgetPlugin::shed)
+                .before(super::RepliconRenetServerPlugin::send_packets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<ClosedChannels>),
+        );
+    }
+}
+
+impl ChannelClosePlugin {
+    /// Marks channels as closed once renet frees their memory for all clients.
+    fn update(
+        remap: Res<ChannelRemap>,
+        backpressure: Res<SendBackpressure>,
+        renet_server: Res<RenetServer>,
+        mut closed_channels: ResMut<ClosedChannels>,
+    ) {
+        closed_channels.update(
+            |channel_id| ChannelSide::Server.wire_id(&remap, channel_id),
+            |wire_id, limit| {
+                renet_server.clients_id_iter().all(|client_id| {
+                    renet_server.channel_available_memory(client_id, wire_id) >= limit
+                        && backpressure.queued_messages(ClientId::new(client_id), wire_id) == 0
+                })
+            },
+        );
+    }
+
+    pub(super) fn discard(
+        closed_channels: Res<ClosedChannels>,
+        mut server: ResMut<RepliconServer>,
+    ) {
+        let messages: Vec<_> = server.drain_sent().collect();
+        for (client_id, channel_id, message) in messages {
+            if closed_channels.is_closed(channel_id) {
+                trace!("discarding message over closed channel {channel_id} for `{client_id:?}`");
+                continue;
+            }
+
+            server.send(client_id, channel_id, message);
+        }
+    }
+}
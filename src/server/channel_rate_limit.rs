@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use crate::ChannelRateLimit;
+
+pub(super) struct ChannelRateLimitPlugin;
+
+impl Plugin for ChannelRateLimitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::remove_disconnected
+                .in_set(ServerSet::SendEvents)
+                .after(super::RepliconRenetServerPlugin::forward_server_events)
+                .run_if(resource_exists::<ChannelRateLimit>),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::limit
+                .in_set(ServerSet::SendPackets)
+                .after(super::lobby::LobbyPlugin::hold_replication)
+                .before(super::memory_budget::MemoryBudgetPlugin::shed)
+                .before(super::RepliconRenetServerPlugin::send_packets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<ChannelRateLimit>),
+        );
+    }
+}
+
+impl ChannelRateLimitPlugin {
+    fn remove_disconnected(
+        mut server_events: EventReader<ServerEvent>,
+        mut rate_limit: ResMut<ChannelRateLimit>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                rate_limit.remove_peer(client_id);
+            }
+        }
+    }
+
+    pub(super) fn limit(
+        time: Res<Time<Real>>,
+        channels: Res<RepliconChannels>,
+        mut rate_limit: ResMut<ChannelRateLimit>,
+        mut server: ResMut<RepliconServer>,
+    ) {
+        rate_limit.refill(time.delta());
+
+        let messages: Vec<_> = server.drain_sent().collect();
+        rate_limit
+            .release(|client_id, channel_id, message| server.send(client_id, channel_id, message));
+        for (client_id, channel_id, message) in messages {
+            let reliable =
+                channels.server_channels()[channel_id as usize].kind != ChannelKind::Unreliable;
+            rate_limit.send(client_id, channel_id, reliable, message, |message| {
+                server.send(client_id, channel_id, message)
+            });
+        }
+    }
+}
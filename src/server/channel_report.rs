@@ -0,0 +1,84 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::{ChannelConfig, RenetServer};
+use bevy_replicon::prelude::*;
+
+use crate::{
+    channel_report::{ChannelDirection, ChannelReport, ChannelThroughput},
+    ChannelGroup, ChannelRemap,
+};
+
+/// Assembles [`ChannelReport`] for each channel of a connected client.
+///
+/// Configs are derived from [`RepliconChannels`] the same way as in
+/// [`RenetChannelsExt::get_server_configs`](crate::RenetChannelsExt::get_server_configs),
+/// since renet doesn't expose the configs it was created with.
+/// If the server was created with customized configs, like from
+/// [`ChannelPolicy`](crate::ChannelPolicy) or [`ChannelProfile`](crate::ChannelProfile),
+/// `send_type` and `max_memory_usage_bytes` will show the defaults.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::ServerChannelReport;
+///
+/// fn log_channels(connected_clients: Res<ConnectedClients>, report: ServerChannelReport) {
+///     for client in connected_clients.iter() {
+///         for channel in report.channel_report(client.id()) {
+///             info!("`{:?}`: {channel:?}", client.id());
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ServerChannelReport<'w> {
+    channels: Res<'w, RepliconChannels>,
+    remap: Res<'w, ChannelRemap>,
+    group: Res<'w, ChannelGroup>,
+    throughput: Res<'w, ChannelThroughput>,
+    renet_server: Option<Res<'w, RenetServer>>,
+}
+
+impl ServerChannelReport<'_> {
+    /// Returns reports for server channels followed by client channels.
+    ///
+    /// Memory usage is [`None`] if the client is not connected to renet.
+    pub fn channel_report(&self, client_id: ClientId) -> Vec<ChannelReport> {
+        let connected = self
+            .renet_server
+            .as_ref()
+            .filter(|renet_server| renet_server.is_connected(client_id.get()));
+
+        let sent = self.configs(self.channels.server_channels()).map(|config| {
+            let available_memory = connected.map(|renet_server| {
+                let wire_id = self.remap.server_wire_id(config.channel_id);
+                renet_server.channel_available_memory(client_id.get(), wire_id)
+            });
+            let throughput =
+                self.throughput
+                    .last(client_id, ChannelDirection::Send, config.channel_id);
+            ChannelReport::new(config, ChannelDirection::Send, available_memory, throughput)
+        });
+
+        let received = self.configs(self.channels.client_channels()).map(|config| {
+            let throughput =
+                self.throughput
+                    .last(client_id, ChannelDirection::Receive, config.channel_id);
+            ChannelReport::new(config, ChannelDirection::Receive, None, throughput)
+        });
+
+        sent.chain(received).collect()
+    }
+
+    fn configs(&self, channels: &[RepliconChannel]) -> impl Iterator<Item = ChannelConfig> {
+        let group = *self.group;
+        crate::create_configs(
+            channels,
+            self.channels.default_max_bytes,
+            &Default::default(),
+        )
+        .into_iter()
+        .filter(move |config| group.contains(config.channel_id))
+    }
+}
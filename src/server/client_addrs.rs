@@ -0,0 +1,97 @@
+use std::net::SocketAddr;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_replicon::prelude::*;
+
+use super::multi_socket::MultiSocketServerTransport;
+
+/// Remote socket addresses of connected clients.
+///
+/// Updated from [`NetcodeServerTransport`] or [`MultiSocketServerTransport`] in [`ServerSet::SendEvents`], so the address
+/// of a newly connected client is usually available in the same frame as [`ServerEvent::ClientConnected`].
+/// If the transport doesn't know the address yet, it will be picked up in the next frames.
+/// Addresses are removed once clients disconnect.
+///
+/// Only available with the `renet_netcode` feature. For the Steam transport renet's
+/// client ID is already the Steam ID of the client.
+#[derive(Resource, Default, Debug)]
+pub struct ClientAddrs {
+    addrs: HashMap<ClientId, SocketAddr>,
+    pending: HashSet<ClientId>,
+}
+
+impl ClientAddrs {
+    /// Returns the address of a connected client.
+    pub fn get(&self, client_id: ClientId) -> Option<SocketAddr> {
+        self.addrs.get(&client_id).copied()
+    }
+
+    /// Returns an iterator over all known client addresses.
+    pub fn iter(&self) -> impl Iterator<Item = (ClientId, SocketAddr)> + '_ {
+        self.addrs
+            .iter()
+            .map(|(&client_id, &addr)| (client_id, addr))
+    }
+}
+
+pub(super) struct ClientAddrsPlugin;
+
+impl Plugin for ClientAddrsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClientAddrs>().add_systems(
+            PreUpdate,
+            Self::update_addrs
+                .in_set(ServerSet::SendEvents)
+                .after(super::RepliconRenetServerPlugin::forward_server_events),
+        );
+    }
+}
+
+impl ClientAddrsPlugin {
+    fn update_addrs(
+        mut client_addrs: ResMut<ClientAddrs>,
+        mut server_events: EventReader<ServerEvent>,
+        transport: Option<Res<NetcodeServerTransport>>,
+        multi_transport: Option<Res<MultiSocketServerTransport>>,
+    ) {
+        for event in server_events.read() {
+            match *event {
+                ServerEvent::ClientConnected { client_id } => {
+                    client_addrs.pending.insert(client_id);
+                }
+                ServerEvent::ClientDisconnected { client_id, .. } => {
+                    client_addrs.pending.remove(&client_id);
+                    client_addrs.addrs.remove(&client_id);
+                }
+            }
+        }
+
+        if transport.is_none() && multi_transport.is_none() {
+            client_addrs.pending.clear();
+            client_addrs.addrs.clear();
+            return;
+        }
+
+        let ClientAddrs { addrs, pending } = &mut *client_addrs;
+        pending.retain(|&client_id| {
+            let addr = transport
+                .as_ref()
+                .and_then(|transport| transport.client_addr(client_id.get()))
+                .or_else(|| {
+                    multi_transport
+                        .as_ref()
+                        .and_then(|transport| transport.client_addr(client_id.get()))
+                });
+            let Some(addr) = addr else {
+                return true;
+            };
+
+            addrs.insert(client_id, addr);
+            false
+        });
+    }
+}
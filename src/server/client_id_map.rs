@@ -0,0 +1,94 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+/// Read-only view of how clients from [`ConnectedClients`] map to renet connections.
+///
+/// Replicon's [`ClientId`] wraps the ID renet assigned to the connection, so lookups translate
+/// between them only for clients that Replicon knows about. Useful for debugging and tooling that
+/// works with both [`RenetServer`] and Replicon.
+///
+/// The mapping is managed by the forwarding systems and Replicon, so it can't be modified here.
+/// To remove a client, disconnect it via [`RenetServer::disconnect`] and it will be removed from
+/// [`ConnectedClients`] through the usual [`ServerEvent::ClientDisconnected`] path.
+#[derive(SystemParam)]
+pub struct ClientIdMap<'w> {
+    connected_clients: Res<'w, ConnectedClients>,
+    renet_server: Option<Res<'w, RenetServer>>,
+}
+
+impl ClientIdMap<'_> {
+    /// Returns the renet ID of a client from [`ConnectedClients`].
+    pub fn renet_id(&self, client_id: ClientId) -> Option<u64> {
+        self.contains(client_id).then(|| client_id.get())
+    }
+
+    /// Returns the Replicon ID of a renet connection if the client is in [`ConnectedClients`].
+    pub fn client_id(&self, renet_id: u64) -> Option<ClientId> {
+        let client_id = ClientId::new(renet_id);
+        self.contains(client_id).then_some(client_id)
+    }
+
+    /// Iterates over Replicon and renet IDs of all clients from [`ConnectedClients`].
+    pub fn iter(&self) -> impl Iterator<Item = (ClientId, u64)> + '_ {
+        self.connected_clients
+            .iter()
+            .map(|client| (client.id(), client.id().get()))
+    }
+
+    /// Returns the number of clients from [`ConnectedClients`].
+    pub fn len(&self) -> usize {
+        self.connected_clients.len()
+    }
+
+    /// Returns `true` if [`ConnectedClients`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.connected_clients.is_empty()
+    }
+
+    /// Returns clients whose state differs between [`ConnectedClients`] and [`RenetServer`].
+    ///
+    /// Renet processes connections before Replicon, so differences are expected for a frame after
+    /// a client connects or disconnects. Connections deferred by the handshake from
+    /// [`HandshakeMetadata`](crate::HandshakeMetadata) or [`AdmissionControl`](crate::AdmissionControl) are reported as [`ClientIdMismatch::MissingInReplicon`]
+    /// until they are accepted, as well as rejected connections until renet removes them.
+    /// Differences that persist for longer indicate a bug.
+    ///
+    /// Returns an empty list if [`RenetServer`] doesn't exist.
+    pub fn mismatches(&self) -> Vec<ClientIdMismatch> {
+        let Some(renet_server) = &self.renet_server else {
+            return Vec::new();
+        };
+
+        let mut mismatches: Vec<_> = self
+            .connected_clients
+            .iter()
+            .filter(|client| !renet_server.is_connected(client.id().get()))
+            .map(|client| ClientIdMismatch::MissingInRenet(client.id()))
+            .collect();
+
+        mismatches.extend(
+            renet_server
+                .clients_id_iter()
+                .filter(|&renet_id| !self.contains(ClientId::new(renet_id)))
+                .map(ClientIdMismatch::MissingInReplicon),
+        );
+
+        mismatches
+    }
+
+    fn contains(&self, client_id: ClientId) -> bool {
+        self.connected_clients
+            .iter()
+            .any(|client| client.id() == client_id)
+    }
+}
+
+/// Difference between [`ConnectedClients`] and [`RenetServer`] returned by [`ClientIdMap::mismatches`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientIdMismatch {
+    /// The client is in [`ConnectedClients`], but isn't connected in renet.
+    MissingInRenet(ClientId),
+    /// The renet connection with this ID is not in [`ConnectedClients`].
+    MissingInReplicon(u64),
+}
@@ -0,0 +1,140 @@
+use bevy::{prelude::*, utils::HashMap};
+#[cfg(feature = "renet_netcode")]
+use bevy_renet::netcode::NetcodeServerTransport;
+#[cfg(feature = "renet_steam")]
+use bevy_renet::steam::SteamServerTransport;
+use bevy_replicon::prelude::*;
+
+#[cfg(feature = "renet_netcode")]
+use super::multi_socket::MultiSocketServerTransport;
+
+/// Transport over which a client is connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientTransport {
+    /// `NetcodeServerTransport` or `MultiSocketServerTransport`, available with the `renet_netcode` feature.
+    Netcode,
+    /// `SteamServerTransport`, available with the `renet_steam` feature.
+    Steam,
+    /// Local client created via [`RenetServer::new_local_client`](bevy_renet::renet::RenetServer::new_local_client).
+    Memory,
+    /// Transport not provided by renet, set via [`ClientTransports::set`].
+    Custom,
+}
+
+/// Transports of connected clients.
+///
+/// Updated in [`ServerSet::SendEvents`] when Replicon emits [`ServerEvent::ClientConnected`], so the transport
+/// is available in [`ClientCleanupSet`](super::ClientCleanupSet) and later systems of the same frame.
+/// Useful to gate features in cross-play, like granting achievements only to Steam clients.
+///
+/// All transports feed the same [`RenetServer`](bevy_renet::renet::RenetServer) and renet doesn't
+/// track where connections come from, so the transport is detected from transport resources:
+///
+/// 1. [`ClientTransport::Netcode`] if a netcode transport knows the client's address.
+/// 2. [`ClientTransport::Steam`] if `SteamServerTransport` exists.
+/// 3. [`ClientTransport::Memory`] otherwise.
+///
+/// Clients of custom transports are detected as [`ClientTransport::Memory`], use [`Self::set`]
+/// to override it in [`ClientCleanupSet`](super::ClientCleanupSet).
+///
+/// Transports are removed after [`ClientCleanupSet`](super::ClientCleanupSet) once clients disconnect.
+#[derive(Resource, Default, Debug)]
+pub struct ClientTransports(HashMap<ClientId, ClientTransport>);
+
+impl ClientTransports {
+    /// Returns the transport of a connected client.
+    pub fn get(&self, client_id: ClientId) -> Option<ClientTransport> {
+        self.0.get(&client_id).copied()
+    }
+
+    /// Overrides the detected transport of a connected client.
+    ///
+    /// Ignored if the client is not connected.
+    pub fn set(&mut self, client_id: ClientId, transport: ClientTransport) {
+        if let Some(current) = self.0.get_mut(&client_id) {
+            *current = transport;
+        }
+    }
+
+    /// Returns an iterator over transports of all connected clients.
+    pub fn iter(&self) -> impl Iterator<Item = (ClientId, ClientTransport)> + '_ {
+        self.0
+            .iter()
+            .map(|(&client_id, &transport)| (client_id, transport))
+    }
+}
+
+pub(super) struct ClientTransportPlugin;
+
+impl Plugin for ClientTransportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClientTransports>().add_systems(
+            PreUpdate,
+            (
+                Self::detect
+                    .in_set(ServerSet::SendEvents)
+                    .after(super::RepliconRenetServerPlugin::forward_server_events)
+                    .before(super::ClientCleanupSet),
+                Self::remove_disconnected
+                    .after(super::ClientCleanupSet)
+                    .before(ServerSet::Receive),
+            ),
+        );
+    }
+}
+
+impl ClientTransportPlugin {
+    fn detect(
+        mut server_events: EventReader<ServerEvent>,
+        mut client_transports: ResMut<ClientTransports>,
+        #[cfg(feature = "renet_netcode")] netcode_transport: Option<Res<NetcodeServerTransport>>,
+        #[cfg(feature = "renet_netcode")] multi_transport: Option<Res<MultiSocketServerTransport>>,
+        #[cfg(feature = "renet_steam")] steam_transport: Option<NonSend<SteamServerTransport>>,
+    ) {
+        for event in server_events.read() {
+            let ServerEvent::ClientConnected { client_id } = *event else {
+                continue;
+            };
+
+            #[cfg(feature = "renet_netcode")]
+            let netcode = netcode_transport
+                .as_ref()
+                .and_then(|transport| transport.client_addr(client_id.get()))
+                .or_else(|| {
+                    multi_transport
+                        .as_ref()
+                        .and_then(|transport| transport.client_addr(client_id.get()))
+                })
+                .is_some();
+            #[cfg(not(feature = "renet_netcode"))]
+            let netcode = false;
+
+            #[cfg(feature = "renet_steam")]
+            let steam = steam_transport.is_some();
+            #[cfg(not(feature = "renet_steam"))]
+            let steam = false;
+
+            let transport = if netcode {
+                ClientTransport::Netcode
+            } else if steam {
+                ClientTransport::Steam
+            } else {
+                ClientTransport::Memory
+            };
+
+            debug!("`{client_id:?}` connected over `{transport:?}`");
+            client_transports.0.insert(client_id, transport);
+        }
+    }
+
+    fn remove_disconnected(
+        mut server_events: EventReader<ServerEvent>,
+        mut client_transports: ResMut<ClientTransports>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = event {
+                client_transports.0.remove(client_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use crate::clock_sync::{self, ClockSyncChannel};
+
+pub(super) struct ClockSyncPlugin;
+
+impl Plugin for ClockSyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::reply
+                .in_set(ServerSet::ReceivePackets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<ClockSyncChannel>),
+        );
+    }
+}
+
+impl ClockSyncPlugin {
+    /// Replies to timestamp requests from clients with the current server time.
+    fn reply(
+        time: Res<Time<Real>>,
+        channel: Res<ClockSyncChannel>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        for client_id in renet_server.clients_id() {
+            while let Some(message) = renet_server.receive_message(client_id, **channel) {
+                let Some(reply) = clock_sync::encode_reply(&message, time.elapsed()) else {
+                    debug!("ignoring invalid timestamp request from client `{client_id}`");
+                    continue;
+                };
+
+                renet_server.send_message(client_id, **channel, reply);
+            }
+        }
+    }
+}
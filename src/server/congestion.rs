@@ -0,0 +1,211 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_renet::renet::{ChannelConfig, RenetServer};
+use bevy_replicon::prelude::*;
+
+use super::backpressure::SendBackpressure;
+
+/// Congestion of server channels of each client, derived from renet's channel memory.
+///
+/// Renet keeps sent reliable messages in channel memory until the client acknowledges them,
+/// so a channel that is close to its `max_memory_usage_bytes` means the client can't keep up.
+/// Sending more over such a channel leads to [`SendBackpressure`] and eventually to a disconnect.
+/// This resource exposes the usage of each channel as a ratio from 0 to 1, and a channel is considered
+/// congested once its usage reaches [`Self::threshold`] or messages for it are held back by [`SendBackpressure`].
+///
+/// Replicon schedules replication by itself and doesn't provide a way to defer it per client,
+/// so the signal is meant to be consulted by the game's systems, for example, to replicate fewer
+/// entities to a congested client by updating its visibility or to skip low-priority events.
+///
+/// Usage is updated in [`ServerSet::SendPackets`] after messages are passed to renet. So systems in
+/// [`Update`] see the state after the previous frame's send with acknowledgements received in between,
+/// which means the feedback is one frame behind. Clients disconnected from renet are removed on update.
+///
+/// Disabled by default, insert this resource to enable it.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{CongestionSignal, RenetChannelsExt};
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(RepliconPlugins);
+/// let channels = app.world().resource::<RepliconChannels>();
+/// let server_configs = channels.get_server_configs();
+/// app.insert_resource(CongestionSignal::new(&server_configs).with_threshold(0.5));
+///
+/// fn send_chat(congestion: Res<CongestionSignal>, connected_clients: Res<ConnectedClients>) {
+///     for client in connected_clients.iter() {
+///         if congestion.is_congested(client.id()) {
+///             // Skip cosmetic messages for this client.
+///             continue;
+///         }
+///         // ...
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct CongestionSignal {
+    /// Usage ratio from 0 to 1 at which a channel is considered congested.
+    ///
+    /// By default, [`Self::DEFAULT_THRESHOLD`].
+    pub threshold: f32,
+
+    channel_limits: Vec<(u8, usize)>,
+    usage: HashMap<(ClientId, u8), f32>,
+}
+
+impl CongestionSignal {
+    /// Default value for [`Self::threshold`].
+    pub const DEFAULT_THRESHOLD: f32 = 0.75;
+
+    /// Creates a signal for a server created with the given channel configs.
+    ///
+    /// Configs are used to know the limits of each channel, so they must match the ones
+    /// passed to [`ConnectionConfig::server_channels_config`](bevy_renet::renet::ConnectionConfig::server_channels_config).
+    /// Channel IDs are renet's IDs, which match IDs from [`RepliconChannels`] unless
+    /// [`ChannelRemap`](crate::ChannelRemap) is configured.
+    pub fn new(server_configs: &[ChannelConfig]) -> Self {
+        Self {
+            threshold: Self::DEFAULT_THRESHOLD,
+            channel_limits: server_configs
+                .iter()
+                .map(|config| (config.channel_id, config.max_memory_usage_bytes))
+                .collect(),
+            usage: Default::default(),
+        }
+    }
+
+    /// Sets [`Self::threshold`].
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns the usage ratio of the client's channel from 0 to 1.
+    ///
+    /// Returns 1 if messages for the channel are held back by [`SendBackpressure`]
+    /// and [`None`] if the client or the channel is unknown.
+    pub fn usage(&self, client_id: ClientId, channel_id: u8) -> Option<f32> {
+        self.usage.get(&(client_id, channel_id)).copied()
+    }
+
+    /// Returns `true` if the client's channel is congested.
+    pub fn is_channel_congested(&self, client_id: ClientId, channel_id: u8) -> bool {
+        self.usage(client_id, channel_id)
+            .is_some_and(|usage| usage >= self.threshold)
+    }
+
+    /// Returns `true` if any channel of the client is congested.
+    pub fn is_congested(&self, client_id: ClientId) -> bool {
+        self.usage.iter().any(|(&(usage_client, _), &usage)| {
+            usage_client == client_id && usage >= self.threshold
+        })
+    }
+
+    /// Returns clients with at least one congested channel.
+    pub fn congested_clients(&self) -> HashSet<ClientId> {
+        self.usage
+            .iter()
+            .filter(|(_, &usage)| usage >= self.threshold)
+            .map(|(&(client_id, _), _)| client_id)
+            .collect()
+    }
+
+    fn update(&mut self, renet_server: &RenetServer, backpressure: &SendBackpressure) {
+        self.usage.clear();
+        for client_id in renet_server.clients_id_iter() {
+            if !renet_server.is_connected(client_id) {
+                continue;
+            }
+
+            let client_id = ClientId::new(client_id);
+            for &(channel_id, limit) in &self.channel_limits {
+                let usage = if backpressure.queued_messages(client_id, channel_id) > 0 {
+                    1.0
+                } else {
+                    let available =
+                        renet_server.channel_available_memory(client_id.get(), channel_id);
+                    limit.saturating_sub(available) as f32 / limit.max(1) as f32
+                };
+                self.usage.insert((client_id, channel_id), usage);
+            }
+        }
+    }
+}
+
+pub(super) struct CongestionPlugin;
+
+impl Plugin for CongestionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            Self::update
+                .in_set(ServerSet::SendPackets)
+                .after(super::RepliconRenetServerPlugin::send_packets)
+                .run_if(resource_exists::<CongestionSignal>),
+        );
+    }
+}
+
+impl CongestionPlugin {
+    fn update(
+        renet_server: Option<Res<RenetServer>>,
+        backpressure: Res<SendBackpressure>,
+        mut congestion: ResMut<CongestionSignal>,
+    ) {
+        match renet_server {
+            Some(renet_server) => congestion.update(&renet_server, &backpressure),
+            None => congestion.usage.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_renet::renet::{ConnectionConfig, SendType};
+
+    use super::*;
+
+    #[test]
+    fn usage() {
+        let channels_config = vec![ChannelConfig {
+            channel_id: CHANNEL_ID,
+            max_memory_usage_bytes: 100,
+            send_type: SendType::ReliableOrdered {
+                resend_time: Default::default(),
+            },
+        }];
+        let mut renet_server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels_config.clone(),
+            client_channels_config: channels_config.clone(),
+            ..Default::default()
+        });
+        renet_server.add_connection(CLIENT_ID.get());
+
+        let mut congestion = CongestionSignal::new(&channels_config).with_threshold(0.5);
+        let backpressure = SendBackpressure::default();
+
+        congestion.update(&renet_server, &backpressure);
+        assert_eq!(congestion.usage(CLIENT_ID, CHANNEL_ID), Some(0.0));
+        assert!(!congestion.is_congested(CLIENT_ID));
+
+        renet_server.send_message(CLIENT_ID.get(), CHANNEL_ID, vec![0; 60]);
+        congestion.update(&renet_server, &backpressure);
+        assert_eq!(congestion.usage(CLIENT_ID, CHANNEL_ID), Some(0.6));
+        assert!(congestion.is_channel_congested(CLIENT_ID, CHANNEL_ID));
+        assert!(congestion.congested_clients().contains(&CLIENT_ID));
+
+        renet_server.remove_connection(CLIENT_ID.get());
+        congestion.update(&renet_server, &backpressure);
+        assert_eq!(congestion.usage(CLIENT_ID, CHANNEL_ID), None);
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const CHANNEL_ID: u8 = 0;
+}
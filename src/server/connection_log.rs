@@ -0,0 +1,127 @@
+use bevy::{prelude::*, utils::tracing::Level};
+#[cfg(feature = "renet_netcode")]
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_replicon::prelude::*;
+
+use super::{connection_phase::ConnectionPhases, rejection::ClientRejected};
+
+/// Level at which the server logs connection lifecycle.
+///
+/// Each connect, disconnect and rejection is logged as a single line with `client_id`,
+/// `addr` and `reason` fields, so it can be kept in production separately from per-message tracing.
+/// Lines are logged inside the client's span from [`ConnectionPhases`](crate::ConnectionPhases).
+/// The address is currently only provided by the netcode transport and only while the client
+/// is connected, so it's not available for disconnects.
+///
+/// Set to [`None`] to disable these logs. By default set to [`Level::DEBUG`].
+///
+/// # Examples
+///
+/// ```
+/// use bevy::{prelude::*, utils::tracing::Level};
+/// use bevy_replicon_renet::ConnectionLogLevel;
+///
+/// # let mut app = App::new();
+/// app.insert_resource(ConnectionLogLevel(Some(Level::INFO)));
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct ConnectionLogLevel(pub Option<Level>);
+
+impl Default for ConnectionLogLevel {
+    fn default() -> Self {
+        Self(Some(Level::DEBUG))
+    }
+}
+
+/// Logs with the level that is known only at runtime.
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)+) => {
+        let level = $level;
+        if level == Level::ERROR {
+            error!($($arg)+)
+        } else if level == Level::WARN {
+            warn!($($arg)+)
+        } else if level == Level::INFO {
+            info!($($arg)+)
+        } else if level == Level::DEBUG {
+            debug!($($arg)+)
+        } else {
+            trace!($($arg)+)
+        }
+    };
+}
+
+pub(super) struct ConnectionLogPlugin;
+
+impl Plugin for ConnectionLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConnectionLogLevel>().add_systems(
+            PreUpdate,
+            Self::log_connections
+                .in_set(ServerSet::SendEvents)
+                .after(super::RepliconRenetServerPlugin::forward_server_events),
+        );
+    }
+}
+
+impl ConnectionLogPlugin {
+    pub(super) fn log_connections(
+        level: Res<ConnectionLogLevel>,
+        phases: Res<ConnectionPhases>,
+        mut server_events: EventReader<ServerEvent>,
+        mut rejected_events: EventReader<ClientRejected>,
+        #[cfg(feature = "renet_netcode")] netcode_transport: Option<Res<NetcodeServerTransport>>,
+    ) {
+        let Some(level) = **level else {
+            return;
+        };
+
+        #[cfg(feature = "renet_netcode")]
+        let client_addr = |client_id: ClientId| {
+            netcode_transport
+                .as_ref()
+                .and_then(|transport| transport.client_addr(client_id.get()))
+        };
+        #[cfg(not(feature = "renet_netcode"))]
+        let client_addr = |_| None::<std::net::SocketAddr>;
+
+        for event in server_events.read() {
+            let client_id = match event {
+                ServerEvent::ClientConnected { client_id } => client_id,
+                ServerEvent::ClientDisconnected { client_id, .. } => client_id,
+            };
+            let _span = phases.span(*client_id).entered();
+            match event {
+                ServerEvent::ClientConnected { client_id } => {
+                    let addr = client_addr(*client_id);
+                    log_at!(
+                        level,
+                        client_id = client_id.get(),
+                        ?addr,
+                        "client connected"
+                    );
+                }
+                ServerEvent::ClientDisconnected { client_id, reason } => {
+                    log_at!(
+                        level,
+                        client_id = client_id.get(),
+                        %reason,
+                        "client disconnected"
+                    );
+                }
+            }
+        }
+
+        for event in rejected_events.read() {
+            let _span = phases.span(event.client_id).entered();
+            let addr = client_addr(event.client_id);
+            log_at!(
+                level,
+                client_id = event.client_id.get(),
+                ?addr,
+                reason = ?event.reason,
+                "client rejected"
+            );
+        }
+    }
+}
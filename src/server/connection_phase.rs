@@ -0,0 +1,237 @@
+use bevy::{
+    prelude::*,
+    utils::{
+        tracing::{field, Span},
+        HashMap,
+    },
+};
+use bevy_renet::renet::{self, RenetServer};
+use bevy_replicon::prelude::*;
+
+use super::{
+    connection_log::ConnectionLogPlugin,
+    kick::{KickClient, KickPlugin},
+};
+
+/// Phase of a client's connection, tracked in [`ConnectionPhases`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionPhase {
+    /// Renet accepted the connection, but the client wasn't passed to Replicon yet.
+    ///
+    /// Covers the handshake enabled by [`HandshakeMetadata`](crate::HandshakeMetadata),
+    /// [`AdmissionControl`](crate::AdmissionControl) and other checks that can reject the client with [`ClientRejected`](crate::ClientRejected).
+    Connecting,
+
+    /// The client is in [`ConnectedClients`], but not in [`ReplicatedClients`].
+    ///
+    /// Lasts until the game sends [`StartReplication`] if [`ServerPlugin::replicate_after_connect`] is disabled.
+    /// Otherwise lasts only until Replicon processes the connection in [`ServerSet::Receive`].
+    Authorizing,
+
+    /// The client receives replication.
+    Connected,
+
+    /// The client was kicked or renet closed the connection.
+    ///
+    /// Lasts until the connection is closed and the client is cleaned up in [`ClientCleanupSet`](crate::ClientCleanupSet).
+    Disconnecting,
+}
+
+impl ConnectionPhase {
+    /// Returns the value of the `phase` field in logs.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Connecting => "connecting",
+            Self::Authorizing => "authorizing",
+            Self::Connected => "connected",
+            Self::Disconnecting => "disconnecting",
+        }
+    }
+}
+
+/// Current [`ConnectionPhase`] of each client known to renet.
+///
+/// Logs of the crate related to a specific client, like handshake, kicks and connection lifecycle from
+/// [`ConnectionLogLevel`](crate::ConnectionLogLevel), are emitted inside a `connection` span with `client_id`
+/// and `phase` fields. This allows filtering logs of a specific phase, for example, to see why connections
+/// fail during authorization with the `[connection{phase=authorizing}]` directive in the log filter.
+/// Per-message forwarding logs are not wrapped into spans to keep forwarding cheap.
+///
+/// Lifecycle lines are logged in the phase they end: "client connected" is logged with `connecting`,
+/// "client disconnected" with the phase in which the client was disconnected and "client rejected"
+/// with `connecting`. Each phase change is logged at the debug level.
+///
+/// Use [`Self::span`] to log game-specific messages with the same context.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::ConnectionPhases;
+///
+/// fn log_authorization(mut server_events: EventReader<ServerEvent>, phases: Res<ConnectionPhases>) {
+///     for event in server_events.read() {
+///         if let ServerEvent::ClientConnected { client_id } = *event {
+///             let _span = phases.span(client_id).entered();
+///             info!("checking credentials");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct ConnectionPhases(HashMap<ClientId, ConnectionPhase>);
+
+impl ConnectionPhases {
+    /// Returns the phase of the client or [`None`] if renet doesn't know it.
+    pub fn get(&self, client_id: ClientId) -> Option<ConnectionPhase> {
+        self.0.get(&client_id).copied()
+    }
+
+    /// Iterates over all tracked clients with their phases.
+    pub fn iter(&self) -> impl Iterator<Item = (ClientId, ConnectionPhase)> + '_ {
+        self.0.iter().map(|(&client_id, &phase)| (client_id, phase))
+    }
+
+    /// Returns a span with `client_id` and `phase` of the client.
+    ///
+    /// `phase` is empty for unknown clients.
+    pub fn span(&self, client_id: ClientId) -> Span {
+        let span = debug_span!(
+            "connection",
+            client_id = client_id.get(),
+            phase = field::Empty
+        );
+        if let Some(phase) = self.get(client_id) {
+            span.record("phase", phase.as_str());
+        }
+        span
+    }
+
+    fn set(&mut self, client_id: ClientId, phase: ConnectionPhase) {
+        let Some(previous) = self.0.insert(client_id, phase) else {
+            debug!(
+                client_id = client_id.get(),
+                phase = phase.as_str(),
+                "connection phase started"
+            );
+            return;
+        };
+
+        if previous != phase {
+            let _span = debug_span!(
+                "connection",
+                client_id = client_id.get(),
+                phase = previous.as_str()
+            )
+            .entered();
+            debug!(next = phase.as_str(), "connection phase changed");
+        }
+    }
+}
+
+pub(super) struct ConnectionPhasePlugin;
+
+impl Plugin for ConnectionPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConnectionPhases>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::start
+                        .in_set(ServerSet::SendEvents)
+                        .before(super::RepliconRenetServerPlugin::forward_server_events),
+                    Self::update
+                        .in_set(ServerSet::SendEvents)
+                        .after(ConnectionLogPlugin::log_connections),
+                    Self::remove_disconnected
+                        .after(super::ClientCleanupSet)
+                        .before(ServerSet::Receive),
+                    Self::promote_replicated.after(ServerSet::Receive),
+                    Self::reset
+                        .after(ConnectionLogPlugin::log_connections)
+                        .run_if(server_just_stopped),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::mark_kicked
+                    .before(KickPlugin::send)
+                    .before(super::RepliconRenetServerPlugin::send_packets)
+                    .in_set(ServerSet::SendPackets)
+                    .run_if(resource_exists::<RenetServer>),
+            );
+    }
+}
+
+impl ConnectionPhasePlugin {
+    fn start(
+        mut renet_server_events: EventReader<renet::ServerEvent>,
+        mut phases: ResMut<ConnectionPhases>,
+    ) {
+        for event in renet_server_events.read() {
+            if let renet::ServerEvent::ClientConnected { client_id } = *event {
+                phases.set(ClientId::new(client_id), ConnectionPhase::Connecting);
+            }
+        }
+    }
+
+    /// Updates phases after [`ConnectionLogPlugin::log_connections`], so lifecycle lines are logged
+    /// in the phase they end.
+    fn update(
+        mut renet_server_events: EventReader<renet::ServerEvent>,
+        mut server_events: EventReader<ServerEvent>,
+        mut phases: ResMut<ConnectionPhases>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientConnected { client_id } = *event {
+                phases.set(client_id, ConnectionPhase::Authorizing);
+            }
+        }
+
+        // Also covers clients that disconnected before reaching Replicon.
+        for event in renet_server_events.read() {
+            if let renet::ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                phases.set(ClientId::new(client_id), ConnectionPhase::Disconnecting);
+            }
+        }
+    }
+
+    fn remove_disconnected(
+        mut renet_server_events: EventReader<renet::ServerEvent>,
+        mut phases: ResMut<ConnectionPhases>,
+    ) {
+        for event in renet_server_events.read() {
+            if let renet::ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                phases.0.remove(&ClientId::new(client_id));
+            }
+        }
+    }
+
+    fn promote_replicated(
+        replicated_clients: Res<ReplicatedClients>,
+        mut phases: ResMut<ConnectionPhases>,
+    ) {
+        for client in replicated_clients.iter() {
+            if phases.get(client.id()) == Some(ConnectionPhase::Authorizing) {
+                phases.set(client.id(), ConnectionPhase::Connected);
+            }
+        }
+    }
+
+    fn mark_kicked(
+        mut kick_events: EventReader<KickClient>,
+        renet_server: Res<RenetServer>,
+        mut phases: ResMut<ConnectionPhases>,
+    ) {
+        for event in kick_events.read() {
+            if renet_server.is_connected(event.client_id.get()) {
+                phases.set(event.client_id, ConnectionPhase::Disconnecting);
+            }
+        }
+    }
+
+    fn reset(mut phases: ResMut<ConnectionPhases>) {
+        phases.0.clear();
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_replicon::prelude::*;
+
+use super::disconnect::ClientDisconnected;
+use crate::RepliconDisconnectReason;
+
+/// Cumulative connection statistics of the server.
+///
+/// Counts clients accepted into Replicon via [`ServerEvent::ClientConnected`] and their disconnects
+/// via [`ClientDisconnected`], broken down by [`RepliconDisconnectReason`]. Clients rejected before
+/// reaching Replicon, like with [`ClientRejected`](crate::ClientRejected), are not counted.
+/// Updated in [`ServerSet::SendEvents`] and kept across server restarts, so it can be used for dashboards
+/// that track churn over the lifetime of the app. Clients connected while the server stops are counted
+/// with [`RepliconDisconnectReason::ServerShutdown`].
+///
+/// To export these counters to external monitoring, enable the `metrics` feature and add `ConnectionMetrics`.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::{ConnectionStats, RepliconDisconnectReason};
+///
+/// fn report_churn(stats: Res<ConnectionStats>) {
+///     info!(
+///         "{} connected, {} accepted, {} timed out",
+///         stats.current(),
+///         stats.accepted(),
+///         stats.disconnects_by(RepliconDisconnectReason::Timeout),
+///     );
+/// }
+/// ```
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ConnectionStats {
+    accepted: u64,
+    disconnects: HashMap<RepliconDisconnectReason, u64>,
+}
+
+impl ConnectionStats {
+    /// Returns the total number of accepted clients.
+    pub fn accepted(&self) -> u64 {
+        self.accepted
+    }
+
+    /// Returns the total number of disconnects for all reasons.
+    pub fn disconnects(&self) -> u64 {
+        self.disconnects.values().sum()
+    }
+
+    /// Returns the total number of disconnects with the given reason.
+    pub fn disconnects_by(&self, reason: RepliconDisconnectReason) -> u64 {
+        self.disconnects.get(&reason).copied().unwrap_or_default()
+    }
+
+    /// Iterates over disconnect reasons that occurred at least once with their counts.
+    pub fn iter_disconnects(&self) -> impl Iterator<Item = (RepliconDisconnectReason, u64)> + '_ {
+        self.disconnects
+            .iter()
+            .map(|(&reason, &count)| (reason, count))
+    }
+
+    /// Returns the number of currently connected clients.
+    pub fn current(&self) -> u64 {
+        self.accepted.saturating_sub(self.disconnects())
+    }
+}
+
+pub(super) struct ConnectionStatsPlugin;
+
+impl Plugin for ConnectionStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConnectionStats>().add_systems(
+            PreUpdate,
+            Self::update
+                .in_set(ServerSet::SendEvents)
+                .after(super::RepliconRenetServerPlugin::forward_server_events),
+        );
+    }
+}
+
+impl ConnectionStatsPlugin {
+    fn update(
+        mut server_events: EventReader<ServerEvent>,
+        mut disconnect_events: EventReader<ClientDisconnected>,
+        mut stats: ResMut<ConnectionStats>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientConnected { .. } = event {
+                stats.accepted += 1;
+            }
+        }
+
+        for event in disconnect_events.read() {
+            *stats.disconnects.entry(event.reason).or_default() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let mut stats = ConnectionStats {
+            accepted: 3,
+            ..Default::default()
+        };
+        stats
+            .disconnects
+            .insert(RepliconDisconnectReason::Timeout, 1);
+        stats
+            .disconnects
+            .insert(RepliconDisconnectReason::Kicked, 1);
+
+        assert_eq!(stats.disconnects(), 2);
+        assert_eq!(stats.disconnects_by(RepliconDisconnectReason::Timeout), 1);
+        assert_eq!(
+            stats.disconnects_by(RepliconDisconnectReason::ClientRequested),
+            0
+        );
+        assert_eq!(stats.current(), 1);
+    }
+}
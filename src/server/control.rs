@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, RenetServer};
+use bevy_replicon::prelude::*;
+
+use crate::control::{self, ControlChannel, ControlKind};
+
+pub(super) struct ControlPlugin;
+
+impl Plugin for ControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControlMessages>().add_systems(
+            PreUpdate,
+            Self::receive
+                .in_set(ServerSet::ReceivePackets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<ControlChannel>),
+        );
+    }
+}
+
+impl ControlPlugin {
+    /// Drains [`ControlChannel`] of all clients and dispatches messages by their kinds.
+    ///
+    /// Messages that were not taken by features during the previous frame are dropped.
+    pub(super) fn receive(
+        channel: Res<ControlChannel>,
+        mut messages: ResMut<ControlMessages>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        messages.0.clear();
+        for client_id in renet_server.clients_id() {
+            while let Some(message) = renet_server.receive_message(client_id, **channel) {
+                match control::decode(message) {
+                    Ok((kind, payload)) => {
+                        trace!("received control message `{kind:?}` from client `{client_id}`");
+                        messages.0.push((ClientId::new(client_id), kind, payload));
+                    }
+                    Err(kind) => debug!(
+                        "ignoring control message of unknown kind `{kind:?}` from client `{client_id}`"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Messages received over [`ControlChannel`] during this frame.
+///
+/// Features read them after [`ControlPlugin::receive`].
+#[derive(Resource, Default)]
+pub(super) struct ControlMessages(Vec<(ClientId, ControlKind, Bytes)>);
+
+impl ControlMessages {
+    /// Removes and returns senders and payloads of the given kind.
+    pub(super) fn take(&mut self, kind: ControlKind) -> Vec<(ClientId, Bytes)> {
+        let mut payloads = Vec::new();
+        self.0.retain(|(client_id, message_kind, payload)| {
+            if *message_kind != kind {
+                return true;
+            }
+
+            payloads.push((*client_id, payload.clone()));
+            false
+        });
+        payloads
+    }
+}
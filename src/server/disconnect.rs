@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+use crate::RepliconDisconnectReason;
+
+/// An event that indicates that a client passed to Replicon was disconnected.
+///
+/// Emitted alongside [`ServerEvent::ClientDisconnected`], but with a structured reason instead of a string.
+/// Also emitted with [`RepliconDisconnectReason::ServerShutdown`] for each connected client
+/// when [`RenetServer`](bevy_renet::renet::RenetServer) is removed.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientDisconnected {
+    pub client_id: ClientId,
+    pub reason: RepliconDisconnectReason,
+}
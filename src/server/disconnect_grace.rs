@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_replicon::prelude::*;
+
+use super::disconnect::ClientDisconnected;
+use crate::RepliconDisconnectReason;
+
+/// Time during which the game state of an unexpectedly disconnected client should be preserved for reconnection.
+///
+/// Brief network hiccups make renet disconnect a client after its timeout. With this resource, such clients
+/// are moved into [`ReconnectingClients`] instead of being forgotten. If a client with the same [`ClientId`]
+/// connects again before the grace expires, [`ClientReconnected`] is emitted. Otherwise
+/// [`ClientGraceExpired`] is emitted once the grace is over.
+///
+/// The crate doesn't own game entities, so preserving them is up to the game. Instead of despawning
+/// the player's entities on [`ServerEvent::ClientDisconnected`], check [`ReconnectingClients::contains`]
+/// in [`ClientCleanupSet`](crate::ClientCleanupSet), mark the entities as reconnecting and despawn them
+/// on [`ClientGraceExpired`]. After reconnection, Replicon treats the client as a new one and
+/// replicates the whole visible world to it, so the preserved entities are received as usual.
+///
+/// The grace applies only to disconnects with [`RepliconDisconnectReason::Timeout`] and
+/// [`RepliconDisconnectReason::TransportError`]. Clients that were kicked, failed the protocol or were disconnected
+/// by a server shutdown are not expected to come back. The netcode server transport reports both timeouts and
+/// client-initiated disconnects as [`RepliconDisconnectReason::TransportError`], so with it the grace applies
+/// to clients that left on their own too. When the server stops, all pending clients expire.
+///
+/// Netcode clients pick their ID in the connect token, so reconnecting with the same ID identifies the same player.
+/// Authenticate the ID if players can't be trusted, for example, with `AuthorizeByUserData`
+/// or a secure connect token. A reconnecting client doesn't have a reserved slot, so it's still subject to
+/// [`MaxClients`](crate::MaxClients) and other admission checks.
+///
+/// Disabled by default, insert this resource to enable it.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{ClientCleanupSet, ClientGraceExpired, DisconnectGrace, ReconnectingClients};
+///
+/// # let mut app = App::new();
+/// app.insert_resource(DisconnectGrace(Duration::from_secs(30)))
+///     .add_systems(PreUpdate, despawn_disconnected.in_set(ClientCleanupSet))
+///     .add_systems(Update, despawn_expired);
+///
+/// fn despawn_disconnected(
+///     mut server_events: EventReader<ServerEvent>,
+///     reconnecting: Res<ReconnectingClients>,
+/// ) {
+///     for event in server_events.read() {
+///         if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+///             if !reconnecting.contains(client_id) {
+///                 // Despawn the player's entities right away.
+///             }
+///         }
+///     }
+/// }
+///
+/// fn despawn_expired(mut expired_events: EventReader<ClientGraceExpired>) {
+///     for event in expired_events.read() {
+///         // Despawn entities of `event.client_id`.
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct DisconnectGrace(pub Duration);
+
+/// Clients that were disconnected within [`DisconnectGrace`] and may reconnect.
+///
+/// Clients are added in [`ServerSet::SendEvents`] in the same frame when [`ClientDisconnected`](crate::ClientDisconnected)
+/// is emitted, so it can be checked in [`ClientCleanupSet`](crate::ClientCleanupSet).
+#[derive(Resource, Default, Debug)]
+pub struct ReconnectingClients(HashMap<ClientId, Duration>);
+
+impl ReconnectingClients {
+    /// Returns `true` if the client is waiting for reconnection.
+    pub fn contains(&self, client_id: ClientId) -> bool {
+        self.0.contains_key(&client_id)
+    }
+
+    /// Returns the [`Time<Real>::elapsed`] at which the grace for the client expires.
+    pub fn deadline(&self, client_id: ClientId) -> Option<Duration> {
+        self.0.get(&client_id).copied()
+    }
+
+    /// Iterates over all clients waiting for reconnection.
+    pub fn iter(&self) -> impl Iterator<Item = ClientId> + '_ {
+        self.0.keys().copied()
+    }
+
+    /// Returns the number of clients waiting for reconnection.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no clients are waiting for reconnection.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// An event that indicates that a client from [`ReconnectingClients`] connected again within [`DisconnectGrace`].
+///
+/// Emitted in [`ServerSet::SendEvents`] alongside [`ServerEvent::ClientConnected`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientReconnected {
+    pub client_id: ClientId,
+}
+
+/// An event that indicates that a client from [`ReconnectingClients`] didn't reconnect within [`DisconnectGrace`].
+///
+/// Emitted in [`ServerSet::SendEvents`]. The game should clean up the client's state at this point.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientGraceExpired {
+    pub client_id: ClientId,
+}
+
+pub(super) struct DisconnectGracePlugin;
+
+impl Plugin for DisconnectGracePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReconnectingClients>()
+            .add_event::<ClientReconnected>()
+            .add_event::<ClientGraceExpired>()
+            .add_systems(
+                PreUpdate,
+                (Self::expire, Self::reconnect, Self::start)
+                    .chain()
+                    .in_set(ServerSet::SendEvents)
+                    .after(super::RepliconRenetServerPlugin::forward_server_events),
+            );
+    }
+}
+
+impl DisconnectGracePlugin {
+    fn expire(
+        time: Res<Time<Real>>,
+        server: Res<RepliconServer>,
+        mut reconnecting: ResMut<ReconnectingClients>,
+        mut expired_events: EventWriter<ClientGraceExpired>,
+    ) {
+        if reconnecting.is_empty() {
+            return;
+        }
+
+        reconnecting.0.retain(|&client_id, &mut deadline| {
+            if server.is_running() && time.elapsed() < deadline {
+                return true;
+            }
+
+            debug!("grace for `{client_id:?}` expired");
+            expired_events.send(ClientGraceExpired { client_id });
+            false
+        });
+    }
+
+    fn reconnect(
+        mut server_events: EventReader<ServerEvent>,
+        mut reconnecting: ResMut<ReconnectingClients>,
+        mut reconnect_events: EventWriter<ClientReconnected>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientConnected { client_id } = *event {
+                if reconnecting.0.remove(&client_id).is_some() {
+                    debug!("`{client_id:?}` reconnected within grace");
+                    reconnect_events.send(ClientReconnected { client_id });
+                }
+            }
+        }
+    }
+
+    fn start(
+        time: Res<Time<Real>>,
+        grace: Option<Res<DisconnectGrace>>,
+        server: Res<RepliconServer>,
+        mut disconnect_events: EventReader<ClientDisconnected>,
+        mut reconnecting: ResMut<ReconnectingClients>,
+    ) {
+        let Some(grace) = grace.filter(|_| server.is_running()) else {
+            disconnect_events.clear();
+            return;
+        };
+
+        for event in disconnect_events.read() {
+            if matches!(
+                event.reason,
+                RepliconDisconnectReason::Timeout | RepliconDisconnectReason::TransportError
+            ) {
+                debug!(
+                    "waiting {:?} for `{:?}` to reconnect",
+                    **grace, event.client_id
+                );
+                reconnecting
+                    .0
+                    .insert(event.client_id, time.elapsed() + **grace);
+            }
+        }
+    }
+}
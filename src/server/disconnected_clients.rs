@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+use super::{disconnect::ClientDisconnected, handshake::ClientMetadata, ClientCleanupSet};
+use crate::RepliconDisconnectReason;
+
+/// Final state of clients that disconnected during the last frame.
+///
+/// Replicon removes a disconnected client from [`ConnectedClients`] in the same frame, and the crate drops
+/// its [`ClientMetadata`] right after [`ClientCleanupSet`]. With this resource, a [`DisconnectedClient`]
+/// snapshot is taken in [`ClientCleanupSet`] for each [`ClientDisconnected`] and kept for exactly one frame:
+/// until [`ClientCleanupSet`] of the next frame, where it's replaced by snapshots of clients that disconnected
+/// in that frame. So systems in any schedule, including [`Update`] and [`Last`], can react to the disconnect
+/// with the full data, for example, to save the player's progress.
+///
+/// Snapshots are taken for disconnects when [`RenetServer`](bevy_renet::renet::RenetServer)
+/// is removed too, but without metadata.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Default, Debug)]
+pub struct DisconnectedClients(Vec<DisconnectedClient>);
+
+impl DisconnectedClients {
+    /// Returns the snapshot of a client that disconnected during the last frame.
+    pub fn get(&self, client_id: ClientId) -> Option<&DisconnectedClient> {
+        self.0.iter().find(|client| client.client_id == client_id)
+    }
+
+    /// Iterates over snapshots of all clients that disconnected during the last frame.
+    pub fn iter(&self) -> impl Iterator<Item = &DisconnectedClient> {
+        self.0.iter()
+    }
+
+    /// Returns the number of clients that disconnected during the last frame.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no clients disconnected during the last frame.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Snapshot of a disconnected client from [`DisconnectedClients`].
+///
+/// Statistics are copied from the client's
+/// [`ConnectedClient`](bevy_replicon::core::connected_clients::ConnectedClient).
+#[derive(Debug, Clone)]
+pub struct DisconnectedClient {
+    pub client_id: ClientId,
+    pub reason: RepliconDisconnectReason,
+
+    /// Last round-trip time in seconds.
+    pub rtt: f64,
+
+    /// Last packet loss.
+    pub packet_loss: f64,
+
+    /// Last bytes per second sent to the client.
+    pub sent_bps: f64,
+
+    /// Last bytes per second received from the client.
+    pub received_bps: f64,
+
+    /// Metadata received during the handshake, see [`ClientMetadata`].
+    pub metadata: Option<Bytes>,
+}
+
+pub(super) struct DisconnectedClientsPlugin;
+
+impl Plugin for DisconnectedClientsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::take_snapshots
+                .in_set(ClientCleanupSet)
+                .run_if(resource_exists::<DisconnectedClients>),
+        );
+    }
+}
+
+impl DisconnectedClientsPlugin {
+    fn take_snapshots(
+        mut disconnect_events: EventReader<ClientDisconnected>,
+        connected_clients: Res<ConnectedClients>,
+        client_metadata: Res<ClientMetadata>,
+        mut disconnected_clients: ResMut<DisconnectedClients>,
+    ) {
+        if disconnect_events.is_empty() && disconnected_clients.is_empty() {
+            return;
+        }
+
+        disconnected_clients.0.clear();
+        for event in disconnect_events.read() {
+            let Some(client) = connected_clients
+                .iter()
+                .find(|client| client.id() == event.client_id)
+            else {
+                continue;
+            };
+
+            disconnected_clients.0.push(DisconnectedClient {
+                client_id: event.client_id,
+                reason: event.reason,
+                rtt: client.rtt(),
+                packet_loss: client.packet_loss(),
+                sent_bps: client.sent_bps(),
+                received_bps: client.received_bps(),
+                metadata: client_metadata.get(event.client_id).cloned(),
+            });
+        }
+    }
+}
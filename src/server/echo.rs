@@ -0,0 +1,131 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashSet};
+use bevy_renet::renet::{Bytes, RenetServer};
+use bevy_replicon::{prelude::*, server::server_tick::ServerTick};
+
+use crate::{
+    control::{self, ControlChannel, ControlKind},
+    echo,
+};
+
+/// Client channels whose messages are echoed back to their senders with the tick at which they were applied.
+///
+/// Each message received over these channels is sent back to the client that sent it over [`ControlChannel`]
+/// together with the server tick, and the client receives it as [`EventEchoed`](crate::EventEchoed).
+/// This allows the client to reconcile its prediction: discard inputs that the server already applied
+/// and re-simulate the remaining ones on top of the replicated state for that tick. The reconciliation
+/// itself is up to the game.
+///
+/// Echoes are sent in [`ServerSet::SendPackets`] of the first frame in which [`ServerTick`] changes after
+/// the message was received, so the echoed tick is the tick of the replication that includes the effects
+/// of events processed in the same frame they were received. Echoes are sent over a reliable ordered channel,
+/// but replication messages for the same tick can arrive earlier or later than them.
+///
+/// The echoed message is the event as serialized by Replicon, after incoming [`MessageTransforms`](crate::MessageTransforms)
+/// and without a tag from [`MessageTimestamps`](crate::MessageTimestamps). It's usually easier to include
+/// a sequence number in the event and match it instead of deserializing the whole event on the client.
+///
+/// Requires [`ControlChannel`] on both sides, messages are not echoed without it. Echoes take memory
+/// of [`ControlChannel`], so enable it only for low-frequency channels like player inputs.
+/// Channel IDs are IDs of client channels from [`RepliconChannels`].
+///
+/// Disabled by default, insert this resource to enable it.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::EchoClientEvents;
+///
+/// # let mut app = App::new();
+/// const INPUT_CHANNEL: u8 = 2;
+/// app.insert_resource(EchoClientEvents::default().with_channel(INPUT_CHANNEL));
+/// ```
+#[derive(Resource, Default, Debug, Clone)]
+pub struct EchoClientEvents {
+    channels: HashSet<u8>,
+}
+
+impl EchoClientEvents {
+    /// Enables echoing for the client channel.
+    #[must_use]
+    pub fn with_channel(mut self, channel_id: impl Into<u8>) -> Self {
+        self.channels.insert(channel_id.into());
+        self
+    }
+
+    /// Returns `true` if messages over the client channel are echoed.
+    pub fn contains(&self, channel_id: u8) -> bool {
+        self.channels.contains(&channel_id)
+    }
+}
+
+pub(super) struct EchoPlugin;
+
+impl Plugin for EchoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingEchoes>().add_systems(
+            PostUpdate,
+            (
+                Self::send
+                    .before(super::RepliconRenetServerPlugin::send_packets)
+                    .in_set(ServerSet::SendPackets)
+                    .run_if(resource_exists::<RenetServer>)
+                    .run_if(resource_exists::<ControlChannel>)
+                    .run_if(resource_changed::<ServerTick>),
+                Self::reset.run_if(server_just_stopped),
+            ),
+        );
+    }
+}
+
+impl EchoPlugin {
+    fn send(
+        channel: Res<ControlChannel>,
+        server_tick: Res<ServerTick>,
+        mut pending: ResMut<PendingEchoes>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        for (client_id, channel_id, message) in pending.drain(..) {
+            if !renet_server.is_connected(client_id.get()) {
+                debug!("skipping echo over channel {channel_id} for disconnected `{client_id:?}`");
+                continue;
+            }
+
+            trace!("echoing message over channel {channel_id} to `{client_id:?}`");
+            let payload = echo::encode(channel_id, **server_tick, &message);
+            let message = control::encode(ControlKind::Echo, &payload);
+            renet_server.send_message(client_id.get(), **channel, message);
+        }
+    }
+
+    fn reset(mut pending: ResMut<PendingEchoes>) {
+        pending.clear();
+    }
+}
+
+/// Received messages waiting for the next tick to be echoed.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PendingEchoes(Vec<(ClientId, u8, Bytes)>);
+
+/// Records received messages over channels from [`EchoClientEvents`].
+#[derive(SystemParam)]
+pub(super) struct EchoRecorder<'w> {
+    echo: Option<Res<'w, EchoClientEvents>>,
+    channel: Option<Res<'w, ControlChannel>>,
+    pending: ResMut<'w, PendingEchoes>,
+}
+
+impl EchoRecorder<'_> {
+    pub(super) fn record(&mut self, client_id: ClientId, channel_id: u8, message: &Bytes) {
+        if self.channel.is_none()
+            || !self
+                .echo
+                .as_ref()
+                .is_some_and(|echo| echo.contains(channel_id))
+        {
+            return;
+        }
+
+        self.pending.push((client_id, channel_id, message.clone()));
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+/// Aligns server sends with the fixed simulation tick instead of the render frame.
+///
+/// By default messages from [`RepliconServer`] are passed to renet every frame in [`ServerSet::SendPackets`].
+/// While this resource is present, they are passed only in frames where [`FixedUpdate`] ran at least once.
+/// In other frames messages stay in [`RepliconServer`] and are sent together with the next tick,
+/// so a server that renders at a variable frame rate doesn't send partial state between ticks.
+///
+/// Forwarding itself still runs in [`PostUpdate`] rather than in [`FixedUpdate`], since Replicon collects
+/// replication in [`ServerSet::Send`] and the transport sends packets in [`PostUpdate`]. Forwarding inside
+/// [`FixedUpdate`] would always pass the replication of the previous frame.
+///
+/// To replicate once per fixed tick, use [`TickPolicy::Manual`] and increment [`ServerTick`](bevy_replicon::server::server_tick::ServerTick)
+/// after your simulation systems, like in [`FixedPostUpdate`]:
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::prelude::*;
+/// use bevy_replicon_renet::{RepliconRenetPlugins, SendOnFixedTick};
+///
+/// # let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     RepliconPlugins.set(ServerPlugin {
+///         tick_policy: TickPolicy::Manual,
+///         ..Default::default()
+///     }),
+///     RepliconRenetPlugins,
+/// ))
+/// .insert_resource(SendOnFixedTick)
+/// .add_systems(
+///     FixedPostUpdate,
+///     ServerPlugin::increment_tick.run_if(server_running),
+/// );
+/// ```
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct SendOnFixedTick;
+
+/// Whether [`FixedUpdate`] ran since the last send.
+#[derive(Resource, Default)]
+pub(super) struct FixedTicked(bool);
+
+pub(super) struct FixedSendPlugin;
+
+impl Plugin for FixedSendPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedTicked>()
+            .add_systems(
+                FixedUpdate,
+                Self::mark_tick.run_if(resource_exists::<SendOnFixedTick>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::reset
+                    .after(super::RepliconRenetServerPlugin::send_packets)
+                    .in_set(ServerSet::SendPackets),
+            );
+    }
+}
+
+impl FixedSendPlugin {
+    fn mark_tick(mut ticked: ResMut<FixedTicked>) {
+        ticked.0 = true;
+    }
+
+    fn reset(mut ticked: ResMut<FixedTicked>) {
+        ticked.0 = false;
+    }
+
+    /// Returns `true` if messages can be sent in this frame according to [`SendOnFixedTick`].
+    pub(super) fn send_allowed(
+        send_on_fixed_tick: Option<Res<SendOnFixedTick>>,
+        ticked: Res<FixedTicked>,
+    ) -> bool {
+        send_on_fixed_tick.is_none() || ticked.0
+    }
+}
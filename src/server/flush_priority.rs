@@ -0,0 +1,96 @@
+use std::cmp::Reverse;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+/// Order in which the server passes messages of different clients to renet.
+///
+/// By default messages are passed in the order Replicon produced them. With this resource, messages
+/// for clients with a higher priority are passed first, for example, for the host's own client on a
+/// listen server or for VIPs. Messages for clients with equal priority keep their order, as well as
+/// messages for a single client. Clients without an assigned priority have priority 0.
+///
+/// This only affects the order within a frame and doesn't give any delivery or latency guarantees.
+/// Each client has its own renet channels and [`SendBackpressure`](crate::SendBackpressure) queues,
+/// so messages for low priority clients are deferred only by their own limits. Messages held back by
+/// [`SendBackpressure`](crate::SendBackpressure) are passed before new messages regardless of priority.
+///
+/// Priorities are removed once clients disconnect.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct FlushPriority(HashMap<ClientId, u8>);
+
+impl FlushPriority {
+    /// Assigns a priority to the client.
+    pub fn set(&mut self, client_id: ClientId, priority: u8) {
+        self.0.insert(client_id, priority);
+    }
+
+    /// Returns the priority of the client.
+    pub fn get(&self, client_id: ClientId) -> u8 {
+        self.0.get(&client_id).copied().unwrap_or_default()
+    }
+
+    /// Resets the priority of the client to 0.
+    pub fn remove(&mut self, client_id: ClientId) {
+        self.0.remove(&client_id);
+    }
+
+    /// Reorders messages by priorities of their clients.
+    pub(super) fn sort(&self, messages: &mut [(ClientId, u8, Bytes)]) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        // Stable sort keeps the order of messages with equal priority.
+        messages.sort_by_key(|&(client_id, ..)| Reverse(self.get(client_id)));
+    }
+}
+
+pub(super) struct FlushPriorityPlugin;
+
+impl Plugin for FlushPriorityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlushPriority>().add_systems(
+            PreUpdate,
+            Self::remove_disconnected
+                .in_set(ServerSet::SendEvents)
+                .after(super::RepliconRenetServerPlugin::forward_server_events),
+        );
+    }
+}
+
+impl FlushPriorityPlugin {
+    fn remove_disconnected(
+        mut server_events: EventReader<ServerEvent>,
+        mut flush_priority: ResMut<FlushPriority>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                flush_priority.remove(client_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort() {
+        let mut flush_priority = FlushPriority::default();
+        flush_priority.set(ClientId::new(1), 1);
+
+        let mut messages = [
+            (ClientId::new(0), 0, Bytes::from_static(&[0])),
+            (ClientId::new(1), 0, Bytes::from_static(&[1])),
+            (ClientId::new(0), 0, Bytes::from_static(&[2])),
+            (ClientId::new(1), 0, Bytes::from_static(&[3])),
+        ];
+        flush_priority.sort(&mut messages);
+
+        let order: Vec<_> = messages.iter().map(|(.., message)| message[0]).collect();
+        assert_eq!(order, [1, 3, 0, 2]);
+    }
+}
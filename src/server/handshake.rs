@@ -0,0 +1,116 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::{self, Bytes, RenetServer};
+use bevy_replicon::prelude::*;
+
+use super::{
+    connection_phase::ConnectionPhases,
+    control::{ControlMessages, ControlPlugin},
+    rejection::{ClientRejected, RejectReason},
+};
+use crate::{
+    control::{self, ControlChannel, ControlKind},
+    handshake::{HandshakeExtensions, HandshakeMetadata},
+};
+
+/// Metadata received from clients during the handshake enabled by [`HandshakeMetadata`].
+///
+/// Filled before [`ServerEvent::ClientConnected`] is emitted for the client
+/// and cleared for the client after disconnect.
+#[derive(Resource, Default, Debug)]
+pub struct ClientMetadata(HashMap<ClientId, Bytes>);
+
+impl ClientMetadata {
+    /// Returns the metadata of the client if the handshake is completed.
+    pub fn get(&self, client_id: ClientId) -> Option<&Bytes> {
+        self.0.get(&client_id)
+    }
+
+    pub(super) fn contains(&self, client_id: ClientId) -> bool {
+        self.0.contains_key(&client_id)
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+pub(super) struct HandshakePlugin;
+
+impl Plugin for HandshakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HandshakeExtensions>()
+            .init_resource::<ClientMetadata>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::receive
+                        .after(ControlPlugin::receive)
+                        .in_set(ServerSet::SendEvents)
+                        .before(super::RepliconRenetServerPlugin::forward_server_events)
+                        .run_if(resource_exists::<RenetServer>)
+                        .run_if(resource_exists::<ControlChannel>)
+                        .run_if(resource_exists::<HandshakeMetadata>),
+                    Self::remove_disconnected
+                        .after(super::ClientCleanupSet)
+                        .before(ServerSet::Receive),
+                ),
+            );
+    }
+}
+
+impl HandshakePlugin {
+    /// Removes metadata of disconnected clients after [`ClientCleanupSet`](super::ClientCleanupSet).
+    ///
+    /// Reads renet's events because clients that disconnect during the handshake never reach Replicon.
+    fn remove_disconnected(
+        mut renet_server_events: EventReader<renet::ServerEvent>,
+        mut client_metadata: ResMut<ClientMetadata>,
+    ) {
+        for event in renet_server_events.read() {
+            if let renet::ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                client_metadata.0.remove(&ClientId::new(client_id));
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn receive(
+        channel: Res<ControlChannel>,
+        metadata: Res<HandshakeMetadata>,
+        extensions: Res<HandshakeExtensions>,
+        phases: Res<ConnectionPhases>,
+        mut messages: ResMut<ControlMessages>,
+        mut client_metadata: ResMut<ClientMetadata>,
+        mut renet_server: ResMut<RenetServer>,
+        mut rejected_events: EventWriter<ClientRejected>,
+    ) {
+        for (client_id, message) in messages.take(ControlKind::Handshake) {
+            let _span = phases.span(client_id).entered();
+            if !renet_server.is_connected(client_id.get()) {
+                continue;
+            }
+            if client_metadata.contains(client_id) {
+                debug!("ignoring repeated metadata from `{client_id:?}`");
+                continue;
+            }
+
+            debug!(
+                "received {} bytes of metadata from `{client_id:?}`",
+                message.len()
+            );
+            let Some(message) = extensions.read(client_id, message) else {
+                warn!("rejecting `{client_id:?}` due to invalid handshake");
+                renet_server.disconnect(client_id.get());
+                rejected_events.send(ClientRejected {
+                    client_id,
+                    reason: RejectReason::Handshake,
+                });
+                continue;
+            };
+
+            client_metadata.0.insert(client_id, message);
+            let reply = control::encode(ControlKind::Handshake, &extensions.write(&metadata));
+            renet_server.send_message(client_id.get(), **channel, reply);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use crate::channel_policy::REPLICATION_CHANNELS_COUNT;
+
+/// Disconnects clients that haven't sent any messages for the specified duration.
+///
+/// Useful to free slots taken by players who are AFK or stuck on a loading screen.
+/// Activity is tracked in [`LastReceived`]. Only messages over event channels count,
+/// so renet's keep-alive packets and acknowledgements, as well as replication acknowledgements
+/// that Replicon sends automatically, don't keep a client connected. If some kind of
+/// background traffic should count as activity, send it as a client event, like a periodic heartbeat.
+///
+/// Idle clients are disconnected via [`RenetServer::disconnect`], so they go through the usual
+/// [`ServerEvent::ClientDisconnected`] path. [`IdleDisconnect`] is emitted right before that
+/// to distinguish them from other disconnects.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Deref, DerefMut, Debug, Clone, Copy)]
+pub struct IdleTimeout(pub Duration);
+
+/// Time of the last message from each connected client.
+///
+/// Stores [`Time<Real>::elapsed`] at the moment a message over an event channel was received.
+/// Initialized with the connection time, so clients that never send anything are also tracked.
+/// See [`IdleTimeout`] for which messages count.
+#[derive(Resource, Default, Debug)]
+pub struct LastReceived(HashMap<ClientId, Duration>);
+
+impl LastReceived {
+    /// Returns the time of the last message from the client.
+    pub fn get(&self, client_id: ClientId) -> Option<Duration> {
+        self.0.get(&client_id).copied()
+    }
+
+    /// Records activity of the client if the message was received over an event channel.
+    pub(super) fn record(&mut self, client_id: ClientId, channel_id: u8, now: Duration) {
+        if (channel_id as usize) >= REPLICATION_CHANNELS_COUNT {
+            self.0.insert(client_id, now);
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// An event that indicates that a client was disconnected by [`IdleTimeout`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleDisconnect {
+    pub client_id: ClientId,
+
+    /// Time since the last message from the client.
+    pub idle_time: Duration,
+}
+
+pub(super) struct IdlePlugin;
+
+impl Plugin for IdlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastReceived>()
+            .add_event::<IdleDisconnect>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::disconnect_idle
+                        .after(super::RepliconRenetServerPlugin::receive_packets)
+                        .in_set(ServerSet::ReceivePackets)
+                        .run_if(resource_exists::<RenetServer>)
+                        .run_if(resource_exists::<IdleTimeout>),
+                    Self::track_connections
+                        .in_set(ServerSet::SendEvents)
+                        .after(super::RepliconRenetServerPlugin::forward_server_events),
+                ),
+            );
+    }
+}
+
+impl IdlePlugin {
+    fn track_connections(
+        time: Res<Time<Real>>,
+        mut server_events: EventReader<ServerEvent>,
+        mut last_received: ResMut<LastReceived>,
+    ) {
+        for event in server_events.read() {
+            match *event {
+                ServerEvent::ClientConnected { client_id } => {
+                    last_received.0.insert(client_id, time.elapsed());
+                }
+                ServerEvent::ClientDisconnected { client_id, .. } => {
+                    last_received.0.remove(&client_id);
+                }
+            }
+        }
+    }
+
+    fn disconnect_idle(
+        time: Res<Time<Real>>,
+        timeout: Res<IdleTimeout>,
+        mut last_received: ResMut<LastReceived>,
+        mut renet_server: ResMut<RenetServer>,
+        mut idle_events: EventWriter<IdleDisconnect>,
+    ) {
+        let now = time.elapsed();
+        last_received.0.retain(|&client_id, &mut last_time| {
+            let idle_time = now.saturating_sub(last_time);
+            if idle_time <= **timeout {
+                return true;
+            }
+
+            debug!("disconnecting `{client_id:?}` after {idle_time:?} of inactivity");
+            renet_server.disconnect(client_id.get());
+            idle_events.send(IdleDisconnect {
+                client_id,
+                idle_time,
+            });
+            false
+        });
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::{core::channels::ReplicationChannel, prelude::*};
+
+use crate::InitMessageCaptured;
+
+pub(super) struct InitCapturePlugin;
+
+impl Plugin for InitCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<InitMessageCaptured>()
+            .init_resource::<CapturedClients>()
+            .add_systems(
+                PreUpdate,
+                Self::remove_disconnected
+                    .in_set(ServerSet::SendEvents)
+                    .after(super::RepliconRenetServerPlugin::forward_server_events),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::capture
+                    .in_set(ServerSet::SendPackets)
+                    .after(super::lobby::LobbyPlugin::hold_replication)
+                    .after(super::memory_budget::MemoryBudgetPlugin::shed)
+                    .after(super::channel_close::ChannelClosePlugin::discard)
+                    .before(super::RepliconRenetServerPlugin::send_packets)
+                    .run_if(resource_exists::<RenetServer>),
+            );
+    }
+}
+
+impl InitCapturePlugin {
+    fn remove_disconnected(
+        mut server_events: EventReader<ServerEvent>,
+        mut captured: ResMut<CapturedClients>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                captured.remove(&client_id);
+            }
+        }
+    }
+
+    fn capture(
+        mut captured: ResMut<CapturedClients>,
+        mut server: ResMut<RepliconServer>,
+        mut capture_events: EventWriter<InitMessageCaptured>,
+    ) {
+        let messages: Vec<_> = server.drain_sent().collect();
+        for (client_id, channel_id, message) in messages {
+            if channel_id == ReplicationChannel::Updates as u8 && captured.insert(client_id) {
+                capture_events.send(InitMessageCaptured::new(client_id, &message));
+            }
+
+            server.send(client_id, channel_id, message);
+        }
+    }
+}
+
+/// Clients whose init message was already captured.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct CapturedClients(HashSet<ClientId>);
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+/// Optional jitter buffers for messages received from clients.
+///
+/// Client inputs sent over an unreliable channel arrive unevenly, so the server may receive
+/// several messages in one frame and none in the next. For channels with enabled buffering,
+/// received messages are held in a per-client queue until it contains `depth` messages and
+/// then released to Replicon one message per frame. If the queue runs empty, it's filled up again
+/// before releasing. If the queue grows beyond twice the depth (for example, the client
+/// runs at a higher rate), it's drained back to `depth` to keep the latency bounded.
+///
+/// This adds roughly `depth` frames of latency to the channel in exchange for steady delivery.
+/// Buffering is disabled for all channels by default.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::JitterBuffers;
+///
+/// # let mut app = App::new();
+/// # app.init_resource::<JitterBuffers>();
+/// const INPUT_CHANNEL: u8 = 2;
+/// app.world_mut()
+///     .resource_mut::<JitterBuffers>()
+///     .enable(INPUT_CHANNEL, 3);
+/// ```
+#[derive(Resource, Default)]
+pub struct JitterBuffers {
+    depths: HashMap<u8, usize>,
+    queues: HashMap<(ClientId, u8), JitterQueue>,
+}
+
+impl JitterBuffers {
+    /// Enables buffering of messages received over the client channel with the given depth.
+    ///
+    /// Depth is clamped to at least 1.
+    pub fn enable(&mut self, channel_id: impl Into<u8>, depth: usize) -> &mut Self {
+        self.depths.insert(channel_id.into(), depth.max(1));
+        self
+    }
+
+    /// Disables buffering for the channel.
+    ///
+    /// Already buffered messages will be released on the next update.
+    pub fn disable(&mut self, channel_id: impl Into<u8>) -> &mut Self {
+        let channel_id = channel_id.into();
+        self.depths.remove(&channel_id);
+        for ((_, queue_channel), queue) in &mut self.queues {
+            if *queue_channel == channel_id {
+                queue.flushing = true;
+            }
+        }
+        self
+    }
+
+    /// Returns buffer depth for the channel if buffering is enabled for it.
+    pub fn depth(&self, channel_id: impl Into<u8>) -> Option<usize> {
+        self.depths.get(&channel_id.into()).copied()
+    }
+
+    pub(crate) fn is_buffered(&self, channel_id: u8) -> bool {
+        self.depths.contains_key(&channel_id)
+    }
+
+    pub(crate) fn push(&mut self, client_id: ClientId, channel_id: u8, message: Bytes) {
+        self.queues
+            .entry((client_id, channel_id))
+            .or_default()
+            .messages
+            .push_back(message);
+    }
+
+    /// Passes messages that should be released this frame into `f`.
+    pub(crate) fn release(&mut self, mut f: impl FnMut(ClientId, u8, Bytes)) {
+        for (&(client_id, channel_id), queue) in &mut self.queues {
+            if queue.flushing {
+                for message in queue.messages.drain(..) {
+                    f(client_id, channel_id, message);
+                }
+                continue;
+            }
+
+            let Some(&depth) = self.depths.get(&channel_id) else {
+                continue;
+            };
+
+            if !queue.primed {
+                if queue.messages.len() < depth {
+                    continue;
+                }
+                queue.primed = true;
+            }
+
+            let excess = queue.messages.len().saturating_sub(depth * 2);
+            let count = if excess > 0 {
+                queue.messages.len() - depth
+            } else {
+                1
+            };
+            for message in queue.messages.drain(..count.min(queue.messages.len())) {
+                f(client_id, channel_id, message);
+            }
+
+            if queue.messages.is_empty() {
+                queue.primed = false;
+            }
+        }
+
+        self.queues
+            .retain(|_, queue| !(queue.flushing && queue.messages.is_empty()));
+    }
+
+    pub(crate) fn remove_client(&mut self, client_id: ClientId) {
+        self.queues
+            .retain(|&(queue_client, _), _| queue_client != client_id);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.queues.clear();
+    }
+}
+
+#[derive(Default)]
+struct JitterQueue {
+    messages: VecDeque<Bytes>,
+    primed: bool,
+    flushing: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priming() {
+        let mut jitter_buffers = JitterBuffers::default();
+        jitter_buffers.enable(CHANNEL_ID, 2);
+
+        jitter_buffers.push(CLIENT_ID, CHANNEL_ID, Bytes::from_static(&[0]));
+        assert!(release(&mut jitter_buffers).is_empty());
+
+        jitter_buffers.push(CLIENT_ID, CHANNEL_ID, Bytes::from_static(&[1]));
+        assert_eq!(release(&mut jitter_buffers), [0]);
+        assert_eq!(release(&mut jitter_buffers), [1]);
+
+        jitter_buffers.push(CLIENT_ID, CHANNEL_ID, Bytes::from_static(&[2]));
+        assert!(
+            release(&mut jitter_buffers).is_empty(),
+            "empty queue should be filled up again"
+        );
+    }
+
+    #[test]
+    fn excess() {
+        let mut jitter_buffers = JitterBuffers::default();
+        jitter_buffers.enable(CHANNEL_ID, 1);
+
+        for index in 0..3 {
+            jitter_buffers.push(CLIENT_ID, CHANNEL_ID, Bytes::from(vec![index]));
+        }
+
+        assert_eq!(release(&mut jitter_buffers), [0, 1]);
+        assert_eq!(release(&mut jitter_buffers), [2]);
+    }
+
+    #[test]
+    fn disable() {
+        let mut jitter_buffers = JitterBuffers::default();
+        jitter_buffers.enable(CHANNEL_ID, 3);
+
+        for index in 0..2 {
+            jitter_buffers.push(CLIENT_ID, CHANNEL_ID, Bytes::from(vec![index]));
+        }
+        assert!(release(&mut jitter_buffers).is_empty());
+
+        jitter_buffers.disable(CHANNEL_ID);
+        assert_eq!(release(&mut jitter_buffers), [0, 1]);
+        assert!(jitter_buffers.queues.is_empty());
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const CHANNEL_ID: u8 = 0;
+
+    /// Returns the first byte of each released message.
+    fn release(jitter_buffers: &mut JitterBuffers) -> Vec<u8> {
+        let mut released = Vec::new();
+        jitter_buffers.release(|_, _, message| released.push(message[0]));
+        released
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, RenetServer};
+use bevy_replicon::prelude::*;
+
+use super::connection_phase::ConnectionPhases;
+use crate::control::{self, ControlChannel, ControlKind};
+
+/// An event to disconnect a client with a reason.
+///
+/// If [`ControlChannel`] is inserted, the reason is sent over it in [`ServerSet::SendPackets`] and the connection
+/// is closed once the client acknowledges all control messages sent so far or after [`Self::ACK_TIMEOUT`].
+/// The client receives the reason as [`ClientKicked`](crate::ClientKicked) before it's disconnected.
+/// Without [`ControlChannel`], the client is disconnected right away.
+///
+/// Delivery is best effort: if the reason or its acknowledgement is lost on a lossy link for longer than
+/// the timeout, the client is disconnected without receiving it. Until the connection is closed, the client
+/// keeps exchanging messages as usual.
+///
+/// Ignored if the client is not connected.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct KickClient {
+    pub client_id: ClientId,
+
+    /// Game-specific reason, like an encoded string or error code.
+    pub reason: Bytes,
+}
+
+impl KickClient {
+    /// Maximum time to wait for the client to acknowledge the reason.
+    pub const ACK_TIMEOUT: Duration = Duration::from_secs(1);
+}
+
+pub(super) struct KickPlugin;
+
+impl Plugin for KickPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<KickClient>()
+            .init_resource::<PendingKicks>()
+            .add_systems(
+                PreUpdate,
+                Self::disconnect_acknowledged
+                    .in_set(ServerSet::ReceivePackets)
+                    .run_if(resource_exists::<RenetServer>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::send
+                    .before(super::RepliconRenetServerPlugin::send_packets)
+                    .in_set(ServerSet::SendPackets)
+                    .run_if(resource_exists::<RenetServer>),
+            );
+    }
+}
+
+impl KickPlugin {
+    pub(super) fn send(
+        time: Res<Time<Real>>,
+        channel: Option<Res<ControlChannel>>,
+        phases: Res<ConnectionPhases>,
+        mut kick_events: EventReader<KickClient>,
+        mut pending: ResMut<PendingKicks>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        let channel_id = channel.map(|channel| **channel);
+        for event in kick_events.read() {
+            let _span = phases.span(event.client_id).entered();
+            let client_id = event.client_id.get();
+            if !renet_server.is_connected(client_id) {
+                debug!("ignoring kick for disconnected `{:?}`", event.client_id);
+                continue;
+            }
+
+            let Some(channel_id) = channel_id else {
+                debug!("kicking `{:?}` without reason", event.client_id);
+                renet_server.disconnect(client_id);
+                continue;
+            };
+
+            debug!("kicking `{:?}` after sending reason", event.client_id);
+            let available_memory = renet_server.channel_available_memory(client_id, channel_id);
+            let message = control::encode(ControlKind::Kick, &event.reason);
+            renet_server.send_message(client_id, channel_id, message);
+            pending.push(PendingKick {
+                client_id,
+                available_memory,
+                deadline: time.elapsed() + KickClient::ACK_TIMEOUT,
+            });
+        }
+    }
+
+    /// Disconnects kicked clients once renet frees the memory used by the reason, which happens on acknowledgement.
+    fn disconnect_acknowledged(
+        time: Res<Time<Real>>,
+        channel: Option<Res<ControlChannel>>,
+        mut pending: ResMut<PendingKicks>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        let channel_id = channel.map(|channel| **channel);
+        pending.retain(|kick| {
+            if !renet_server.is_connected(kick.client_id) {
+                return false;
+            }
+
+            let acknowledged = channel_id.is_some_and(|channel_id| {
+                renet_server.channel_available_memory(kick.client_id, channel_id)
+                    >= kick.available_memory
+            });
+            if acknowledged || time.elapsed() >= kick.deadline {
+                renet_server.disconnect(kick.client_id);
+                return false;
+            }
+
+            true
+        });
+    }
+}
+
+/// Clients that were sent a kick reason, but not disconnected yet.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(super) struct PendingKicks(Vec<PendingKick>);
+
+pub(super) struct PendingKick {
+    client_id: u64,
+
+    /// Available memory of [`ControlChannel`] before sending the reason.
+    available_memory: usize,
+
+    /// [`Time<Real>::elapsed`] after which the client is disconnected without the acknowledgement.
+    deadline: Duration,
+}
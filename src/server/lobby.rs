@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::{core::channels::ReplicationChannel, prelude::*};
+
+/// Withholds replication messages while present.
+///
+/// Useful for a lobby phase before a match starts: clients connect as usual and appear in
+/// [`ConnectedClients`], [`ServerEvent`]s are emitted and events in both directions still flow,
+/// but messages over [`ReplicationChannel::Updates`] are held on the server. Once the resource
+/// is removed, held messages are sent in their original order before any new messages.
+///
+/// Messages over [`ReplicationChannel::Mutations`] are dropped instead. The channel is unreliable,
+/// and Replicon resends mutations that weren't acknowledged within [`ServerPlugin::mutations_timeout`],
+/// so nothing is lost, while holding them would grow the buffer every tick for the whole lobby phase.
+///
+/// Replicon keeps collecting changes and evaluating visibility as usual, so held messages reflect
+/// the state at the time they were produced and visibility changes made during the lobby phase are
+/// applied once the messages are sent. Messages for clients that disconnect during the lobby phase are dropped.
+///
+/// Replicon buffers server events on the client until the replication message they depend on
+/// arrives. So server events that should be received in the lobby, like player list updates,
+/// need to be marked with [`ServerEventAppExt::make_independent`].
+///
+/// If you don't need to replicate anything until the match starts, consider disabling
+/// [`ServerPlugin::replicate_after_connect`] and sending [`StartReplication`] for each client instead.
+/// This avoids collecting changes for clients in the lobby, while this resource only delays sending.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct LobbyMode;
+
+pub(super) struct LobbyPlugin;
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeldReplication>()
+            .add_systems(
+                PreUpdate,
+                Self::remove_disconnected
+                    .in_set(ServerSet::SendEvents)
+                    .after(super::RepliconRenetServerPlugin::forward_server_events),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::hold_replication
+                    .in_set(ServerSet::SendPackets)
+                    .before(super::RepliconRenetServerPlugin::send_packets),
+            );
+    }
+}
+
+impl LobbyPlugin {
+    fn remove_disconnected(
+        mut server_events: EventReader<ServerEvent>,
+        mut held: ResMut<HeldReplication>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                held.retain(|&(held_id, ..)| held_id != client_id);
+            }
+        }
+    }
+
+    pub(super) fn hold_replication(
+        lobby: Option<Res<LobbyMode>>,
+        mut held: ResMut<HeldReplication>,
+        mut server: ResMut<RepliconServer>,
+    ) {
+        if lobby.is_none() && held.is_empty() {
+            return;
+        }
+
+        let messages: Vec<_> = server.drain_sent().collect();
+        if lobby.is_some() {
+            for (client_id, channel_id, message) in messages {
+                if channel_id == ReplicationChannel::Updates as u8 {
+                    held.push((client_id, channel_id, message));
+                } else if channel_id == ReplicationChannel::Mutations as u8 {
+                    trace!("dropping mutations for `{client_id:?}` in lobby");
+                } else {
+                    server.send(client_id, channel_id, message);
+                }
+            }
+        } else {
+            debug!("releasing {} held replication messages", held.len());
+            for (client_id, channel_id, message) in held.drain(..).chain(messages) {
+                server.send(client_id, channel_id, message);
+            }
+        }
+    }
+}
+
+/// Replication messages withheld by [`LobbyMode`].
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(super) struct HeldReplication(Vec<(ClientId, u8, Bytes)>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_order() {
+        let mut app = create_app();
+        app.init_resource::<LobbyMode>();
+
+        let mut server = app.world_mut().resource_mut::<RepliconServer>();
+        server.send(CLIENT_ID, ReplicationChannel::Updates, vec![0]);
+        server.send(CLIENT_ID, ReplicationChannel::Mutations, vec![1]);
+        server.send(CLIENT_ID, EVENT_CHANNEL, vec![2]);
+
+        app.update();
+
+        let mut server = app.world_mut().resource_mut::<RepliconServer>();
+        let messages: Vec<_> = server.drain_sent().collect();
+        assert_eq!(
+            messages,
+            [(CLIENT_ID, EVENT_CHANNEL, vec![2].into())],
+            "only events should be sent in lobby"
+        );
+        server.send(CLIENT_ID, ReplicationChannel::Updates, vec![3]);
+
+        app.update();
+
+        let mut server = app.world_mut().resource_mut::<RepliconServer>();
+        assert_eq!(server.drain_sent().count(), 0);
+        server.send(CLIENT_ID, ReplicationChannel::Updates, vec![4]);
+        app.world_mut().remove_resource::<LobbyMode>();
+
+        app.update();
+
+        let mut server = app.world_mut().resource_mut::<RepliconServer>();
+        let messages: Vec<_> = server.drain_sent().collect();
+        let updates = ReplicationChannel::Updates as u8;
+        assert_eq!(
+            messages,
+            [
+                (CLIENT_ID, updates, vec![0].into()),
+                (CLIENT_ID, updates, vec![3].into()),
+                (CLIENT_ID, updates, vec![4].into()),
+            ],
+            "held messages should be sent first, mutations should be dropped"
+        );
+        assert!(app.world().resource::<HeldReplication>().is_empty());
+    }
+
+    #[test]
+    fn disconnect() {
+        let mut app = create_app();
+        app.init_resource::<LobbyMode>();
+
+        const OTHER_ID: ClientId = ClientId::new(2);
+        let mut server = app.world_mut().resource_mut::<RepliconServer>();
+        server.send(CLIENT_ID, ReplicationChannel::Updates, vec![0]);
+        server.send(OTHER_ID, ReplicationChannel::Updates, vec![1]);
+
+        app.update();
+
+        app.world_mut().send_event(ServerEvent::ClientDisconnected {
+            client_id: CLIENT_ID,
+            reason: Default::default(),
+        });
+        app.world_mut().remove_resource::<LobbyMode>();
+
+        app.update();
+
+        let mut server = app.world_mut().resource_mut::<RepliconServer>();
+        let messages: Vec<_> = server.drain_sent().collect();
+        assert_eq!(
+            messages,
+            [(OTHER_ID, ReplicationChannel::Updates as u8, vec![1].into())],
+            "messages for disconnected clients should be dropped"
+        );
+    }
+
+    fn create_app() -> App {
+        let mut server = RepliconServer::default();
+        server.set_running(true);
+
+        let mut app = App::new();
+        app.add_event::<ServerEvent>()
+            .insert_resource(server)
+            .add_plugins(LobbyPlugin);
+
+        app
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const EVENT_CHANNEL: u8 = 2;
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+/// Marker resource that makes the server refuse new connections.
+///
+/// While present, each newly connected client is immediately disconnected and reported via
+/// [`ClientRejected`](super::rejection::ClientRejected) with
+/// [`RejectReason::Maintenance`](super::rejection::RejectReason::Maintenance).
+/// Already connected clients are not affected.
+///
+/// Useful for draining a server before shutdown: insert this resource,
+/// wait until [`ConnectedClients`](bevy_replicon::prelude::ConnectedClients) becomes empty
+/// and then remove [`RenetServer`](bevy_renet::renet::RenetServer).
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct MaintenanceMode;
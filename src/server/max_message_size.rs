@@ -0,0 +1,194 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use crate::ReceiveLimit;
+
+/// Maximum size of messages received from clients per channel.
+///
+/// Renet limits only the total memory of a channel, so a malicious or buggy client can send a single message
+/// that is large enough to make the server spend time on it in transforms and Replicon's deserialization.
+/// With this resource, each message is checked in [`ServerSet::ReceivePackets`] right after it's received from renet,
+/// before [`MessageTransforms`](crate::MessageTransforms) and before it reaches Replicon. Messages that exceed
+/// the limit of their channel are discarded, reported via [`OversizedMessage`] and handled according
+/// to [`Self::action`].
+///
+/// Limits are checked against the size on the wire, so include the overhead of transforms, like encryption,
+/// and tags, like [`MessageTimestamps`](crate::MessageTimestamps). Channel IDs are IDs of client channels
+/// from [`RepliconChannels`].
+///
+/// Disabled by default, insert this resource to enable it.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::{MaxMessageSize, OversizedMessageAction};
+///
+/// # let mut app = App::new();
+/// const CHAT_CHANNEL: u8 = 2;
+/// app.insert_resource(
+///     MaxMessageSize::default()
+///         .with_channel(CHAT_CHANNEL, 512)
+///         .with_action(OversizedMessageAction::Disconnect),
+/// );
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct MaxMessageSize {
+    /// Limit for channels without a specific limit.
+    ///
+    /// By default, [`Self::DEFAULT_LIMIT`].
+    pub default_limit: usize,
+
+    /// What to do with clients that sent an oversized message.
+    pub action: OversizedMessageAction,
+
+    channels: HashMap<u8, usize>,
+}
+
+impl MaxMessageSize {
+    /// Default limit for all channels, which is enough for typical game events and inputs.
+    pub const DEFAULT_LIMIT: usize = 64 * 1024;
+
+    /// Sets [`Self::default_limit`].
+    #[must_use]
+    pub fn with_default_limit(mut self, limit: usize) -> Self {
+        self.default_limit = limit;
+        self
+    }
+
+    /// Sets a limit for a specific client channel.
+    #[must_use]
+    pub fn with_channel(mut self, channel_id: impl Into<u8>, limit: usize) -> Self {
+        self.channels.insert(channel_id.into(), limit);
+        self
+    }
+
+    /// Sets [`Self::action`].
+    #[must_use]
+    pub fn with_action(mut self, action: OversizedMessageAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Returns the limit for the client channel.
+    pub fn limit(&self, channel_id: u8) -> usize {
+        self.channels
+            .get(&channel_id)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+}
+
+impl Default for MaxMessageSize {
+    fn default() -> Self {
+        Self {
+            default_limit: Self::DEFAULT_LIMIT,
+            action: Default::default(),
+            channels: Default::default(),
+        }
+    }
+}
+
+/// Action for [`MaxMessageSize`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedMessageAction {
+    /// Log a warning and discard the message.
+    #[default]
+    Discard,
+    /// Log a warning, discard the message with all remaining messages from the client in this frame
+    /// and disconnect the client.
+    Disconnect,
+}
+
+/// An event that indicates that a client sent a message that exceeds [`MaxMessageSize`].
+///
+/// Emitted in [`ServerSet::ReceivePackets`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OversizedMessage {
+    pub client_id: ClientId,
+
+    /// Client channel over which the message was sent.
+    pub channel_id: u8,
+
+    /// Size of the message in bytes.
+    pub len: usize,
+
+    /// Limit of the channel in bytes.
+    pub limit: usize,
+}
+
+/// Limits applied to messages received from renet.
+#[derive(SystemParam)]
+pub(super) struct ReceiveLimits<'w, 's> {
+    receive_limit: Res<'w, ReceiveLimit>,
+    max_size: Option<Res<'w, MaxMessageSize>>,
+    oversized_events: EventWriter<'w, OversizedMessage>,
+    violators: Local<'s, HashSet<ClientId>>,
+}
+
+impl ReceiveLimits<'_, '_> {
+    /// Returns the limit on the number of messages per channel.
+    pub(super) fn receive_limit(&self) -> ReceiveLimit {
+        *self.receive_limit
+    }
+
+    /// Returns `true` if the message fits into [`MaxMessageSize`].
+    pub(super) fn check_size(&mut self, client_id: ClientId, channel_id: u8, len: usize) -> bool {
+        let Some(max_size) = &self.max_size else {
+            return true;
+        };
+
+        if self.violators.contains(&client_id) {
+            return false;
+        }
+
+        let limit = max_size.limit(channel_id);
+        if len <= limit {
+            return true;
+        }
+
+        self.oversized_events.send(OversizedMessage {
+            client_id,
+            channel_id,
+            len,
+            limit,
+        });
+        match max_size.action {
+            OversizedMessageAction::Discard => {
+                warn!("discarding {len} bytes from `{client_id:?}` over channel {channel_id} exceeding {limit} bytes");
+            }
+            OversizedMessageAction::Disconnect => {
+                warn!("disconnecting `{client_id:?}` for sending {len} bytes over channel {channel_id} exceeding {limit} bytes");
+                self.violators.insert(client_id);
+            }
+        }
+
+        false
+    }
+
+    /// Disconnects clients that violated [`MaxMessageSize`] with [`OversizedMessageAction::Disconnect`].
+    pub(super) fn disconnect_violators(&mut self, renet_server: &mut RenetServer) {
+        for client_id in self.violators.drain() {
+            renet_server.disconnect(client_id.get());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let max_size = MaxMessageSize::default()
+            .with_default_limit(16)
+            .with_channel(1, 4);
+        assert_eq!(max_size.limit(0), 16);
+        assert_eq!(max_size.limit(1), 4);
+    }
+}
@@ -0,0 +1,252 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::{ChannelConfig, RenetServer};
+use bevy_replicon::prelude::*;
+
+use super::backpressure::SendBackpressure;
+use crate::channel_policy::REPLICATION_CHANNELS_COUNT;
+
+/// Caps the memory used by server channels across all clients.
+///
+/// Each renet connection allocates `max_memory_usage_bytes` per channel, so the worst case
+/// grows with both the number of channels and clients. Renet fixes these limits when a connection
+/// is created, so they can't be lowered at runtime. Instead, this resource splits [`Self::max_bytes`]
+/// between connected clients according to [`Self::share`] and sheds messages before they reach renet
+/// once a client uses more than its share:
+///
+/// 1. Messages over unreliable event channels are dropped after a client uses 3/4 of its share.
+/// 2. Messages over [`ReplicationChannel::Mutations`](bevy_replicon::core::channels::ReplicationChannel::Mutations)
+///    are dropped after a client uses its whole share. Replicon resends unacknowledged mutations,
+///    so clients will receive the latest state once the memory is freed.
+///
+/// Reliable channels are never shed, since dropping their messages would break delivery guarantees.
+/// Messages that don't fit are held back by [`SendBackpressure`] instead, and its queued bytes are
+/// counted as used memory.
+///
+/// This is an approximation. Usage is computed from renet's available memory of each channel, which
+/// includes messages that were already sent but not acknowledged yet, and doesn't include memory
+/// that renet uses for packet buffers and fragmentation. The share also changes as clients connect
+/// and disconnect, so the cap can be briefly exceeded when many clients join at once.
+/// Choose per-channel limits such that a single client's worst case stays reasonable.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Debug, Clone)]
+pub struct TotalMemoryBudget {
+    /// Maximum bytes used by server channels of all clients.
+    pub max_bytes: usize,
+
+    /// How [`Self::max_bytes`] is split between clients.
+    ///
+    /// [`BudgetShare::Even`] by default.
+    pub share: BudgetShare,
+
+    channel_limits: Vec<(u8, usize)>,
+    used_bytes: usize,
+}
+
+impl TotalMemoryBudget {
+    /// Creates a budget for a server created with the given channel configs.
+    ///
+    /// Configs are used to know the limits of each channel, so they must match the ones
+    /// passed to [`ConnectionConfig::server_channels_config`](bevy_renet::renet::ConnectionConfig::server_channels_config).
+    pub fn new(max_bytes: usize, server_configs: &[ChannelConfig]) -> Self {
+        Self {
+            max_bytes,
+            share: Default::default(),
+            channel_limits: server_configs
+                .iter()
+                .map(|config| (config.channel_id, config.max_memory_usage_bytes))
+                .collect(),
+            used_bytes: 0,
+        }
+    }
+
+    /// Returns the total bytes used by server channels during the last send.
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    /// Sets [`Self::share`].
+    #[must_use]
+    pub fn with_share(mut self, share: BudgetShare) -> Self {
+        self.share = share;
+        self
+    }
+
+    /// Returns the bytes each client can use with the given number of connected clients.
+    ///
+    /// Used with [`BudgetShare::Even`].
+    pub fn client_share(&self, clients_count: usize) -> usize {
+        self.max_bytes / clients_count.max(1)
+    }
+
+    /// Returns the bytes a client with the given weight can use when weights of all connected clients sum up
+    /// to `total_weight`.
+    ///
+    /// Used with [`BudgetShare::Visibility`].
+    pub fn weighted_share(&self, weight: usize, total_weight: usize) -> usize {
+        let share = self.max_bytes as u128 * weight as u128 / total_weight.max(1) as u128;
+        share as usize
+    }
+
+    /// Returns the bytes used by server channels of the client.
+    fn client_usage(&self, renet_server: &RenetServer, client_id: ClientId) -> usize {
+        self.channel_limits
+            .iter()
+            .map(|&(channel_id, limit)| {
+                let available = renet_server.channel_available_memory(client_id.get(), channel_id);
+                limit.saturating_sub(available)
+            })
+            .sum()
+    }
+}
+
+/// Strategy for splitting [`TotalMemoryBudget::max_bytes`] between clients.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetShare {
+    /// Each client gets the same share.
+    #[default]
+    Even,
+
+    /// Each client gets a share proportional to the number of replicated entities visible to it plus one.
+    ///
+    /// Clients with large views receive more replication data, so with an even split they would
+    /// be starved by clients that see only a small part of the world. The extra one keeps a share for
+    /// clients that see nothing, so they can still receive events.
+    ///
+    /// Visibility is taken from [`ReplicatedClients`], so it matches only with [`VisibilityPolicy::Blacklist`]
+    /// or [`VisibilityPolicy::Whitelist`]. With [`VisibilityPolicy::All`] it splits the budget evenly.
+    /// Clients that don't receive replication yet get the minimal weight.
+    ///
+    /// Counting takes O(entities × clients) on each send, so prefer [`Self::Even`] for large worlds
+    /// with many clients unless bandwidth is the bottleneck.
+    Visibility,
+}
+
+pub(super) struct MemoryBudgetPlugin;
+
+impl Plugin for MemoryBudgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            Self::shed
+                .in_set(ServerSet::SendPackets)
+                .after(super::lobby::LobbyPlugin::hold_replication)
+                .before(super::RepliconRenetServerPlugin::send_packets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<TotalMemoryBudget>),
+        );
+    }
+}
+
+impl MemoryBudgetPlugin {
+    pub(super) fn shed(
+        channels: Res<RepliconChannels>,
+        backpressure: Res<SendBackpressure>,
+        renet_server: Res<RenetServer>,
+        replicated_clients: Res<ReplicatedClients>,
+        entities: Query<Entity, With<Replicated>>,
+        mut budget: ResMut<TotalMemoryBudget>,
+        mut server: ResMut<RepliconServer>,
+    ) {
+        let mut usage: HashMap<_, _> = renet_server
+            .clients_id_iter()
+            .map(ClientId::new)
+            .filter(|&client_id| renet_server.is_connected(client_id.get()))
+            .map(|client_id| {
+                let used = budget.client_usage(&renet_server, client_id)
+                    + backpressure.queued_bytes(client_id);
+                (client_id, used)
+            })
+            .collect();
+
+        let weights: HashMap<_, _> = match budget.share {
+            BudgetShare::Even => Default::default(),
+            BudgetShare::Visibility => usage
+                .keys()
+                .map(|&client_id| {
+                    let visible = replicated_clients
+                        .get_client(client_id)
+                        .map(|client| {
+                            entities
+                                .iter()
+                                .filter(|&entity| client.visibility().is_visible(entity))
+                                .count()
+                        })
+                        .unwrap_or_default();
+                    (client_id, visible + 1)
+                })
+                .collect(),
+        };
+        let total_weight = weights.values().sum();
+        let even_share = budget.client_share(usage.len());
+
+        let mut shed_count = 0;
+        let messages: Vec<_> = server.drain_sent().collect();
+        for (client_id, channel_id, message) in messages {
+            let share = match weights.get(&client_id) {
+                Some(&weight) => budget.weighted_share(weight, total_weight),
+                None => even_share,
+            };
+            let used = usage.entry(client_id).or_default();
+            let kind = channels.server_channels()[channel_id as usize].kind;
+            if kind == ChannelKind::Unreliable {
+                let threshold = if (channel_id as usize) < REPLICATION_CHANNELS_COUNT {
+                    share
+                } else {
+                    share / 4 * 3
+                };
+                if *used + message.len() > threshold {
+                    trace!(
+                        "shedding {} bytes for `{client_id:?}` over channel {channel_id}",
+                        message.len()
+                    );
+                    shed_count += 1;
+                    continue;
+                }
+            }
+
+            *used += message.len();
+            server.send(client_id, channel_id, message);
+        }
+
+        if shed_count > 0 {
+            debug!("shed {shed_count} messages due to exceeded memory budget");
+        }
+
+        budget.used_bytes = usage.values().sum();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_renet::renet::SendType;
+
+    use super::*;
+
+    #[test]
+    fn client_share() {
+        let config = ChannelConfig {
+            channel_id: 0,
+            max_memory_usage_bytes: 1000,
+            send_type: SendType::Unreliable,
+        };
+        let budget = TotalMemoryBudget::new(3000, &[config]);
+        assert_eq!(budget.client_share(0), 3000);
+        assert_eq!(budget.client_share(3), 1000);
+    }
+
+    #[test]
+    fn weighted_share() {
+        let budget = TotalMemoryBudget::new(5000, &[]);
+        assert_eq!(budget.weighted_share(4, 5), 4000);
+        assert_eq!(budget.weighted_share(1, 5), 1000);
+        assert_eq!(budget.weighted_share(1, 0), 5000);
+
+        let budget = TotalMemoryBudget::new(usize::MAX, &[]);
+        assert_eq!(
+            budget.weighted_share(usize::MAX, usize::MAX),
+            usize::MAX,
+            "shouldn't overflow"
+        );
+    }
+}
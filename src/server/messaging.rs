@@ -0,0 +1,156 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::{Bytes, RenetServer};
+use bevy_replicon::prelude::*;
+
+/// Sends non-Replicon data, like chat or voice metadata, over custom renet channels.
+///
+/// Messages are passed to [`RenetServer`] as is, without [`MessageTransforms`](crate::MessageTransforms),
+/// and only to clients from [`ConnectedClients`], so rejected clients won't receive them.
+/// On the client read them directly from [`RenetClient`](bevy_renet::renet::RenetClient).
+///
+/// Replicon manages channels starting from 0, so custom channel IDs must start after
+/// the last channel from [`RepliconChannels`]. Make sure to create all Replicon channels
+/// (they are also created when registering events) before assigning custom IDs and add
+/// a [`ChannelConfig`](bevy_renet::renet::ChannelConfig) for each of them to `server_channels_config`.
+#[derive(SystemParam)]
+pub struct ServerMessaging<'w> {
+    channels: Res<'w, RepliconChannels>,
+    connected_clients: Res<'w, ConnectedClients>,
+    renet_server: ResMut<'w, RenetServer>,
+    prioritized: ResMut<'w, PrioritizedMessages>,
+}
+
+impl ServerMessaging<'_> {
+    /// Sends a message to a single client.
+    pub fn send(&mut self, client_id: ClientId, channel_id: u8, message: impl Into<Bytes>) {
+        self.renet_server
+            .send_message(client_id.get(), channel_id, message);
+    }
+
+    /// Queues a message to a single client and sends it at the end of the frame.
+    ///
+    /// Before sending, queued messages are reordered by priority, so messages with a higher priority
+    /// are passed to renet first. Messages with equal priority keep their order. Renet packs messages
+    /// from a channel in the order they were passed, so higher priority messages get into packets first
+    /// when the channel has more data than fits into `available_bytes_per_tick` from
+    /// [`ConnectionConfig`](bevy_renet::renet::ConnectionConfig).
+    ///
+    /// Messages from [`Self::send`] and Replicon are passed to renet immediately, so they aren't reordered
+    /// with prioritized messages. Use it only for channels with
+    /// [`SendType::Unreliable`](bevy_renet::renet::SendType::Unreliable) or
+    /// [`SendType::ReliableUnordered`](bevy_renet::renet::SendType::ReliableUnordered),
+    /// since reordering would break the guarantees of ordered channels.
+    /// Calls for ordered channels from [`RepliconChannels`] are refused with an error
+    /// and the message is sent like with [`Self::send`] instead. Send types of custom channels
+    /// are unknown to the crate, so it's up to the caller to use only unordered ones.
+    pub fn send_prioritized(
+        &mut self,
+        client_id: ClientId,
+        channel_id: u8,
+        priority: u8,
+        message: impl Into<Bytes>,
+    ) {
+        let ordered = self
+            .channels
+            .server_channels()
+            .get(channel_id as usize)
+            .is_some_and(|channel| channel.kind == ChannelKind::Ordered);
+        if ordered {
+            error!("channel {channel_id} is ordered and can't be prioritized, sending without reordering");
+            self.send(client_id, channel_id, message);
+            return;
+        }
+
+        self.prioritized.push(PrioritizedMessage {
+            client_id,
+            channel_id,
+            priority,
+            message: message.into(),
+        });
+    }
+
+    /// Sends a message to all connected clients.
+    pub fn broadcast(&mut self, channel_id: u8, message: impl Into<Bytes>) {
+        let message = message.into();
+        for client in self.connected_clients.iter() {
+            self.renet_server
+                .send_message(client.id().get(), channel_id, message.clone());
+        }
+    }
+
+    /// Like [`Self::broadcast`], but takes a shared payload without copying it.
+    ///
+    /// The payload is wrapped once and each client receives a reference-counted handle to it, so broadcasting
+    /// large messages to many clients doesn't allocate per client. Renet still allocates its own per-client
+    /// bookkeeping and packets. Useful when the same payload is already shared elsewhere, for example,
+    /// cached for late joiners.
+    pub fn broadcast_shared(&mut self, channel_id: u8, message: Arc<[u8]>) {
+        self.broadcast(channel_id, Bytes::from_owner(message));
+    }
+
+    /// Sends a message to all connected clients except the specified one.
+    pub fn broadcast_except(
+        &mut self,
+        client_id: ClientId,
+        channel_id: u8,
+        message: impl Into<Bytes>,
+    ) {
+        let message = message.into();
+        for client in self
+            .connected_clients
+            .iter()
+            .filter(|client| client.id() != client_id)
+        {
+            self.renet_server
+                .send_message(client.id().get(), channel_id, message.clone());
+        }
+    }
+}
+
+/// Messages from [`ServerMessaging::send_prioritized`] that wait for sending.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PrioritizedMessages(Vec<PrioritizedMessage>);
+
+struct PrioritizedMessage {
+    client_id: ClientId,
+    channel_id: u8,
+    priority: u8,
+    message: Bytes,
+}
+
+pub(super) struct MessagingPlugin;
+
+impl Plugin for MessagingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrioritizedMessages>().add_systems(
+            PostUpdate,
+            Self::send_prioritized
+                .in_set(ServerSet::SendPackets)
+                .run_if(resource_exists::<RenetServer>),
+        );
+    }
+}
+
+impl MessagingPlugin {
+    fn send_prioritized(
+        mut prioritized: ResMut<PrioritizedMessages>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        // Stable sort keeps the order of messages with equal priority.
+        prioritized.sort_by_key(|message| Reverse(message.priority));
+        for message in prioritized.drain(..) {
+            let client_id = message.client_id.get();
+            if !renet_server.is_connected(client_id) {
+                debug!(
+                    "skipping prioritized message over channel {} for disconnected `{:?}`",
+                    message.channel_id, message.client_id
+                );
+                continue;
+            }
+
+            renet_server.send_message(client_id, message.channel_id, message.message);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::{
+    netcode::{NetcodeTransportError, ServerConfig, NETCODE_USER_DATA_BYTES},
+    renet::RenetServer,
+    RenetReceive, RenetSend, RenetServerPlugin,
+};
+use renetcode::{NetcodeServer, ServerResult, NETCODE_MAX_PACKET_BYTES};
+
+/// Netcode server transport that accepts connections on multiple sockets.
+///
+/// Use it instead of [`NetcodeServerTransport`](bevy_renet::netcode::NetcodeServerTransport) to listen
+/// on several ports or interfaces at once, for example, on a public and a LAN port or on a range of ports
+/// for NAT traversal. Multiple instances of `NetcodeServerTransport` can't feed a single [`RenetServer`],
+/// since each of them sends packets for every client of the server, including clients of other instances.
+///
+/// All sockets share a single netcode server, so client IDs stay unique across sockets the same way as
+/// with a single socket: a client can't connect with an ID that is already connected, regardless of the
+/// socket it connects to, and [`ServerConfig::max_clients`] applies to all sockets together.
+/// Packets to a client are always sent over the socket the client connected to.
+///
+/// [`ServerConfig::public_addresses`] needs to contain public addresses of all sockets, because netcode
+/// accepts only connect tokens that were issued for one of them.
+///
+/// Works with [`ClientAddrs`](crate::ClientAddrs), [`AuthorizeByUserData`](crate::AuthorizeByUserData)
+/// and [`ServerTransportError`](crate::ServerTransportError) the same way as `NetcodeServerTransport`.
+///
+/// Only available with the `renet_netcode` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{
+///     net::{Ipv4Addr, SocketAddr, UdpSocket},
+///     time::SystemTime,
+/// };
+///
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::{
+///     netcode::{ServerAuthentication, ServerConfig},
+///     MultiSocketServerTransport,
+/// };
+///
+/// # let mut app = App::new();
+/// let public_socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 5000)).unwrap();
+/// let lan_socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 5001)).unwrap();
+/// let current_time = SystemTime::now()
+///     .duration_since(SystemTime::UNIX_EPOCH)
+///     .unwrap();
+/// let server_config = ServerConfig {
+///     current_time,
+///     max_clients: 10,
+///     protocol_id: 0,
+///     public_addresses: vec![
+///         SocketAddr::new(Ipv4Addr::new(203, 0, 113, 1).into(), 5000),
+///         SocketAddr::new(Ipv4Addr::new(192, 168, 0, 2).into(), 5001),
+///     ],
+///     authentication: ServerAuthentication::Unsecure,
+/// };
+/// let transport =
+///     MultiSocketServerTransport::new(server_config, vec![public_socket, lan_socket]).unwrap();
+///
+/// app.insert_resource(transport);
+/// ```
+#[derive(Resource, Debug)]
+pub struct MultiSocketServerTransport {
+    sockets: Vec<UdpSocket>,
+    netcode_server: NetcodeServer,
+    /// Indices of sockets for addresses of connected clients.
+    routes: HashMap<SocketAddr, usize>,
+    buffer: [u8; NETCODE_MAX_PACKET_BYTES],
+}
+
+impl MultiSocketServerTransport {
+    /// Creates a transport that listens on all given sockets.
+    ///
+    /// Returns an error if no sockets are passed or a socket can't be switched to non-blocking mode.
+    pub fn new(server_config: ServerConfig, sockets: Vec<UdpSocket>) -> io::Result<Self> {
+        if sockets.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "at least one socket is required",
+            ));
+        }
+        for socket in &sockets {
+            socket.set_nonblocking(true)?;
+        }
+
+        Ok(Self {
+            sockets,
+            netcode_server: NetcodeServer::new(server_config),
+            routes: Default::default(),
+            buffer: [0; NETCODE_MAX_PACKET_BYTES],
+        })
+    }
+
+    /// Returns the server public addresses.
+    pub fn addresses(&self) -> Vec<SocketAddr> {
+        self.netcode_server.addresses()
+    }
+
+    /// Returns the local addresses of all sockets.
+    pub fn local_addrs(&self) -> io::Result<Vec<SocketAddr>> {
+        self.sockets.iter().map(UdpSocket::local_addr).collect()
+    }
+
+    /// Returns the maximum number of clients that can be connected over all sockets.
+    pub fn max_clients(&self) -> usize {
+        self.netcode_server.max_clients()
+    }
+
+    /// Updates the maximum number of clients that can be connected over all sockets.
+    ///
+    /// Lowering the value doesn't disconnect already connected clients.
+    pub fn set_max_clients(&mut self, max_clients: usize) {
+        self.netcode_server.set_max_clients(max_clients);
+    }
+
+    /// Returns the number of clients connected over all sockets.
+    pub fn connected_clients(&self) -> usize {
+        self.netcode_server.connected_clients()
+    }
+
+    /// Returns the user data of a connected client.
+    pub fn user_data(&self, client_id: u64) -> Option<[u8; NETCODE_USER_DATA_BYTES]> {
+        self.netcode_server.user_data(client_id)
+    }
+
+    /// Returns the address of a connected client.
+    pub fn client_addr(&self, client_id: u64) -> Option<SocketAddr> {
+        self.netcode_server.client_addr(client_id)
+    }
+
+    /// Returns the local address of the socket the client is connected to.
+    pub fn client_local_addr(&self, client_id: u64) -> Option<SocketAddr> {
+        let addr = self.netcode_server.client_addr(client_id)?;
+        let &index = self.routes.get(&addr)?;
+        self.sockets[index].local_addr().ok()
+    }
+
+    /// Returns the duration since the last packet from a connected client.
+    pub fn time_since_last_received_packet(&self, client_id: u64) -> Option<Duration> {
+        self.netcode_server
+            .time_since_last_received_packet(client_id)
+    }
+
+    /// Disconnects all clients, sending disconnect packets immediately.
+    ///
+    /// Use it when the server exits, otherwise prefer [`RenetServer::disconnect_all`].
+    pub fn disconnect_all(&mut self, server: &mut RenetServer) {
+        for client_id in self.netcode_server.clients_id() {
+            let result = self.netcode_server.disconnect(client_id);
+            handle_result(result, &self.sockets, &mut self.routes, None, server);
+        }
+    }
+
+    /// Advances the transport by the duration and receives packets from all sockets.
+    ///
+    /// Returns the last error, but keeps receiving from other sockets if one of them fails.
+    pub fn update(
+        &mut self,
+        duration: Duration,
+        server: &mut RenetServer,
+    ) -> Result<(), NetcodeTransportError> {
+        self.netcode_server.update(duration);
+
+        let mut status = Ok(());
+        for (index, socket) in self.sockets.iter().enumerate() {
+            loop {
+                match socket.recv_from(&mut self.buffer) {
+                    Ok((len, addr)) => {
+                        let result = self
+                            .netcode_server
+                            .process_packet(addr, &mut self.buffer[..len]);
+                        handle_result(result, &self.sockets, &mut self.routes, Some(index), server);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionReset => continue,
+                    Err(e) => {
+                        status = Err(e.into());
+                        break;
+                    }
+                }
+            }
+        }
+
+        for client_id in self.netcode_server.clients_id() {
+            let result = self.netcode_server.update_client(client_id);
+            handle_result(result, &self.sockets, &mut self.routes, None, server);
+        }
+
+        for client_id in server.disconnections_id() {
+            let result = self.netcode_server.disconnect(client_id);
+            handle_result(result, &self.sockets, &mut self.routes, None, server);
+        }
+
+        status
+    }
+
+    /// Sends packets to connected clients over their sockets.
+    pub fn send_packets(&mut self, server: &mut RenetServer) {
+        for client_id in server.clients_id() {
+            let Ok(packets) = server.get_packets_to_send(client_id) else {
+                continue;
+            };
+            for packet in packets {
+                match self
+                    .netcode_server
+                    .generate_payload_packet(client_id, &packet)
+                {
+                    Ok((addr, payload)) => {
+                        if !send_packet(&self.sockets, &self.routes, None, payload, addr) {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("unable to encrypt payload for client `{client_id}`: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Applies the result of the netcode server to renet and sockets.
+///
+/// `received_on` is the index of the socket the processed packet was received from.
+fn handle_result(
+    result: ServerResult,
+    sockets: &[UdpSocket],
+    routes: &mut HashMap<SocketAddr, usize>,
+    received_on: Option<usize>,
+    server: &mut RenetServer,
+) {
+    match result {
+        ServerResult::None => (),
+        ServerResult::PacketToSend { payload, addr } => {
+            send_packet(sockets, routes, received_on, payload, addr);
+        }
+        ServerResult::Payload { client_id, payload } => {
+            if let Err(e) = server.process_packet_from(payload, client_id) {
+                error!("unable to process payload from client `{client_id}`: {e}");
+            }
+        }
+        ServerResult::ClientConnected {
+            client_id,
+            addr,
+            payload,
+            ..
+        } => {
+            if let Some(index) = received_on {
+                routes.insert(addr, index);
+            }
+            server.add_connection(client_id);
+            send_packet(sockets, routes, received_on, payload, addr);
+        }
+        ServerResult::ClientDisconnected {
+            client_id,
+            addr,
+            payload,
+        } => {
+            server.remove_connection(client_id);
+            if let Some(payload) = payload {
+                send_packet(sockets, routes, received_on, payload, addr);
+            }
+            routes.remove(&addr);
+        }
+    }
+}
+
+/// Sends the packet over the socket it was received on or the socket of the connected client.
+///
+/// Returns `false` if the packet wasn't sent.
+fn send_packet(
+    sockets: &[UdpSocket],
+    routes: &HashMap<SocketAddr, usize>,
+    received_on: Option<usize>,
+    packet: &[u8],
+    addr: SocketAddr,
+) -> bool {
+    let Some(index) = received_on.or_else(|| routes.get(&addr).copied()) else {
+        error!("no socket to send a packet to {addr}");
+        return false;
+    };
+
+    if let Err(e) = sockets[index].send_to(packet, addr) {
+        error!("unable to send a packet to {addr}: {e}");
+        return false;
+    }
+
+    true
+}
+
+pub(super) struct MultiSocketPlugin;
+
+impl Plugin for MultiSocketPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NetcodeTransportError>()
+            .add_systems(
+                PreUpdate,
+                Self::update
+                    .in_set(RenetReceive)
+                    .after(RenetServerPlugin::update_system)
+                    .before(RenetServerPlugin::emit_server_events_system)
+                    .run_if(resource_exists::<MultiSocketServerTransport>)
+                    .run_if(resource_exists::<RenetServer>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::send_packets
+                    .in_set(RenetSend)
+                    .run_if(resource_exists::<MultiSocketServerTransport>)
+                    .run_if(resource_exists::<RenetServer>),
+            )
+            .add_systems(
+                Last,
+                Self::disconnect_on_exit
+                    .run_if(resource_exists::<MultiSocketServerTransport>)
+                    .run_if(resource_exists::<RenetServer>),
+            );
+    }
+}
+
+impl MultiSocketPlugin {
+    fn update(
+        time: Res<Time>,
+        mut transport: ResMut<MultiSocketServerTransport>,
+        mut server: ResMut<RenetServer>,
+        mut transport_errors: EventWriter<NetcodeTransportError>,
+    ) {
+        if let Err(e) = transport.update(time.delta(), &mut server) {
+            transport_errors.send(e);
+        }
+    }
+
+    fn send_packets(
+        mut transport: ResMut<MultiSocketServerTransport>,
+        mut server: ResMut<RenetServer>,
+    ) {
+        transport.send_packets(&mut server);
+    }
+
+    pub(super) fn disconnect_on_exit(
+        exit_events: EventReader<AppExit>,
+        mut transport: ResMut<MultiSocketServerTransport>,
+        mut server: ResMut<RenetServer>,
+    ) {
+        if !exit_events.is_empty() {
+            transport.disconnect_all(&mut server);
+        }
+    }
+}
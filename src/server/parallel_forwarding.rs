@@ -0,0 +1,391 @@
+use std::mem;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, ComputeTaskPool, Task, TaskPool},
+    utils::HashMap,
+};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+use crate::MessageTransforms;
+
+/// Applies [`MessageTransforms`] to forwarded messages on the task pool.
+///
+/// Transforms, like encryption, are the most expensive part of forwarding.
+/// With this resource, the server applies them for different clients in parallel: messages are still drained
+/// from renet and Replicon on the main schedule, then each client's messages are transformed in a separate task
+/// on [`ComputeTaskPool`], and the results are passed to renet or Replicon in the original order within the same system.
+///
+/// Messages of a single client are transformed sequentially in the order they were sent or received, so stateful
+/// transforms, like [`OutgoingSequenceTags`](crate::OutgoingSequenceTags), see the same sequence as without
+/// this resource. The only requirement is that transforms don't share state between clients that depends on
+/// the order, since clients are processed concurrently.
+///
+/// Spawning tasks has its own cost, so messages are transformed on the calling thread if the frame has fewer
+/// than [`Self::min_messages`] of them. See the `parallel_forwarding` group in the forwarding benchmark
+/// to pick the threshold for your transforms.
+///
+/// # Background forwarding
+///
+/// With [`Self::background`] enabled, transforms run in a background task on [`AsyncComputeTaskPool`] instead,
+/// concurrently with the rest of the frame. Messages are double-buffered: messages drained in a frame are transformed
+/// while the game logic runs and are passed to renet or Replicon by the same forwarding system in the next frame,
+/// before the messages of that frame. If the task isn't finished by then, the system waits for it.
+///
+/// This moves the transforms off the main schedule at the cost of one frame of latency in each direction.
+/// The order of messages and frame boundaries are preserved: messages drained in one frame are always passed
+/// together and before messages of later frames, so replication messages arrive in the same groups as without
+/// this mode. Messages of clients that disconnect while their messages are in flight are dropped.
+///
+/// [`RenetServer`](bevy_renet::renet::RenetServer) itself stays on the main schedule. It's accessed by many systems
+/// each frame, including the transport, so moving it to a task would require locking it for the whole frame.
+/// Without transforms there is nothing to move, so messages are forwarded synchronously in the same frame.
+///
+/// Disabled by default, insert this resource to enable it.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::ParallelForwarding;
+///
+/// # let mut app = App::new();
+/// app.insert_resource(
+///     ParallelForwarding::default()
+///         .with_min_messages(128)
+///         .with_background(true),
+/// );
+/// ```
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ParallelForwarding {
+    /// Minimum number of messages in a frame to transform them in parallel.
+    ///
+    /// By default, 64.
+    pub min_messages: usize,
+
+    /// Transform messages in a background task across frames.
+    ///
+    /// See [background forwarding](Self#background-forwarding) for details.
+    ///
+    /// By default, `false`.
+    pub background: bool,
+}
+
+impl ParallelForwarding {
+    /// Sets [`Self::min_messages`].
+    #[must_use]
+    pub fn with_min_messages(mut self, min_messages: usize) -> Self {
+        self.min_messages = min_messages;
+        self
+    }
+
+    /// Sets [`Self::background`].
+    #[must_use]
+    pub fn with_background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Returns `true` if messages should be transformed on the task pool.
+    fn is_parallel(parallel: Option<&Self>, transforms: &MessageTransforms, len: usize) -> bool {
+        parallel.is_some_and(|parallel| len >= parallel.min_messages) && !transforms.is_empty()
+    }
+
+    /// Returns `true` if messages should be transformed in a background task.
+    fn is_background(parallel: Option<&Self>, transforms: &MessageTransforms, len: usize) -> bool {
+        parallel.is_some_and(|parallel| parallel.background) && !transforms.is_empty() && len > 0
+    }
+}
+
+impl Default for ParallelForwarding {
+    fn default() -> Self {
+        Self {
+            min_messages: 64,
+            background: false,
+        }
+    }
+}
+
+/// Message with its client and channel.
+type Message = (ClientId, u8, Bytes);
+
+/// Background tasks that transform messages drained in the previous frame.
+///
+/// See [background forwarding](ParallelForwarding#background-forwarding).
+#[derive(Resource, Default)]
+pub(super) struct ForwardingPipeline {
+    outgoing: Option<Task<Vec<Message>>>,
+    incoming: Option<Task<Vec<Message>>>,
+}
+
+impl ForwardingPipeline {
+    /// Drops all messages in flight.
+    pub(super) fn clear(&mut self) {
+        self.outgoing = None;
+        self.incoming = None;
+    }
+}
+
+/// [`MessageTransforms`] applied in parallel if [`ParallelForwarding`] is enabled.
+#[derive(SystemParam)]
+pub(super) struct ServerTransforms<'w> {
+    transforms: Res<'w, MessageTransforms>,
+    parallel: Option<Res<'w, ParallelForwarding>>,
+    pipeline: ResMut<'w, ForwardingPipeline>,
+}
+
+impl ServerTransforms<'_> {
+    /// Applies outgoing transforms to all messages.
+    ///
+    /// In background mode, replaces the messages with the ones transformed since the previous call
+    /// and starts transforming the passed ones.
+    pub(super) fn apply_outgoing(&mut self, messages: &mut Vec<Message>) {
+        let parallel = self.parallel.as_deref().copied();
+        let previous = self.pipeline.outgoing.take().map(block_on);
+        if ParallelForwarding::is_background(parallel.as_ref(), &self.transforms, messages.len()) {
+            let transforms = self.transforms.clone();
+            let mut batch = mem::take(messages);
+            let task = AsyncComputeTaskPool::get_or_init(TaskPool::default).spawn(async move {
+                apply_outgoing(parallel.as_ref(), &transforms, &mut batch);
+                batch
+            });
+            self.pipeline.outgoing = Some(task);
+        } else {
+            apply_outgoing(parallel.as_ref(), &self.transforms, messages);
+        }
+
+        prepend(messages, previous);
+    }
+
+    /// Applies incoming transforms to all messages and removes rejected ones.
+    ///
+    /// In background mode, replaces the messages with the ones transformed since the previous call
+    /// and starts transforming the passed ones.
+    pub(super) fn apply_incoming(&mut self, messages: &mut Vec<Message>) {
+        let parallel = self.parallel.as_deref().copied();
+        let previous = self.pipeline.incoming.take().map(block_on);
+        if ParallelForwarding::is_background(parallel.as_ref(), &self.transforms, messages.len()) {
+            let transforms = self.transforms.clone();
+            let mut batch = mem::take(messages);
+            let task = AsyncComputeTaskPool::get_or_init(TaskPool::default).spawn(async move {
+                apply_incoming(parallel.as_ref(), &transforms, &mut batch);
+                batch
+            });
+            self.pipeline.incoming = Some(task);
+        } else {
+            apply_incoming(parallel.as_ref(), &self.transforms, messages);
+        }
+
+        prepend(messages, previous);
+    }
+}
+
+/// Inserts messages from the previous frame before the current ones.
+fn prepend(messages: &mut Vec<Message>, previous: Option<Vec<Message>>) {
+    if let Some(mut previous) = previous {
+        previous.append(messages);
+        *messages = previous;
+    }
+}
+
+fn apply_outgoing(
+    parallel: Option<&ParallelForwarding>,
+    transforms: &MessageTransforms,
+    messages: &mut [Message],
+) {
+    if !ParallelForwarding::is_parallel(parallel, transforms, messages.len()) {
+        for (client_id, channel_id, message) in messages {
+            *message = transforms.apply_outgoing(*client_id, *channel_id, message.clone());
+        }
+        return;
+    }
+
+    let results = transform_per_client(messages, |client_id, channel_id, message| {
+        transforms.apply_outgoing(client_id, channel_id, message)
+    });
+    for (index, message) in results {
+        messages[index].2 = message;
+    }
+}
+
+fn apply_incoming(
+    parallel: Option<&ParallelForwarding>,
+    transforms: &MessageTransforms,
+    messages: &mut Vec<Message>,
+) {
+    let mut transformed = vec![None; messages.len()];
+    if ParallelForwarding::is_parallel(parallel, transforms, messages.len()) {
+        let results = transform_per_client(messages, |client_id, channel_id, message| {
+            transforms.apply_incoming(client_id, channel_id, message)
+        });
+        for (index, message) in results {
+            transformed[index] = message;
+        }
+    } else {
+        for (message, &(client_id, channel_id, ref original)) in
+            transformed.iter_mut().zip(messages.iter())
+        {
+            *message = transforms.apply_incoming(client_id, channel_id, original.clone());
+        }
+    }
+
+    let mut transformed = transformed.into_iter();
+    messages.retain_mut(|(client_id, _, message)| {
+        let Some(transformed) = transformed.next().flatten() else {
+            debug!("discarding message from `{client_id:?}` rejected by transform");
+            return false;
+        };
+
+        *message = transformed;
+        true
+    });
+}
+
+/// Runs `transform` for each client's messages in a separate task.
+///
+/// Returns results with indices of the original messages.
+fn transform_per_client<T: Send + 'static>(
+    messages: &[Message],
+    transform: impl Fn(ClientId, u8, Bytes) -> T + Sync,
+) -> Vec<(usize, T)> {
+    let mut clients = HashMap::<ClientId, Vec<usize>>::default();
+    for (index, &(client_id, ..)) in messages.iter().enumerate() {
+        clients.entry(client_id).or_default().push(index);
+    }
+
+    let transform = &transform;
+    ComputeTaskPool::get_or_init(TaskPool::default)
+        .scope(|scope| {
+            for indices in clients.into_values() {
+                scope.spawn(async move {
+                    indices
+                        .into_iter()
+                        .map(|index| {
+                            let (client_id, channel_id, ref message) = messages[index];
+                            (index, transform(client_id, channel_id, message.clone()))
+                        })
+                        .collect::<Vec<_>>()
+                });
+            }
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::{IncomingTransform, OutgoingTransform};
+
+    #[test]
+    fn outgoing_order() {
+        let mut transforms = MessageTransforms::default();
+        transforms.add_outgoing(Counter::default());
+
+        let mut messages = messages();
+        apply_outgoing(
+            Some(&ParallelForwarding::default().with_min_messages(0)),
+            &transforms,
+            &mut messages,
+        );
+
+        let counters: Vec<_> = messages.iter().map(|(.., message)| message[0]).collect();
+        assert_eq!(counters, [0, 0, 1, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn background_order() {
+        let mut world = World::new();
+        let mut transforms = MessageTransforms::default();
+        transforms.add_outgoing(Counter::default());
+        world.insert_resource(transforms);
+        world.insert_resource(ParallelForwarding::default().with_background(true));
+        world.init_resource::<ForwardingPipeline>();
+
+        let mut state = SystemState::<ServerTransforms>::new(&mut world);
+        let mut batch = messages();
+        state.get_mut(&mut world).apply_outgoing(&mut batch);
+        assert!(
+            batch.is_empty(),
+            "messages should be passed on the next call"
+        );
+
+        let mut batch = messages();
+        state.get_mut(&mut world).apply_outgoing(&mut batch);
+        let counters: Vec<_> = batch.iter().map(|(.., message)| message[0]).collect();
+        assert_eq!(counters, [0, 0, 1, 1, 2, 2, 3, 3]);
+
+        world.remove_resource::<ParallelForwarding>();
+        let mut batch = messages();
+        state.get_mut(&mut world).apply_outgoing(&mut batch);
+        let counters: Vec<_> = batch.iter().map(|(.., message)| message[0]).collect();
+        assert_eq!(
+            counters,
+            [4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11],
+            "messages in flight should be passed before the current ones"
+        );
+    }
+
+    #[test]
+    fn incoming_rejected() {
+        let mut transforms = MessageTransforms::default();
+        transforms.add_incoming(RejectOdd);
+
+        for parallel in [
+            None,
+            Some(ParallelForwarding::default().with_min_messages(0)),
+        ] {
+            let mut messages = messages();
+            apply_incoming(parallel.as_ref(), &transforms, &mut messages);
+
+            let values: Vec<_> = messages.iter().map(|(.., message)| message[0]).collect();
+            assert_eq!(values, [0, 0, 2, 2]);
+        }
+    }
+
+    /// Messages for 2 clients interleaved, each with its index within the client.
+    fn messages() -> Vec<(ClientId, u8, Bytes)> {
+        (0..8)
+            .map(|index| {
+                let client_id = ClientId::new(index % 2);
+                (client_id, 0, Bytes::from(vec![(index / 2) as u8]))
+            })
+            .collect()
+    }
+
+    /// Replaces each message with the number of messages previously sent to the same client.
+    #[derive(Default)]
+    struct Counter {
+        counts: Mutex<HashMap<ClientId, u8>>,
+    }
+
+    impl OutgoingTransform for Counter {
+        fn transform(&self, client_id: ClientId, _channel_id: u8, _message: Bytes) -> Bytes {
+            let mut counts = self.counts.lock().unwrap();
+            let count = counts.entry(client_id).or_default();
+            let message = Bytes::from(vec![*count]);
+            *count += 1;
+            message
+        }
+    }
+
+    struct RejectOdd;
+
+    impl IncomingTransform for RejectOdd {
+        fn transform(
+            &self,
+            _client_id: ClientId,
+            _channel_id: u8,
+            message: Bytes,
+        ) -> Option<Bytes> {
+            (message[0] & 1 == 0).then_some(message)
+        }
+    }
+}
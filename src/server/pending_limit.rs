@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+/// Maximum number of connections that haven't started replication yet.
+///
+/// Connections are pending from the moment renet accepts them until they appear in
+/// [`ReplicatedClients`](bevy_replicon::prelude::ReplicatedClients). This includes connections waiting
+/// for [`HandshakeMetadata`](crate::HandshakeMetadata) or [`AdmissionControl`](crate::AdmissionControl)
+/// and clients in [`ConnectedClients`](bevy_replicon::prelude::ConnectedClients) that haven't been authorized
+/// via [`StartReplication`](bevy_replicon::prelude::StartReplication) when
+/// [`ServerPlugin::replicate_after_connect`](bevy_replicon::prelude::ServerPlugin::replicate_after_connect)
+/// is disabled. With this resource, new connections beyond the limit are rejected with
+/// [`RejectReason::TooManyPending`](super::rejection::RejectReason::TooManyPending) until pending clients
+/// start replication or disconnect.
+///
+/// Protects a custom authorization from clients that connect, but never authorize. Such clients still
+/// occupy a slot until they disconnect, so combine it with [`IdleTimeout`](crate::IdleTimeout)
+/// or a custom timeout for authorization.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Deref, DerefMut, Debug, Clone, Copy)]
+pub struct MaxPendingConnections(pub usize);
@@ -0,0 +1,98 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use super::backpressure::SendBackpressure;
+use crate::{ChannelGroup, ChannelRemap};
+
+/// Backlog of server channels for each client, sampled each frame after sending.
+///
+/// Renet doesn't expose the number of queued messages, only the available memory of each channel.
+/// Memory is occupied by messages that were passed to renet, but not yet sent (for unreliable channels)
+/// or acknowledged (for reliable channels). Messages that didn't fit into a reliable channel are held back
+/// by [`SendBackpressure`] and counted separately. So decreasing available memory or growing number
+/// of held back messages over several frames means that the server produces messages faster than the
+/// connection can carry and game code should send less to the client.
+///
+/// Updated in [`ServerSet::SendPackets`] for clients from [`ConnectedClients`].
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ClientPendingMessages {
+    clients: HashMap<ClientId, Vec<Option<ChannelBacklog>>>,
+}
+
+impl ClientPendingMessages {
+    /// Returns the number of bytes that can be queued on the channel for the client before it's full.
+    ///
+    /// Returns [`None`] if the client wasn't sampled yet or the channel is outside of [`ChannelGroup`].
+    pub fn available_memory(&self, client_id: ClientId, channel_id: u8) -> Option<usize> {
+        self.get(client_id, channel_id)
+            .map(|backlog| backlog.available_memory)
+    }
+
+    /// Returns the number of messages held back by [`SendBackpressure`] for the client on the channel.
+    ///
+    /// Returns [`None`] if the client wasn't sampled yet or the channel is outside of [`ChannelGroup`].
+    pub fn held_back(&self, client_id: ClientId, channel_id: u8) -> Option<usize> {
+        self.get(client_id, channel_id)
+            .map(|backlog| backlog.held_back)
+    }
+
+    fn get(&self, client_id: ClientId, channel_id: u8) -> Option<&ChannelBacklog> {
+        self.clients
+            .get(&client_id)
+            .and_then(|channels| channels.get(channel_id as usize))
+            .and_then(Option::as_ref)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChannelBacklog {
+    available_memory: usize,
+    held_back: usize,
+}
+
+pub(super) struct PendingMessagesPlugin;
+
+impl Plugin for PendingMessagesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClientPendingMessages>().add_systems(
+            PostUpdate,
+            Self::sample
+                .after(super::RepliconRenetServerPlugin::send_packets)
+                .in_set(ServerSet::SendPackets)
+                .run_if(resource_exists::<RenetServer>),
+        );
+    }
+}
+
+impl PendingMessagesPlugin {
+    fn sample(
+        connected_clients: Res<ConnectedClients>,
+        channels: Res<RepliconChannels>,
+        remap: Res<ChannelRemap>,
+        group: Res<ChannelGroup>,
+        backpressure: Res<SendBackpressure>,
+        renet_server: Res<RenetServer>,
+        mut pending: ResMut<ClientPendingMessages>,
+    ) {
+        pending.clients.clear();
+        for client in connected_clients.iter() {
+            if !renet_server.is_connected(client.id().get()) {
+                continue;
+            }
+
+            let backlog = pending.clients.entry(client.id()).or_default();
+            for channel_id in 0..channels.server_channels().len() as u8 {
+                let channel_backlog = group.contains(channel_id).then(|| {
+                    let wire_id = remap.server_wire_id(channel_id);
+                    ChannelBacklog {
+                        available_memory: renet_server
+                            .channel_available_memory(client.id().get(), wire_id),
+                        held_back: backpressure.queued_messages(client.id(), wire_id),
+                    }
+                });
+                backlog.push(channel_backlog);
+            }
+        }
+    }
+}
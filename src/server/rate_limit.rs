@@ -0,0 +1,133 @@
+use std::{collections::VecDeque, net::IpAddr, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// Limits how many new connections the server accepts per second.
+///
+/// Connections above the limit are disconnected before reaching Replicon and reported via
+/// [`ClientRejected`](super::rejection::ClientRejected) with
+/// [`RejectReason::RateLimited`](super::rejection::RejectReason::RateLimited).
+///
+/// The per-IP limit requires the client address, which is currently only provided by the
+/// netcode transport. For other transports only the global limit is applied.
+#[derive(Resource, Debug, Clone)]
+pub struct ConnectionRateLimit {
+    max_per_second: usize,
+    max_per_ip: Option<usize>,
+    accepted: VecDeque<Duration>,
+    accepted_by_ip: HashMap<IpAddr, VecDeque<Duration>>,
+}
+
+impl ConnectionRateLimit {
+    /// Creates a limit for the total number of connections per second.
+    pub fn new(max_per_second: usize) -> Self {
+        Self {
+            max_per_second,
+            max_per_ip: None,
+            accepted: Default::default(),
+            accepted_by_ip: Default::default(),
+        }
+    }
+
+    /// Additionally limits the number of connections per second from a single IP address.
+    ///
+    /// IPv4-mapped IPv6 addresses are treated as their IPv4 counterparts.
+    #[must_use]
+    pub fn with_max_per_ip(mut self, max_per_ip: usize) -> Self {
+        self.max_per_ip = Some(max_per_ip);
+        self
+    }
+
+    /// Returns the maximum number of connections per second.
+    pub fn max_per_second(&self) -> usize {
+        self.max_per_second
+    }
+
+    /// Returns the maximum number of connections per second from a single IP address.
+    pub fn max_per_ip(&self) -> Option<usize> {
+        self.max_per_ip
+    }
+
+    /// Returns `true` and records the connection if it's within the limits.
+    ///
+    /// `now` is the elapsed time since app startup.
+    pub(crate) fn try_accept(&mut self, now: Duration, client_ip: Option<IpAddr>) -> bool {
+        remove_expired(&mut self.accepted, now);
+        self.accepted_by_ip.retain(|_, accepted| {
+            remove_expired(accepted, now);
+            !accepted.is_empty()
+        });
+
+        if self.accepted.len() >= self.max_per_second {
+            return false;
+        }
+
+        if let (Some(max_per_ip), Some(client_ip)) = (self.max_per_ip, client_ip) {
+            let accepted = self
+                .accepted_by_ip
+                .entry(client_ip.to_canonical())
+                .or_default();
+            if accepted.len() >= max_per_ip {
+                return false;
+            }
+            accepted.push_back(now);
+        }
+
+        self.accepted.push_back(now);
+
+        true
+    }
+}
+
+/// Removes connections that were accepted at least a second before `now`.
+fn remove_expired(accepted: &mut VecDeque<Duration>, now: Duration) {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    while accepted
+        .front()
+        .is_some_and(|&time| now.saturating_sub(time) >= WINDOW)
+    {
+        accepted.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    #[test]
+    fn window() {
+        let mut rate_limit = ConnectionRateLimit::new(2);
+
+        assert!(rate_limit.try_accept(Duration::ZERO, None));
+        assert!(rate_limit.try_accept(Duration::from_millis(500), None));
+        assert!(!rate_limit.try_accept(Duration::from_millis(900), None));
+        assert!(
+            rate_limit.try_accept(Duration::from_secs(1), None),
+            "first connection should leave the window"
+        );
+        assert!(!rate_limit.try_accept(Duration::from_secs(1), None));
+    }
+
+    #[test]
+    fn per_ip() {
+        let mut rate_limit = ConnectionRateLimit::new(3).with_max_per_ip(1);
+        let first_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+        let second_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 2));
+        let mapped_ip = IpAddr::V6(Ipv4Addr::new(192, 168, 0, 1).to_ipv6_mapped());
+        let now = Duration::ZERO;
+
+        assert!(rate_limit.try_accept(now, Some(first_ip)));
+        assert!(!rate_limit.try_accept(now, Some(first_ip)));
+        assert!(!rate_limit.try_accept(now, Some(mapped_ip)));
+        assert!(rate_limit.try_accept(now, Some(second_ip)));
+        assert!(rate_limit.try_accept(now, Some(IpAddr::V6(Ipv6Addr::LOCALHOST))));
+        assert!(
+            !rate_limit.try_accept(now, None),
+            "rejected connections shouldn't count toward the global limit"
+        );
+        assert!(rate_limit.try_accept(Duration::from_secs(1), Some(first_ip)));
+    }
+}
@@ -0,0 +1,63 @@
+use std::net::SocketAddr;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use crate::{
+    control::{self, ControlChannel, ControlKind},
+    redirect,
+};
+
+/// An event to tell a client to reconnect to another server, for example, during a zone handoff.
+///
+/// The address is sent over [`ControlChannel`] in [`ServerSet::SendPackets`]. The client then
+/// disconnects by itself and emits [`ClientRedirected`](crate::ClientRedirected), see its documentation
+/// for what is preserved. The connection is not closed by the server, so disconnect the client
+/// after a timeout if it doesn't leave.
+///
+/// Ignored if [`ControlChannel`] is not inserted or the client is not connected.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedirectClient {
+    pub client_id: ClientId,
+
+    /// Address of the server to connect to.
+    pub addr: SocketAddr,
+}
+
+pub(super) struct RedirectPlugin;
+
+impl Plugin for RedirectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RedirectClient>().add_systems(
+            PostUpdate,
+            Self::send
+                .before(super::RepliconRenetServerPlugin::send_packets)
+                .in_set(ServerSet::SendPackets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<ControlChannel>),
+        );
+    }
+}
+
+impl RedirectPlugin {
+    fn send(
+        channel: Res<ControlChannel>,
+        mut redirect_events: EventReader<RedirectClient>,
+        mut renet_server: ResMut<RenetServer>,
+    ) {
+        for event in redirect_events.read() {
+            if !renet_server.is_connected(event.client_id.get()) {
+                debug!("ignoring redirect for disconnected `{:?}`", event.client_id);
+                continue;
+            }
+
+            debug!("redirecting `{:?}` to {}", event.client_id, event.addr);
+            renet_server.send_message(
+                event.client_id.get(),
+                **channel,
+                control::encode(ControlKind::Redirect, &redirect::encode(event.addr)),
+            );
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::net::IpAddr;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+#[cfg(feature = "renet_netcode")]
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use super::{
+    capacity::MaxClients, maintenance::MaintenanceMode, pending_limit::MaxPendingConnections,
+    rate_limit::ConnectionRateLimit,
+};
+#[cfg(feature = "renet_netcode")]
+use super::{multi_socket::MultiSocketServerTransport, user_data_auth::AuthorizeByUserData};
+
+/// An event that indicates that a connection was refused by the server before being
+/// passed to Replicon.
+///
+/// Rejected clients are disconnected from [`RenetServer`](bevy_renet::renet::RenetServer)
+/// and never appear in [`ConnectedClients`], so Replicon won't emit
+/// [`ServerEvent`] for them.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientRejected {
+    pub client_id: ClientId,
+    pub reason: RejectReason,
+}
+
+/// Reason for [`ClientRejected`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// The server is in [`MaintenanceMode`].
+    Maintenance,
+    /// The connection exceeded [`ConnectionRateLimit`].
+    RateLimited,
+    /// The client sent a malformed handshake or it was rejected by a
+    /// [`HandshakeExtension`](crate::HandshakeExtension).
+    Handshake,
+    /// The client was rejected by `AuthorizeByUserData`, available with the `renet_netcode` feature.
+    ///
+    /// Contains the code returned by the callback or [`None`] if the client had no user data.
+    Unauthorized(Option<u16>),
+    /// The number of pending connections reached [`MaxPendingConnections`].
+    TooManyPending,
+    /// The number of connections reached [`MaxClients`].
+    Full,
+}
+
+/// Decides whether a newly connected client should be passed to Replicon.
+#[derive(SystemParam)]
+pub(super) struct ConnectionFilter<'w> {
+    time: Res<'w, Time<Real>>,
+    maintenance: Option<Res<'w, MaintenanceMode>>,
+    max_clients: Option<Res<'w, MaxClients>>,
+    max_pending: Option<Res<'w, MaxPendingConnections>>,
+    replicated_clients: Res<'w, ReplicatedClients>,
+    rate_limit: Option<ResMut<'w, ConnectionRateLimit>>,
+    #[cfg(feature = "renet_netcode")]
+    netcode_transport: Option<Res<'w, NetcodeServerTransport>>,
+    #[cfg(feature = "renet_netcode")]
+    multi_transport: Option<Res<'w, MultiSocketServerTransport>>,
+    #[cfg(feature = "renet_netcode")]
+    authorization: Option<Res<'w, AuthorizeByUserData>>,
+}
+
+impl ConnectionFilter<'_> {
+    /// Returns the reason to reject the client or [`None`] if it's accepted.
+    pub(super) fn check(
+        &mut self,
+        client_id: u64,
+        renet_server: Option<&RenetServer>,
+    ) -> Option<RejectReason> {
+        if self.maintenance.is_some() {
+            return Some(RejectReason::Maintenance);
+        }
+
+        if let (Some(max_clients), Some(renet_server)) = (&self.max_clients, renet_server) {
+            if other_connections(client_id, renet_server).count() >= max_clients.0 {
+                return Some(RejectReason::Full);
+            }
+        }
+
+        if let (Some(max_pending), Some(renet_server)) = (&self.max_pending, renet_server) {
+            if self.pending_count(client_id, renet_server) >= max_pending.0 {
+                return Some(RejectReason::TooManyPending);
+            }
+        }
+
+        let client_ip = self.client_ip(client_id);
+        if let Some(rate_limit) = &mut self.rate_limit {
+            if !rate_limit.try_accept(self.time.elapsed(), client_ip) {
+                return Some(RejectReason::RateLimited);
+            }
+        }
+
+        #[cfg(feature = "renet_netcode")]
+        if let Some(authorization) = &self.authorization {
+            let user_data = self
+                .netcode_transport
+                .as_ref()
+                .and_then(|transport| transport.user_data(client_id))
+                .or_else(|| {
+                    self.multi_transport
+                        .as_ref()
+                        .and_then(|transport| transport.user_data(client_id))
+                });
+            if let Err(code) = authorization.authorize(ClientId::new(client_id), user_data) {
+                return Some(RejectReason::Unauthorized(code));
+            }
+        }
+
+        None
+    }
+
+    /// Returns the number of connected clients that haven't started replication, excluding the checked one.
+    fn pending_count(&self, client_id: u64, renet_server: &RenetServer) -> usize {
+        other_connections(client_id, renet_server)
+            .filter(|&id| {
+                self.replicated_clients
+                    .get_client(ClientId::new(id))
+                    .is_none()
+            })
+            .count()
+    }
+
+    /// Returns the client IP address if the transport provides it.
+    #[cfg(feature = "renet_netcode")]
+    fn client_ip(&self, client_id: u64) -> Option<IpAddr> {
+        self.netcode_transport
+            .as_ref()
+            .and_then(|transport| transport.client_addr(client_id))
+            .or_else(|| {
+                self.multi_transport
+                    .as_ref()
+                    .and_then(|transport| transport.client_addr(client_id))
+            })
+            .map(|addr| addr.ip())
+    }
+
+    #[cfg(not(feature = "renet_netcode"))]
+    fn client_ip(&self, _client_id: u64) -> Option<IpAddr> {
+        None
+    }
+}
+
+/// Iterates over connected clients, excluding the checked one.
+///
+/// Rejected clients are already disconnected in renet, so they aren't included.
+fn other_connections(client_id: u64, renet_server: &RenetServer) -> impl Iterator<Item = u64> + '_ {
+    renet_server
+        .clients_id_iter()
+        .filter(move |&id| id != client_id && renet_server.is_connected(id))
+}
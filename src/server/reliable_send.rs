@@ -0,0 +1,320 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use super::control::{ControlMessages, ControlPlugin};
+use crate::{
+    control::ControlKind,
+    reliable_channel::{self, ReliableSendChannel},
+};
+
+/// Sends messages over [`ReliableSendChannel`] and tracks their acknowledgement by clients.
+///
+/// The client receives messages as [`ReliableMessage`](crate::ReliableMessage) events
+/// and automatically replies with an acknowledgement over [`ControlChannel`](crate::ControlChannel), which is reported on the server as [`MessageAcked`].
+/// Messages to clients that disconnected before acknowledging are dropped from tracking.
+///
+/// To poll the delivery status instead of reading events, send via [`Self::send_tracked`].
+#[derive(SystemParam)]
+pub struct ReliableSend<'w> {
+    channel: Res<'w, ReliableSendChannel>,
+    state: ResMut<'w, ReliableSendState>,
+    renet_server: ResMut<'w, RenetServer>,
+}
+
+impl ReliableSend<'_> {
+    /// Sends a message to the client and returns its sequence number.
+    ///
+    /// The returned number will be reported in [`MessageAcked`] once the client receives the message.
+    pub fn send(&mut self, client_id: ClientId, message: impl AsRef<[u8]>) -> u64 {
+        let sequence = self.state.next_sequence;
+        self.state.next_sequence += 1;
+
+        self.renet_server.send_message(
+            client_id.get(),
+            **self.channel,
+            reliable_channel::encode(sequence, message.as_ref()),
+        );
+        self.state.pending.insert((client_id, sequence));
+
+        sequence
+    }
+
+    /// Like [`Self::send`], but returns a handle to poll the delivery status via [`Self::poll`].
+    ///
+    /// Useful for request-acknowledge patterns when game logic needs to know that a critical
+    /// command landed, not just that it was queued. The status is stored until it's polled after
+    /// the resolution, so poll each handle until it returns [`MessageStatus::Acked`] or [`MessageStatus::Lost`].
+    /// Each message carries an 8-byte sequence number and the client replies with an acknowledgement,
+    /// so use it sparingly.
+    pub fn send_tracked(
+        &mut self,
+        client_id: ClientId,
+        message: impl AsRef<[u8]>,
+    ) -> MessageHandle {
+        let sequence = self.send(client_id, message);
+        let handle = MessageHandle {
+            client_id,
+            sequence,
+        };
+        self.state.tracked.insert(handle, MessageStatus::Queued);
+
+        handle
+    }
+
+    /// Returns the delivery status of a message sent via [`Self::send_tracked`].
+    ///
+    /// Once the message is acknowledged or lost, the status is returned one last time and the handle is forgotten.
+    /// Returns [`None`] for forgotten handles and after the server stops.
+    pub fn poll(&mut self, handle: MessageHandle) -> Option<MessageStatus> {
+        let status = *self.state.tracked.get(&handle)?;
+        if status != MessageStatus::Queued {
+            self.state.tracked.remove(&handle);
+        }
+
+        Some(status)
+    }
+
+    /// Returns `true` if the message was sent, but not acknowledged yet.
+    pub fn is_pending(&self, client_id: ClientId, sequence: u64) -> bool {
+        self.state.pending.contains(&(client_id, sequence))
+    }
+}
+
+/// An event that indicates that a message sent via [`ReliableSend`] was received by the client.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageAcked {
+    pub client_id: ClientId,
+    pub sequence: u64,
+}
+
+/// Identifies a message sent via [`ReliableSend::send_tracked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MessageHandle {
+    client_id: ClientId,
+    sequence: u64,
+}
+
+impl MessageHandle {
+    /// Returns the client the message was sent to.
+    pub fn client_id(&self) -> ClientId {
+        self.client_id
+    }
+
+    /// Returns the sequence number of the message, the same as reported in [`MessageAcked`].
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+/// Delivery status of a message sent via [`ReliableSend::send_tracked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageStatus {
+    /// The message was passed to renet, but not acknowledged yet.
+    Queued,
+    /// The client received the message.
+    Acked,
+    /// The client disconnected before acknowledging the message.
+    ///
+    /// The message may still have been received if the acknowledgement was lost.
+    Lost,
+}
+
+/// Tracks messages sent via [`ReliableSend`].
+#[derive(Resource, Default)]
+pub struct ReliableSendState {
+    next_sequence: u64,
+    pending: HashSet<(ClientId, u64)>,
+    tracked: HashMap<MessageHandle, MessageStatus>,
+}
+
+impl ReliableSendState {
+    /// Updates the status of the message if it's tracked.
+    fn resolve(&mut self, client_id: ClientId, sequence: u64, status: MessageStatus) {
+        let handle = MessageHandle {
+            client_id,
+            sequence,
+        };
+        if let Some(tracked_status) = self.tracked.get_mut(&handle) {
+            *tracked_status = status;
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.pending.clear();
+        self.tracked.clear();
+    }
+}
+
+pub(super) struct ReliableSendPlugin;
+
+impl Plugin for ReliableSendPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReliableSendState>()
+            .add_event::<MessageAcked>()
+            .add_systems(
+                PreUpdate,
+                Self::receive_acks
+                    .after(ControlPlugin::receive)
+                    .in_set(ServerSet::ReceivePackets)
+                    .run_if(resource_exists::<RenetServer>)
+                    .run_if(resource_exists::<ReliableSendChannel>),
+            );
+    }
+}
+
+impl ReliableSendPlugin {
+    fn receive_acks(
+        renet_server: Res<RenetServer>,
+        mut messages: ResMut<ControlMessages>,
+        mut state: ResMut<ReliableSendState>,
+        mut acked_events: EventWriter<MessageAcked>,
+    ) {
+        for (client_id, message) in messages.take(ControlKind::Ack) {
+            let Some(sequence) = reliable_channel::decode_ack(&message) else {
+                debug!("ignoring invalid acknowledgement from `{client_id:?}`");
+                continue;
+            };
+
+            if state.pending.remove(&(client_id, sequence)) {
+                trace!("`{client_id:?}` acknowledged message {sequence}");
+                state.resolve(client_id, sequence, MessageStatus::Acked);
+                acked_events.send(MessageAcked {
+                    client_id,
+                    sequence,
+                });
+            }
+        }
+
+        let mut lost = Vec::new();
+        state.pending.retain(|&(client_id, sequence)| {
+            let connected = renet_server.is_connected(client_id.get());
+            if !connected {
+                lost.push((client_id, sequence));
+            }
+            connected
+        });
+        for (client_id, sequence) in lost {
+            state.resolve(client_id, sequence, MessageStatus::Lost);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+    use bevy_renet::renet::{ChannelConfig, ConnectionConfig, RenetClient, SendType};
+
+    use super::*;
+    use crate::control::{self, ControlChannel};
+
+    #[test]
+    fn acknowledgement() {
+        let mut app = create_app();
+        let mut client = app
+            .world_mut()
+            .resource_mut::<RenetServer>()
+            .new_local_client(CLIENT_ID.get());
+
+        let mut reliable_state = SystemState::<ReliableSend>::new(app.world_mut());
+        let sequence = reliable_state
+            .get_mut(app.world_mut())
+            .send(CLIENT_ID, [1, 2]);
+        assert!(reliable_state
+            .get_mut(app.world_mut())
+            .is_pending(CLIENT_ID, sequence));
+
+        exchange_packets(&mut app, &mut client);
+        let message = client
+            .receive_message(CHANNEL_ID)
+            .expect("client should receive the message");
+        let (received_sequence, payload) =
+            reliable_channel::decode(message).expect("message should be tagged");
+        assert_eq!(received_sequence, sequence);
+        assert_eq!(payload.as_ref(), [1, 2]);
+
+        client.send_message(
+            CONTROL_CHANNEL_ID,
+            control::encode(ControlKind::Ack, &received_sequence.to_le_bytes()),
+        );
+        exchange_packets(&mut app, &mut client);
+        app.update();
+
+        assert!(!reliable_state
+            .get_mut(app.world_mut())
+            .is_pending(CLIENT_ID, sequence));
+        let acked_events = app.world().resource::<Events<MessageAcked>>();
+        let mut reader = acked_events.get_cursor();
+        let acked: Vec<_> = reader.read(acked_events).copied().collect();
+        assert_eq!(
+            acked,
+            [MessageAcked {
+                client_id: CLIENT_ID,
+                sequence
+            }]
+        );
+    }
+
+    #[test]
+    fn lost_on_disconnect() {
+        let mut app = create_app();
+        let mut client = app
+            .world_mut()
+            .resource_mut::<RenetServer>()
+            .new_local_client(CLIENT_ID.get());
+
+        let mut reliable_state = SystemState::<ReliableSend>::new(app.world_mut());
+        let sequence = reliable_state.get_mut(app.world_mut()).send(CLIENT_ID, [0]);
+
+        app.world_mut()
+            .resource_mut::<RenetServer>()
+            .disconnect_local_client(CLIENT_ID.get(), &mut client);
+        app.update();
+
+        assert!(!reliable_state
+            .get_mut(app.world_mut())
+            .is_pending(CLIENT_ID, sequence));
+        assert!(app.world().resource::<Events<MessageAcked>>().is_empty());
+    }
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    const CHANNEL_ID: u8 = 0;
+    const CONTROL_CHANNEL_ID: u8 = 1;
+
+    fn create_app() -> App {
+        let channels_config = vec![
+            ChannelConfig {
+                channel_id: CHANNEL_ID,
+                max_memory_usage_bytes: 1024,
+                send_type: SendType::ReliableOrdered {
+                    resend_time: Default::default(),
+                },
+            },
+            ControlChannel(CONTROL_CHANNEL_ID).config(),
+        ];
+        let renet_server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels_config.clone(),
+            client_channels_config: channels_config,
+            ..Default::default()
+        });
+
+        let mut app = App::new();
+        app.add_plugins((ControlPlugin, ReliableSendPlugin))
+            .insert_resource(renet_server)
+            .insert_resource(ReliableSendChannel(CHANNEL_ID))
+            .insert_resource(ControlChannel(CONTROL_CHANNEL_ID));
+
+        app
+    }
+
+    fn exchange_packets(app: &mut App, client: &mut RenetClient) {
+        app.world_mut()
+            .resource_mut::<RenetServer>()
+            .process_local_client(CLIENT_ID.get(), client)
+            .expect("local client should be connected");
+    }
+}
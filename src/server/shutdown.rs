@@ -0,0 +1,254 @@
+use std::{mem, time::Duration};
+
+use bevy::prelude::*;
+#[cfg(feature = "renet_netcode")]
+use bevy_renet::netcode::NetcodeServerPlugin;
+use bevy_renet::renet::{Bytes, RenetServer};
+#[cfg(feature = "renet_steam")]
+use bevy_renet::steam::SteamServerPlugin;
+use bevy_replicon::prelude::*;
+
+use super::maintenance::MaintenanceMode;
+#[cfg(feature = "renet_netcode")]
+use super::multi_socket::MultiSocketPlugin;
+
+/// Delays [`AppExit`] to shut the server down gracefully.
+///
+/// When [`AppExit`] is sent while [`RenetServer`] exists, the event is held and the server:
+///
+/// 1. Inserts [`MaintenanceMode`], so new connections are rejected.
+/// 2. Broadcasts the message from [`Self::with_message`] to all connected clients, if set.
+/// 3. Waits for [`Self::grace_period`] or until all clients disconnect, whichever comes first.
+/// 4. Disconnects remaining clients via [`RenetServer::disconnect_all`], emits [`ShutdownReport`]
+///    and waits a frame, so the transport sends disconnect packets.
+/// 5. Sends the held [`AppExit`] again, so the app exits with the original exit code.
+///
+/// Bevy doesn't exit on signals by default. To shut down on a signal, set a flag from the signal handler
+/// and send [`AppExit`] from a system once the flag is set.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Debug, Clone)]
+pub struct GracefulShutdown {
+    /// Maximum time to wait for clients to leave before disconnecting them.
+    pub grace_period: Duration,
+
+    message: Option<(u8, Bytes)>,
+}
+
+impl GracefulShutdown {
+    /// Creates a shutdown without a message.
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            grace_period,
+            message: None,
+        }
+    }
+
+    /// Broadcasts a message, like "server shutting down", when the shutdown starts.
+    ///
+    /// The message is passed to renet as is, so the channel is not managed by Replicon.
+    /// See [`ServerMessaging`](crate::ServerMessaging) for how to configure custom channels.
+    #[must_use]
+    pub fn with_message(mut self, channel_id: u8, message: impl Into<Bytes>) -> Self {
+        self.message = Some((channel_id, message.into()));
+        self
+    }
+}
+
+/// An event with the summary of [`GracefulShutdown`].
+///
+/// Emitted in [`Last`] when remaining clients are disconnected, a frame before the app exits,
+/// so it can be read by systems in any schedule of the last frame. The report is also logged.
+///
+/// Not emitted if [`RenetServer`] is removed during the grace period.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Number of clients that were still connected when the grace period ended and had to be disconnected.
+    pub clients_disconnected: usize,
+
+    /// Number of messages passed to renet since the shutdown started, including the broadcasted message.
+    pub messages_flushed: usize,
+
+    /// Time from the start of the shutdown until remaining clients were disconnected.
+    pub duration: Duration,
+}
+
+impl ShutdownReport {
+    /// Returns `true` if all clients left during the grace period.
+    ///
+    /// Clients that had to be disconnected may not have received messages that were still
+    /// in flight at this moment.
+    pub fn is_clean(&self) -> bool {
+        self.clients_disconnected == 0
+    }
+}
+
+pub(super) struct ShutdownPlugin;
+
+impl Plugin for ShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        let hold_exit = Self::hold_exit
+            .run_if(resource_exists::<GracefulShutdown>)
+            .run_if(resource_exists::<RenetServer>);
+        #[cfg(feature = "renet_netcode")]
+        let hold_exit = hold_exit
+            .before(NetcodeServerPlugin::disconnect_on_exit)
+            .before(MultiSocketPlugin::disconnect_on_exit);
+        #[cfg(feature = "renet_steam")]
+        let hold_exit = hold_exit.before(SteamServerPlugin::disconnect_on_exit);
+
+        app.add_event::<ShutdownReport>()
+            .init_resource::<ShutdownStage>()
+            .add_systems(
+                Last,
+                // Progress is checked before holding, so the grace period starts on the next frame
+                // and the broadcasted message is sent before clients are disconnected.
+                (Self::progress, hold_exit).chain(),
+            );
+    }
+}
+
+impl ShutdownPlugin {
+    fn hold_exit(
+        mut commands: Commands,
+        time: Res<Time<Real>>,
+        shutdown: Res<GracefulShutdown>,
+        connected_clients: Res<ConnectedClients>,
+        mut exit_events: ResMut<Events<AppExit>>,
+        mut stage: ResMut<ShutdownStage>,
+        mut server: ResMut<RenetServer>,
+    ) {
+        if exit_events.is_empty() || matches!(*stage, ShutdownStage::Exiting) {
+            return;
+        }
+
+        let exit = exit_events
+            .drain()
+            .find(AppExit::is_error)
+            .unwrap_or(AppExit::Success);
+
+        match &mut *stage {
+            ShutdownStage::Running => {
+                info!(
+                    "shutting down gracefully within {:?}",
+                    shutdown.grace_period
+                );
+                commands.insert_resource(MaintenanceMode);
+                let mut messages_flushed = 0;
+                if let Some((channel_id, message)) = &shutdown.message {
+                    for client in connected_clients.iter() {
+                        server.send_message(client.id().get(), *channel_id, message.clone());
+                        messages_flushed += 1;
+                    }
+                }
+                *stage = ShutdownStage::Grace {
+                    started_at: time.elapsed(),
+                    deadline: time.elapsed() + shutdown.grace_period,
+                    messages_flushed,
+                    exit,
+                };
+            }
+            ShutdownStage::Grace { exit: held, .. } | ShutdownStage::Disconnecting(held) => {
+                if exit.is_error() && held.is_success() {
+                    *held = exit;
+                }
+            }
+            ShutdownStage::Exiting => unreachable!("exit events shouldn't be held while exiting"),
+        }
+    }
+
+    fn progress(
+        time: Res<Time<Real>>,
+        connected_clients: Res<ConnectedClients>,
+        mut exit_events: EventWriter<AppExit>,
+        mut report_events: EventWriter<ShutdownReport>,
+        mut stage: ResMut<ShutdownStage>,
+        server: Option<ResMut<RenetServer>>,
+    ) {
+        match mem::take(&mut *stage) {
+            ShutdownStage::Running => (),
+            ShutdownStage::Grace {
+                started_at,
+                deadline,
+                messages_flushed,
+                exit,
+            } => {
+                let Some(mut server) = server else {
+                    exit_events.send(exit);
+                    *stage = ShutdownStage::Exiting;
+                    return;
+                };
+
+                if time.elapsed() >= deadline || connected_clients.is_empty() {
+                    debug!(
+                        "disconnecting {} remaining clients",
+                        connected_clients.len()
+                    );
+                    server.disconnect_all();
+
+                    let report = ShutdownReport {
+                        clients_disconnected: connected_clients.len(),
+                        messages_flushed,
+                        duration: time.elapsed() - started_at,
+                    };
+                    if report.is_clean() {
+                        info!(
+                            "all clients left, flushed {messages_flushed} messages in {:?}",
+                            report.duration
+                        );
+                    } else {
+                        warn!(
+                            "disconnected {} remaining clients, flushed {messages_flushed} messages in {:?}",
+                            report.clients_disconnected, report.duration
+                        );
+                    }
+                    report_events.send(report);
+
+                    *stage = ShutdownStage::Disconnecting(exit);
+                } else {
+                    *stage = ShutdownStage::Grace {
+                        started_at,
+                        deadline,
+                        messages_flushed,
+                        exit,
+                    };
+                }
+            }
+            ShutdownStage::Disconnecting(exit) => {
+                exit_events.send(exit);
+                *stage = ShutdownStage::Exiting;
+            }
+            ShutdownStage::Exiting => *stage = ShutdownStage::Exiting,
+        }
+    }
+}
+
+/// Progress of [`GracefulShutdown`].
+#[derive(Resource, Default)]
+pub(super) enum ShutdownStage {
+    #[default]
+    Running,
+    Grace {
+        /// [`Time<Real>::elapsed`] at which the shutdown started.
+        started_at: Duration,
+        /// [`Time<Real>::elapsed`] at which remaining clients are disconnected.
+        deadline: Duration,
+        /// Messages passed to renet since the shutdown started.
+        messages_flushed: usize,
+        exit: AppExit,
+    },
+    Disconnecting(AppExit),
+    Exiting,
+}
+
+impl ShutdownStage {
+    /// Counts messages passed to renet during the grace period.
+    pub(super) fn record_flushed(&mut self, count: usize) {
+        if let ShutdownStage::Grace {
+            messages_flushed, ..
+        } = self
+        {
+            *messages_flushed += count;
+        }
+    }
+}
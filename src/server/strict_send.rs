@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// Marker resource that makes the server panic on messages to disconnected clients.
+///
+/// A client may disconnect in the middle of a frame after Replicon has already queued
+/// messages for it. By default such messages are skipped with a debug log. Insert this resource
+/// to panic instead, which is useful in tests to catch messages that are sent
+/// to clients that were never connected.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct StrictSend;
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::{core::replicon_tick::RepliconTick, prelude::*};
+
+use crate::timestamp::{self, MessageTimestamps};
+
+/// Tags of messages received from clients over channels from [`MessageTimestamps`] during this frame.
+///
+/// Updated in [`ServerSet::ReceivePackets`] and cleared at the beginning of the next update. Replicon
+/// deserializes each client event from a separate message and keeps their order, so the N-th timestamp
+/// of a channel belongs to the N-th event read from it during this frame, like with
+/// [`EventReader<FromClient<E>>`](FromClient).
+///
+/// With [`JitterBuffers`](crate::JitterBuffers), timestamps of buffered messages are recorded when
+/// the messages are released to Replicon.
+#[derive(Resource, Default, Debug)]
+pub struct ReceivedTimestamps(HashMap<(ClientId, u8), Vec<MessageTimestamp>>);
+
+impl ReceivedTimestamps {
+    /// Returns timestamps of messages received from the client over the channel during this frame.
+    pub fn get(&self, client_id: ClientId, channel_id: impl Into<u8>) -> &[MessageTimestamp] {
+        self.0
+            .get(&(client_id, channel_id.into()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn push(&mut self, client_id: ClientId, channel_id: u8, timestamp: MessageTimestamp) {
+        self.0
+            .entry((client_id, channel_id))
+            .or_default()
+            .push(timestamp);
+    }
+}
+
+/// Tag of a single message from [`ReceivedTimestamps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageTimestamp {
+    /// Estimated server time at which the client sent the message.
+    ///
+    /// [`None`] if the client had no estimate yet. See [`MessageTimestamps`] for details.
+    pub sent_at: Option<Duration>,
+
+    /// Last server tick the client received before sending the message.
+    pub tick: RepliconTick,
+
+    /// [`Time<Real>::elapsed`] at which the message was passed to Replicon.
+    pub received_at: Duration,
+}
+
+/// Strips tags from received messages and records them.
+#[derive(SystemParam)]
+pub(super) struct TimestampReader<'w> {
+    time: Res<'w, Time<Real>>,
+    timestamps: Option<Res<'w, MessageTimestamps>>,
+    received: ResMut<'w, ReceivedTimestamps>,
+}
+
+impl TimestampReader<'_> {
+    pub(super) fn clear(&mut self) {
+        if !self.received.0.is_empty() {
+            self.received.0.clear();
+        }
+    }
+
+    /// Returns the message without the tag or [`None`] if the message should be tagged, but isn't.
+    ///
+    /// Messages over channels without timestamps are returned as is.
+    pub(super) fn read(
+        &mut self,
+        client_id: ClientId,
+        channel_id: u8,
+        message: Bytes,
+    ) -> Option<Bytes> {
+        if !self
+            .timestamps
+            .as_ref()
+            .is_some_and(|timestamps| timestamps.contains(channel_id))
+        {
+            return Some(message);
+        }
+
+        let Some((sent_at, tick, message)) = timestamp::decode(message) else {
+            debug!("discarding message from `{client_id:?}` without a timestamp");
+            return None;
+        };
+
+        self.received.push(
+            client_id,
+            channel_id,
+            MessageTimestamp {
+                sent_at,
+                tick,
+                received_at: self.time.elapsed(),
+            },
+        );
+
+        Some(message)
+    }
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy_renet::netcode::{NetcodeClientTransport, NetcodeServerTransport, NetcodeTransportError};
+use bevy_replicon::prelude::*;
+
+use super::multi_socket::MultiSocketServerTransport;
+use crate::transport_error;
+
+/// An event that indicates that [`NetcodeServerTransport`] or [`MultiSocketServerTransport`] failed to update.
+///
+/// Emitted in [`ServerSet::ReceivePackets`] for each fatal error reported by the transport, for example,
+/// when the socket can no longer receive because the network interface went down. Renet keeps
+/// running after such errors, but clients may stop receiving anything, so use this event to alert
+/// and recover.
+///
+/// Transient errors are logged at the debug level and not forwarded. The transport stops receiving
+/// for the current frame on any error and continues in the next one, so they are retried automatically.
+/// Renet already skips [`WouldBlock`](std::io::ErrorKind::WouldBlock), [`Interrupted`](std::io::ErrorKind::Interrupted)
+/// and [`ConnectionReset`](std::io::ErrorKind::ConnectionReset) on its own. Additionally, IO errors
+/// of the following kinds are considered transient:
+///
+/// - [`TimedOut`](std::io::ErrorKind::TimedOut).
+/// - [`ConnectionRefused`](std::io::ErrorKind::ConnectionRefused) and [`ConnectionAborted`](std::io::ErrorKind::ConnectionAborted),
+///   which some platforms report on a UDP socket after an ICMP message from an unreachable client.
+///
+/// All other IO errors and all netcode and renet errors are fatal.
+///
+/// To recover, remove [`RenetServer`](bevy_renet::renet::RenetServer) and [`NetcodeServerTransport`],
+/// bind a new socket and insert a newly created server and transport. All clients will be
+/// disconnected and will need to reconnect.
+///
+/// Renet reports client and server transport errors with the same event, so errors are
+/// forwarded only if there is no [`NetcodeClientTransport`] in the world. Otherwise they are
+/// reported as [`ClientTransportError`](crate::ClientTransportError).
+///
+/// Only available with the `renet_netcode` feature.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct ServerTransportError {
+    pub reason: String,
+}
+
+pub(super) struct TransportErrorPlugin;
+
+impl Plugin for TransportErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ServerTransportError>().add_systems(
+            PreUpdate,
+            Self::forward_errors
+                .in_set(ServerSet::ReceivePackets)
+                .run_if(
+                    resource_exists::<NetcodeServerTransport>
+                        .or(resource_exists::<MultiSocketServerTransport>),
+                )
+                .run_if(not(resource_exists::<NetcodeClientTransport>)),
+        );
+    }
+}
+
+impl TransportErrorPlugin {
+    fn forward_errors(
+        mut transport_errors: EventReader<NetcodeTransportError>,
+        mut server_errors: EventWriter<ServerTransportError>,
+    ) {
+        for error in transport_errors.read() {
+            if transport_error::is_transient(error) {
+                debug!("ignoring transient server transport error: {error}");
+                continue;
+            }
+
+            error!("server transport failed: {error}");
+            server_errors.send(ServerTransportError {
+                reason: error.to_string(),
+            });
+        }
+    }
+}
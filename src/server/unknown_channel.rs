@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use bevy_replicon::prelude::*;
+
+use crate::{
+    transport::ChannelSide,
+    unknown_channel::{UnknownChannelAction, UnknownChannels},
+    UnknownChannelPolicy,
+};
+
+pub(super) struct UnknownChannelPlugin;
+
+impl Plugin for UnknownChannelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::drain
+                .after(super::RepliconRenetServerPlugin::receive_packets)
+                .in_set(ServerSet::ReceivePackets)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<UnknownChannelPolicy>),
+        );
+    }
+}
+
+impl UnknownChannelPlugin {
+    fn drain(unknown_channels: UnknownChannels, mut renet_server: ResMut<RenetServer>) {
+        for client_id in renet_server.clients_id() {
+            let count = unknown_channels.drain(&mut *renet_server, client_id, ChannelSide::Client);
+            if count == 0 {
+                continue;
+            }
+
+            match unknown_channels.action() {
+                UnknownChannelAction::Discard => {
+                    warn!("discarding {count} messages from client `{client_id}` over unknown channels");
+                }
+                UnknownChannelAction::Disconnect => {
+                    warn!("disconnecting client `{client_id}` for sending {count} messages over unknown channels");
+                    renet_server.disconnect(client_id);
+                }
+            }
+        }
+    }
+}
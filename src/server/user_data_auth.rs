@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy_renet::netcode::NETCODE_USER_DATA_BYTES;
+use bevy_replicon::prelude::*;
+
+/// Authorizes connecting clients by their netcode user data.
+///
+/// Called once for each client that connects via
+/// [`NetcodeServerTransport`](bevy_renet::netcode::NetcodeServerTransport) with the user data from its
+/// [`ClientAuthentication`](bevy_renet::netcode::ClientAuthentication). Return [`Err`] with an app-specific
+/// code to reject the client. Rejected clients are disconnected and reported via
+/// [`ClientRejected`](crate::ClientRejected) with [`RejectReason::Unauthorized`](crate::RejectReason::Unauthorized)
+/// before they are passed to Replicon, so they never appear in [`ConnectedClients`] and game code doesn't
+/// receive [`ServerEvent::ClientConnected`] for them. Clients without user data, like local clients, are rejected.
+///
+/// User data can be trusted only with [`ServerAuthentication::Secure`](bevy_renet::netcode::ServerAuthentication::Secure).
+/// In this mode it's a part of the connect token, which is encrypted with the private key shared between
+/// the server and the backend that issues tokens, so clients can't modify it. With
+/// [`ServerAuthentication::Unsecure`](bevy_renet::netcode::ServerAuthentication::Unsecure) clients
+/// send arbitrary user data, so a token inside it needs to be verified by the callback itself, for example,
+/// by checking its signature.
+///
+/// Only available with the `renet_netcode` feature.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::AuthorizeByUserData;
+///
+/// const INVALID_TOKEN: u16 = 1;
+///
+/// # let mut app = App::new();
+/// app.insert_resource(AuthorizeByUserData::new(|_client_id, user_data| {
+///     if user_data.starts_with(b"secret") {
+///         Ok(())
+///     } else {
+///         Err(INVALID_TOKEN)
+///     }
+/// }));
+/// ```
+#[derive(Resource)]
+#[allow(clippy::type_complexity)]
+pub struct AuthorizeByUserData(
+    Box<dyn Fn(ClientId, &[u8; NETCODE_USER_DATA_BYTES]) -> Result<(), u16> + Send + Sync>,
+);
+
+impl AuthorizeByUserData {
+    /// Creates an authorization from a function.
+    pub fn new(
+        authorize: impl Fn(ClientId, &[u8; NETCODE_USER_DATA_BYTES]) -> Result<(), u16>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self(Box::new(authorize))
+    }
+
+    /// Returns the rejection code if the client is not authorized.
+    pub(super) fn authorize(
+        &self,
+        client_id: ClientId,
+        user_data: Option<[u8; NETCODE_USER_DATA_BYTES]>,
+    ) -> Result<(), Option<u16>> {
+        let Some(user_data) = user_data else {
+            return Err(None);
+        };
+
+        (self.0)(client_id, &user_data).map_err(Some)
+    }
+}
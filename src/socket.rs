@@ -0,0 +1,88 @@
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+};
+
+use bevy::prelude::*;
+use socket2::{Domain, Protocol, Socket, Type};
+
+/// Binds a UDP socket on all interfaces that accepts both IPv6 and IPv4 connections.
+///
+/// Creates an IPv6 socket on [`Ipv6Addr::UNSPECIFIED`] and explicitly disables `IPV6_V6ONLY`,
+/// so IPv4 clients are accepted as IPv4-mapped addresses (`::ffff:a.b.c.d`).
+/// The default value of this option differs between platforms (enabled on Windows and
+/// most BSDs, disabled on Linux), so relying on [`UdpSocket::bind`] is not enough.
+///
+/// Falls back to an IPv4-only socket on [`Ipv4Addr::UNSPECIFIED`] if IPv6 is unavailable
+/// or the platform doesn't support dual-stack sockets (for example, OpenBSD).
+///
+/// The returned socket can be passed directly into
+/// [`NetcodeServerTransport::new`](bevy_renet::netcode::NetcodeServerTransport::new).
+pub fn bind_dual_stack(port: u16) -> io::Result<UdpSocket> {
+    match bind_ipv6_any(port) {
+        Ok(socket) => Ok(socket),
+        Err(e) => {
+            warn!("unable to bind dual-stack socket, falling back to IPv4: {e}");
+            UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))
+        }
+    }
+}
+
+/// Binds a UDP socket on a random port that can reach the given server address.
+///
+/// The socket is bound to the unspecified address of the same family as `server_addr`.
+/// An IPv4-bound socket can't send packets to an IPv6 address, so this should be used instead
+/// of hardcoding [`Ipv4Addr::UNSPECIFIED`] when the server address comes from the user.
+pub fn bind_client_socket(server_addr: SocketAddr) -> io::Result<UdpSocket> {
+    match server_addr {
+        SocketAddr::V4(_) => UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0)),
+    }
+}
+
+fn bind_ipv6_any(port: u16) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_only_v6(false)?;
+    let addr = SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), port);
+    socket.bind(&addr.into())?;
+
+    Ok(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn dual_stack() {
+        let server_socket = bind_dual_stack(0).unwrap();
+        server_socket
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let server_addr = server_socket.local_addr().unwrap();
+        assert!(server_addr.ip().is_unspecified());
+
+        let ipv4_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), server_addr.port());
+        let client_socket = bind_client_socket(ipv4_addr).unwrap();
+        assert!(client_socket.local_addr().unwrap().is_ipv4());
+
+        client_socket.send_to(&[1], ipv4_addr).unwrap();
+        let mut buffer = [0; 1];
+        let (len, client_addr) = server_socket.recv_from(&mut buffer).unwrap();
+        assert_eq!(buffer[..len], [1]);
+        assert_eq!(
+            client_addr.is_ipv6(),
+            server_addr.is_ipv6(),
+            "IPv4 client should be reported as an IPv4-mapped address on a dual-stack socket"
+        );
+    }
+
+    #[test]
+    fn client_socket() {
+        let ipv6_addr = SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 0);
+        let client_socket = bind_client_socket(ipv6_addr).unwrap();
+        assert!(client_socket.local_addr().unwrap().is_ipv6());
+    }
+}
@@ -0,0 +1,300 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetClient, RenetServer};
+use bevy_replicon::{
+    core::{channels::ReplicationChannel, server_entity_map::ServerEntityMap},
+    prelude::*,
+};
+
+use crate::channel_report::{ChannelDirection, ChannelThroughput};
+
+/// Updates both apps until the client's replicated state matches the server's.
+///
+/// Packets are expected to be passed by a transport, like netcode on loopback.
+/// For clients created via [`RenetServer::new_local_client`], use [`assert_converged_local`].
+///
+/// Each tick updates the server and then the client. The state is considered converged once the client
+/// is connected, every [`Replicated`] entity on the server is mapped to a client entity, both sides have
+/// the same number of [`Replicated`] entities, and the client received nothing over replication channels
+/// during the tick, so all changes were delivered and acknowledged. This means the server should not
+/// change replicated components every tick, and all entities should be visible to the client.
+///
+/// Component values are not compared, since they can be transformed on receive. Query them after
+/// this function returns.
+///
+/// Only available with the `test_support` feature.
+///
+/// # Panics
+///
+/// Panics if the state didn't converge within `max_ticks`.
+pub fn assert_converged(server_app: &mut App, client_app: &mut App, max_ticks: usize) {
+    converge(server_app, client_app, None, max_ticks);
+}
+
+/// Like [`assert_converged`], but passes packets between the server and a local client
+/// created via [`RenetServer::new_local_client`] with the given ID.
+///
+/// Only available with the `test_support` feature.
+///
+/// # Panics
+///
+/// Panics if the state didn't converge within `max_ticks` or the local client is not connected.
+pub fn assert_converged_local(
+    server_app: &mut App,
+    client_app: &mut App,
+    client_id: u64,
+    max_ticks: usize,
+) {
+    converge(server_app, client_app, Some(client_id), max_ticks);
+}
+
+/// Updates both apps until the client is connected on both sides.
+///
+/// Packets are expected to be passed by a transport, like netcode on loopback.
+/// For clients created via [`RenetServer::new_local_client`], use [`connect_now_local`].
+///
+/// Each tick updates the server and then the client. Returns once [`RepliconClient`] is connected
+/// and the server has `client_id` in [`ConnectedClients`], so the handshake, including
+/// [`HandshakeMetadata`](crate::HandshakeMetadata) if used, is completed. Unlike updating until
+/// connected without a limit, a connection that can't be established fails the test instead of hanging it.
+///
+/// Only available with the `test_support` feature.
+///
+/// # Panics
+///
+/// Panics if the client didn't connect within `max_ticks`.
+pub fn connect_now(server_app: &mut App, client_app: &mut App, client_id: u64, max_ticks: usize) {
+    connect(server_app, client_app, client_id, false, max_ticks);
+}
+
+/// Like [`connect_now`], but passes packets between the server and a local client
+/// created via [`RenetServer::new_local_client`] with the given ID.
+///
+/// Local clients are connected in renet immediately, so only a few ticks are needed for the server
+/// and the client to process the connection. The number of ticks is limited by [`LOCAL_CONNECT_TICKS`].
+///
+/// Only available with the `test_support` feature.
+///
+/// # Panics
+///
+/// Panics if the client didn't connect within [`LOCAL_CONNECT_TICKS`] or the local client is not connected.
+pub fn connect_now_local(server_app: &mut App, client_app: &mut App, client_id: u64) {
+    connect(server_app, client_app, client_id, true, LOCAL_CONNECT_TICKS);
+}
+
+/// Maximum number of ticks for [`connect_now_local`].
+///
+/// Enough for [`HandshakeMetadata`](crate::HandshakeMetadata) round trips.
+pub const LOCAL_CONNECT_TICKS: usize = 8;
+
+fn connect(
+    server_app: &mut App,
+    client_app: &mut App,
+    client_id: u64,
+    local: bool,
+    max_ticks: usize,
+) {
+    let local_id = local.then_some(client_id);
+    for _ in 0..max_ticks {
+        server_app.update();
+        exchange_packets(server_app, client_app, local_id);
+        client_app.update();
+        exchange_packets(server_app, client_app, local_id);
+
+        if is_connected(server_app, client_app, client_id) {
+            return;
+        }
+    }
+
+    panic!("client `{client_id}` didn't connect within {max_ticks} ticks");
+}
+
+fn converge(server_app: &mut App, client_app: &mut App, local_id: Option<u64>, max_ticks: usize) {
+    for _ in 0..max_ticks {
+        server_app.update();
+        exchange_packets(server_app, client_app, local_id);
+        client_app.update();
+        exchange_packets(server_app, client_app, local_id);
+
+        if is_converged(server_app, client_app) {
+            return;
+        }
+    }
+
+    panic!(
+        "client didn't converge with the server within {max_ticks} ticks: \
+        server has {} replicated entities, client has {}",
+        replicated_count(server_app),
+        replicated_count(client_app),
+    );
+}
+
+fn exchange_packets(server_app: &mut App, client_app: &mut App, local_id: Option<u64>) {
+    let Some(client_id) = local_id else {
+        return;
+    };
+
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    let mut client = client_app.world_mut().resource_mut::<RenetClient>();
+    server
+        .process_local_client(client_id, &mut client)
+        .expect("local client should be connected");
+}
+
+fn is_connected(server_app: &App, client_app: &App, client_id: u64) -> bool {
+    client_app
+        .world()
+        .resource::<RepliconClient>()
+        .is_connected()
+        && server_app
+            .world()
+            .resource::<ConnectedClients>()
+            .iter()
+            .any(|client| client.id().get() == client_id)
+}
+
+fn is_converged(server_app: &mut App, client_app: &mut App) -> bool {
+    if !client_app
+        .world()
+        .resource::<RepliconClient>()
+        .is_connected()
+    {
+        return false;
+    }
+
+    let throughput = client_app.world().resource::<ChannelThroughput>();
+    let received = [ReplicationChannel::Updates, ReplicationChannel::Mutations]
+        .into_iter()
+        .map(|channel| {
+            throughput.current(ClientId::SERVER, ChannelDirection::Receive, channel.into())
+        })
+        .sum::<usize>();
+    if received != 0 {
+        return false;
+    }
+
+    if replicated_count(server_app) != replicated_count(client_app) {
+        return false;
+    }
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    let mut server_entities = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<Replicated>>();
+    server_entities
+        .iter(server_app.world())
+        .all(|entity| entity_map.to_client().contains_key(&entity))
+}
+
+fn replicated_count(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<Replicated>>()
+        .iter(app.world())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_renet::renet::ConnectionConfig;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{RenetChannelsExt, RepliconRenetPlugins};
+
+    #[test]
+    fn local() {
+        let mut server_app = create_app();
+        let mut client_app = create_app();
+
+        let channels = server_app.world().resource::<RepliconChannels>();
+        let mut server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels.get_server_configs(),
+            client_channels_config: channels.get_client_configs(),
+            ..Default::default()
+        });
+        let client = server.new_local_client(0);
+        server_app.insert_resource(server);
+        client_app.insert_resource(client);
+
+        server_app.world_mut().spawn((Replicated, DummyComponent));
+        assert_converged_local(&mut server_app, &mut client_app, 0, 10);
+
+        let mut components = client_app
+            .world_mut()
+            .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>();
+        assert_eq!(components.iter(client_app.world()).count(), 1);
+    }
+
+    #[test]
+    fn connect_local() {
+        let mut server_app = create_app();
+        let mut client_app = create_app();
+
+        let channels = server_app.world().resource::<RepliconChannels>();
+        let mut server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels.get_server_configs(),
+            client_channels_config: channels.get_client_configs(),
+            ..Default::default()
+        });
+        let client = server.new_local_client(0);
+        server_app.insert_resource(server);
+        client_app.insert_resource(client);
+
+        connect_now_local(&mut server_app, &mut client_app, 0);
+
+        let connected_clients = server_app.world().resource::<ConnectedClients>();
+        assert_eq!(connected_clients.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "didn't connect")]
+    fn not_connected() {
+        let mut server_app = create_app();
+        let mut client_app = create_app();
+
+        let channels = server_app.world().resource::<RepliconChannels>();
+        let server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels.get_server_configs(),
+            client_channels_config: channels.get_client_configs(),
+            ..Default::default()
+        });
+        server_app.insert_resource(server);
+
+        connect_now(&mut server_app, &mut client_app, 0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "didn't converge")]
+    fn not_converged() {
+        let mut server_app = create_app();
+        let mut client_app = create_app();
+
+        let channels = server_app.world().resource::<RepliconChannels>();
+        let server = RenetServer::new(ConnectionConfig {
+            server_channels_config: channels.get_server_configs(),
+            client_channels_config: channels.get_client_configs(),
+            ..Default::default()
+        });
+        server_app.insert_resource(server);
+
+        server_app.world_mut().spawn(Replicated);
+        assert_converged(&mut server_app, &mut client_app, 3);
+    }
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .replicate::<DummyComponent>();
+
+        app
+    }
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct DummyComponent;
+}
@@ -0,0 +1,116 @@
+use std::{mem, time::Duration};
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_renet::renet::Bytes;
+use bevy_replicon::core::replicon_tick::RepliconTick;
+
+/// Client channels whose messages are tagged with the time and tick at which they were sent.
+///
+/// For each message sent over these channels, the client prepends:
+///
+/// - The estimated server time from [`ClockSync`](crate::ClockSync) at the beginning of the frame
+///   in which the message was sent.
+/// - The last server tick the client received, which is the tick of the world the player was looking at.
+///   It's the latest of [`ServerUpdateTick`](bevy_replicon::client::ServerUpdateTick) and
+///   [`ServerMutateTicks::last_tick`](bevy_replicon::client::server_mutate_ticks::ServerMutateTicks::last_tick).
+///   Ticks of mutations are tracked by Replicon only if
+///   [`TrackAppExt::track_mutate_messages`](bevy_replicon::core::replication::track_mutate_messages::TrackAppExt::track_mutate_messages)
+///   is called, otherwise only ticks of spawns, insertions and removals are taken into account.
+///
+/// The server strips the tag and records it in [`ReceivedTimestamps`](crate::ReceivedTimestamps), which
+/// can be used for lag compensation, like rewinding hitboxes to the moment the player fired.
+///
+/// Time is meaningful only if [`ClockSyncChannel`](crate::ClockSyncChannel) is inserted on both sides,
+/// since clocks of the client and server are unrelated otherwise. Until [`ClockSync`](crate::ClockSync)
+/// receives its first reply, messages are tagged without time. The tick doesn't depend on clock sync.
+///
+/// Tags are added before [`MessageTransforms`](crate::MessageTransforms) and add 12 bytes to each message.
+/// The resource must be inserted with the same channels on both sides, since it changes the wire format.
+/// Channel IDs are IDs of client channels from [`RepliconChannels`](bevy_replicon::prelude::RepliconChannels).
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon_renet::MessageTimestamps;
+///
+/// # let mut app = App::new();
+/// const INPUT_CHANNEL: u8 = 2;
+/// app.insert_resource(MessageTimestamps::default().with_channel(INPUT_CHANNEL));
+/// ```
+#[derive(Resource, Default, Debug, Clone)]
+pub struct MessageTimestamps {
+    channels: HashSet<u8>,
+}
+
+impl MessageTimestamps {
+    /// Enables tagging for the client channel.
+    #[must_use]
+    pub fn with_channel(mut self, channel_id: impl Into<u8>) -> Self {
+        self.channels.insert(channel_id.into());
+        self
+    }
+
+    /// Returns `true` if messages over the client channel are tagged.
+    pub fn contains(&self, channel_id: u8) -> bool {
+        self.channels.contains(&channel_id)
+    }
+}
+
+const TIME_SIZE: usize = mem::size_of::<u64>();
+const TICK_SIZE: usize = mem::size_of::<u32>();
+
+/// Value of the time field for messages sent without a time estimate.
+const NO_TIME: u64 = u64::MAX;
+
+/// Prepends the server time estimate and tick to the message.
+#[cfg(feature = "client")]
+pub(crate) fn encode(sent_at: Option<Duration>, tick: RepliconTick, message: &[u8]) -> Bytes {
+    let sent_at = sent_at.map_or(NO_TIME, |time| time.as_micros() as u64);
+    let mut tagged = Vec::with_capacity(TIME_SIZE + TICK_SIZE + message.len());
+    tagged.extend_from_slice(&sent_at.to_le_bytes());
+    tagged.extend_from_slice(&tick.get().to_le_bytes());
+    tagged.extend_from_slice(message);
+    tagged.into()
+}
+
+/// Splits a message serialized by [`encode`] into its time, tick and the original message.
+#[cfg(feature = "server")]
+pub(crate) fn decode(mut message: Bytes) -> Option<(Option<Duration>, RepliconTick, Bytes)> {
+    if message.len() < TIME_SIZE + TICK_SIZE {
+        return None;
+    }
+
+    let tag = message.split_to(TIME_SIZE + TICK_SIZE);
+    let (sent_at, tick) = tag.split_at(TIME_SIZE);
+    let sent_at = u64::from_le_bytes(sent_at.try_into().ok()?);
+    let tick = u32::from_le_bytes(tick.try_into().ok()?);
+    let sent_at = (sent_at != NO_TIME).then(|| Duration::from_micros(sent_at));
+
+    Some((sent_at, RepliconTick::new(tick), message))
+}
+
+#[cfg(all(test, feature = "client", feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let sent_at = Duration::from_millis(1500);
+        let tick = RepliconTick::new(42);
+        let tagged = encode(Some(sent_at), tick, &[1, 2]);
+        assert_eq!(tagged.len(), TIME_SIZE + TICK_SIZE + 2);
+        assert_eq!(
+            decode(tagged),
+            Some((Some(sent_at), tick, Bytes::from_static(&[1, 2])))
+        );
+
+        let tagged = encode(None, tick, &[]);
+        assert_eq!(decode(tagged), Some((None, tick, Bytes::new())));
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(decode(Bytes::from_static(&[0; TIME_SIZE])), None);
+    }
+}
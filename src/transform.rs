@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_renet::renet::Bytes;
+use bevy_replicon::prelude::*;
+
+/// Modifies messages right before they are passed to renet.
+///
+/// Registered via [`MessageTransforms::add_outgoing`].
+pub trait OutgoingTransform: Send + Sync + 'static {
+    /// Returns the message that will be sent instead of the original.
+    ///
+    /// `client_id` is the receiving client on the server and [`ClientId::SERVER`] on the client.
+    fn transform(&self, client_id: ClientId, channel_id: u8, message: Bytes) -> Bytes;
+}
+
+/// Reverses [`OutgoingTransform`] for messages received from renet.
+///
+/// Registered via [`MessageTransforms::add_incoming`].
+pub trait IncomingTransform: Send + Sync + 'static {
+    /// Returns the message that will be passed to Replicon instead of the original.
+    ///
+    /// Returning [`None`] discards the message, which is useful for rejecting messages
+    /// with an invalid MAC or sequence tag.
+    ///
+    /// `client_id` is the sending client on the server and [`ClientId::SERVER`] on the client.
+    fn transform(&self, client_id: ClientId, channel_id: u8, message: Bytes) -> Option<Bytes>;
+}
+
+/// Middleware for messages forwarded between renet and Replicon.
+///
+/// Outgoing transforms are applied in the order they were added, after Replicon
+/// has serialized the message and before it's passed to renet.
+/// Incoming transforms are applied in reverse order, right after the message
+/// is received from renet and before it's passed to Replicon.
+/// So if you register transforms for both directions in the same order, each incoming transform
+/// will undo its outgoing counterpart.
+///
+/// This crate doesn't compress messages on its own. If you implement compression as a transform,
+/// register it before any integrity or encryption layer. Encrypted or tagged data doesn't
+/// compress well, and the MAC should cover the bytes that actually go over the wire.
+///
+/// Transforms should be registered identically on both client and server.
+///
+/// Cloning is cheap, since clones share the registered transforms.
+#[derive(Resource, Default, Clone)]
+pub struct MessageTransforms {
+    outgoing: Vec<Arc<dyn OutgoingTransform>>,
+    incoming: Vec<Arc<dyn IncomingTransform>>,
+}
+
+impl MessageTransforms {
+    /// Adds a transform for messages sent to renet.
+    pub fn add_outgoing(&mut self, transform: impl OutgoingTransform) -> &mut Self {
+        self.outgoing.push(Arc::new(transform));
+        self
+    }
+
+    /// Adds a transform for messages received from renet.
+    pub fn add_incoming(&mut self, transform: impl IncomingTransform) -> &mut Self {
+        self.incoming.push(Arc::new(transform));
+        self
+    }
+
+    /// Returns `true` if no transforms are registered.
+    #[cfg(feature = "server")]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.outgoing.is_empty() && self.incoming.is_empty()
+    }
+
+    #[cfg(any(feature = "client", feature = "server"))]
+    pub(crate) fn apply_outgoing(
+        &self,
+        client_id: ClientId,
+        channel_id: u8,
+        mut message: Bytes,
+    ) -> Bytes {
+        for transform in &self.outgoing {
+            message = transform.transform(client_id, channel_id, message);
+        }
+        message
+    }
+
+    #[cfg(any(feature = "client", feature = "server"))]
+    pub(crate) fn apply_incoming(
+        &self,
+        client_id: ClientId,
+        channel_id: u8,
+        mut message: Bytes,
+    ) -> Option<Bytes> {
+        for transform in self.incoming.iter().rev() {
+            message = transform.transform(client_id, channel_id, message)?;
+        }
+        Some(message)
+    }
+}
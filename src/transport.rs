@@ -0,0 +1,188 @@
+use bevy_renet::renet::Bytes;
+#[cfg(feature = "client")]
+use bevy_renet::renet::RenetClient;
+#[cfg(feature = "server")]
+use bevy_renet::renet::RenetServer;
+
+use crate::{ChannelRemap, ReceiveLimit};
+
+/// Messaging backend used by the forwarding systems.
+///
+/// Abstracts renet's server and client, so the forwarding logic can be shared and tested without renet.
+pub(crate) trait MessageTransport {
+    /// Identifies the other side of a connection.
+    ///
+    /// Client ID on the server and `()` on the client, since it's connected only to the server.
+    type Peer: Copy;
+
+    fn receive_message(&mut self, peer: Self::Peer, channel_id: u8) -> Option<Bytes>;
+
+    fn send_message(&mut self, peer: Self::Peer, channel_id: u8, message: Bytes);
+}
+
+#[cfg(feature = "server")]
+impl MessageTransport for RenetServer {
+    type Peer = u64;
+
+    fn receive_message(&mut self, client_id: u64, channel_id: u8) -> Option<Bytes> {
+        RenetServer::receive_message(self, client_id, channel_id)
+    }
+
+    fn send_message(&mut self, client_id: u64, channel_id: u8, message: Bytes) {
+        RenetServer::send_message(self, client_id, channel_id, message)
+    }
+}
+
+#[cfg(feature = "client")]
+impl MessageTransport for RenetClient {
+    type Peer = ();
+
+    fn receive_message(&mut self, _peer: (), channel_id: u8) -> Option<Bytes> {
+        RenetClient::receive_message(self, channel_id)
+    }
+
+    fn send_message(&mut self, _peer: (), channel_id: u8, message: Bytes) {
+        RenetClient::send_message(self, channel_id, message)
+    }
+}
+
+/// Side that sends messages over a channel, used to pick the mapping from [`ChannelRemap`].
+#[derive(Clone, Copy)]
+pub(crate) enum ChannelSide {
+    /// Channels used by the server to send, received on the client.
+    Server,
+    /// Channels used by clients to send, received on the server.
+    Client,
+}
+
+impl ChannelSide {
+    /// Returns the wire ID for a local channel of this side.
+    pub(crate) fn wire_id(self, remap: &ChannelRemap, channel_id: u8) -> u8 {
+        match self {
+            ChannelSide::Server => remap.server_wire_id(channel_id),
+            ChannelSide::Client => remap.client_wire_id(channel_id),
+        }
+    }
+}
+
+/// Receives messages from the given channels of the peer and passes them to `receive` with local channel IDs.
+///
+/// Takes at most [`ReceiveLimit`] messages per channel and translates channel IDs via [`ChannelRemap`].
+pub(crate) fn receive_messages<T: MessageTransport>(
+    transport: &mut T,
+    peer: T::Peer,
+    channel_ids: impl Iterator<Item = u8>,
+    side: ChannelSide,
+    remap: &ChannelRemap,
+    receive_limit: ReceiveLimit,
+    mut receive: impl FnMut(u8, Bytes),
+) {
+    for channel_id in channel_ids {
+        let wire_id = side.wire_id(remap, channel_id);
+        for _ in 0..receive_limit.max_messages() {
+            let Some(message) = transport.receive_message(peer, wire_id) else {
+                break;
+            };
+
+            receive(channel_id, message);
+        }
+    }
+}
+
+/// Sends a message over a local channel translated via [`ChannelRemap`].
+pub(crate) fn send_message<T: MessageTransport>(
+    transport: &mut T,
+    peer: T::Peer,
+    side: ChannelSide,
+    remap: &ChannelRemap,
+    channel_id: u8,
+    message: Bytes,
+) {
+    transport.send_message(peer, side.wire_id(remap, channel_id), message);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use bevy::utils::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn limit() {
+        let mut transport = MockTransport::default();
+        for index in 0..3 {
+            transport.send_message((), 0, vec![index].into());
+        }
+        transport.send_message((), 1, vec![3].into());
+
+        let mut received = Vec::new();
+        receive_messages(
+            &mut transport,
+            (),
+            0..2,
+            ChannelSide::Server,
+            &ChannelRemap::default(),
+            ReceiveLimit(NonZeroUsize::new(2)),
+            |channel_id, message| received.push((channel_id, message[0])),
+        );
+        assert_eq!(received, [(0, 0), (0, 1), (1, 3)]);
+
+        received.clear();
+        receive_messages(
+            &mut transport,
+            (),
+            0..2,
+            ChannelSide::Server,
+            &ChannelRemap::default(),
+            ReceiveLimit(NonZeroUsize::new(2)),
+            |channel_id, message| received.push((channel_id, message[0])),
+        );
+        assert_eq!(received, [(0, 2)], "remaining messages should be kept");
+    }
+
+    #[test]
+    fn remap() {
+        let mut transport = MockTransport::default();
+        transport.send_message((), 0, vec![0].into());
+        transport.send_message((), 1, vec![1].into());
+
+        let remap = ChannelRemap::default()
+            .with_client_channel(0, 1)
+            .with_client_channel(1, 0);
+        let mut received = Vec::new();
+        receive_messages(
+            &mut transport,
+            (),
+            0..2,
+            ChannelSide::Client,
+            &remap,
+            ReceiveLimit::default(),
+            |channel_id, message| received.push((channel_id, message[0])),
+        );
+        assert_eq!(received, [(0, 1), (1, 0)]);
+    }
+
+    /// Transport that receives the messages sent to it.
+    #[derive(Default)]
+    struct MockTransport {
+        channels: HashMap<u8, Vec<Bytes>>,
+    }
+
+    impl MessageTransport for MockTransport {
+        type Peer = ();
+
+        fn receive_message(&mut self, _peer: (), channel_id: u8) -> Option<Bytes> {
+            let messages = self.channels.get_mut(&channel_id)?;
+            if messages.is_empty() {
+                return None;
+            }
+            Some(messages.remove(0))
+        }
+
+        fn send_message(&mut self, _peer: (), channel_id: u8, message: Bytes) {
+            self.channels.entry(channel_id).or_default().push(message);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+#[cfg(feature = "renet_netcode")]
+use bevy_renet::netcode::{NetcodeClientTransport, NetcodeServerTransport};
+#[cfg(feature = "renet_steam")]
+use bevy_renet::steam::{SteamClientTransport, SteamServerTransport};
+
+/// Returns `true` if [`NetcodeServerTransport`], [`MultiSocketServerTransport`](crate::MultiSocketServerTransport)
+/// or [`NetcodeClientTransport`] is present.
+///
+/// Complements [`resource_exists`] checks for [`RenetServer`](bevy_renet::renet::RenetServer)
+/// and [`RenetClient`](bevy_renet::renet::RenetClient) when multiple transports are enabled.
+#[cfg(feature = "renet_netcode")]
+pub fn netcode_transport_active(
+    server_transport: Option<Res<NetcodeServerTransport>>,
+    #[cfg(feature = "server")] multi_transport: Option<Res<crate::MultiSocketServerTransport>>,
+    client_transport: Option<Res<NetcodeClientTransport>>,
+) -> bool {
+    #[cfg(feature = "server")]
+    if multi_transport.is_some() {
+        return true;
+    }
+
+    server_transport.is_some() || client_transport.is_some()
+}
+
+/// Returns `true` if [`SteamServerTransport`] or [`SteamClientTransport`] is present.
+///
+/// Useful to enable Steam-specific features, like the overlay, only for apps connected via Steam.
+/// [`SteamServerTransport`] is a non-send resource, so systems that use this condition run on the main thread.
+#[cfg(feature = "renet_steam")]
+pub fn steam_transport_active(
+    server_transport: Option<NonSend<SteamServerTransport>>,
+    client_transport: Option<Res<SteamClientTransport>>,
+) -> bool {
+    server_transport.is_some() || client_transport.is_some()
+}
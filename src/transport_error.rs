@@ -0,0 +1,58 @@
+use std::io::ErrorKind;
+
+use bevy_renet::netcode::NetcodeTransportError;
+
+/// Returns `true` if the error doesn't indicate a broken transport.
+///
+/// See [`ServerTransportError`](crate::ServerTransportError) for the list of such errors.
+pub(crate) fn is_transient(error: &NetcodeTransportError) -> bool {
+    let NetcodeTransportError::IO(error) = error else {
+        return false;
+    };
+
+    matches!(
+        error.kind(),
+        ErrorKind::WouldBlock
+            | ErrorKind::Interrupted
+            | ErrorKind::TimedOut
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionAborted
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use bevy_renet::{netcode::NetcodeError, renet::DisconnectReason};
+
+    use super::*;
+
+    #[test]
+    fn transient() {
+        for kind in [
+            ErrorKind::WouldBlock,
+            ErrorKind::TimedOut,
+            ErrorKind::ConnectionRefused,
+        ] {
+            assert!(is_transient(&NetcodeTransportError::IO(kind.into())));
+        }
+    }
+
+    #[test]
+    fn fatal() {
+        assert!(!is_transient(&NetcodeTransportError::IO(io::Error::other(
+            "interface is down"
+        ))));
+        assert!(!is_transient(&NetcodeTransportError::IO(
+            ErrorKind::AddrNotAvailable.into()
+        )));
+        assert!(!is_transient(&NetcodeTransportError::Netcode(
+            NetcodeError::Expired
+        )));
+        assert!(!is_transient(&NetcodeTransportError::Renet(
+            DisconnectReason::Transport
+        )));
+    }
+}
@@ -0,0 +1,165 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_renet::renet::ChannelConfig;
+use bevy_replicon::prelude::*;
+
+use crate::{
+    transport::{ChannelSide, MessageTransport},
+    AssetStreamChannel, ChannelRemap, ClockSyncChannel, ControlChannel, ReliableSendChannel,
+};
+
+/// Drains renet channels that nothing reads.
+///
+/// The forwarding systems poll only channels from [`RepliconChannels`]. If renet was configured
+/// with more receive channels, for example, after a version skew between client and server,
+/// messages sent over them by a peer stay buffered in renet until the channel's memory limit is reached.
+/// With this resource such channels are drained every frame in [`ServerSet::ReceivePackets`] and
+/// [`ClientSet::ReceivePackets`] and handled according to [`Self::action`].
+///
+/// Channels from [`RepliconChannels`] (translated via [`ChannelRemap`]), as well as channels of this crate's features,
+/// like [`ControlChannel`] or [`ClockSyncChannel`], are never drained. If you read a custom channel directly
+/// from renet, exclude it via [`Self::with_known`].
+///
+/// Renet itself disconnects peers that send over channels missing from its configs, so only channels
+/// that exist in the configs can be drained, which is why they need to be passed on creation.
+///
+/// Disabled by default, insert this resource to enable it.
+#[derive(Resource, Debug, Clone)]
+pub struct UnknownChannelPolicy {
+    /// What to do with messages received over unknown channels.
+    pub action: UnknownChannelAction,
+
+    channel_ids: Vec<u8>,
+}
+
+impl UnknownChannelPolicy {
+    /// Creates a policy for the channels the local side receives over.
+    ///
+    /// Pass `client_channels_config` on the server and `server_channels_config` on the client.
+    pub fn new(action: UnknownChannelAction, receive_configs: &[ChannelConfig]) -> Self {
+        Self {
+            action,
+            channel_ids: receive_configs
+                .iter()
+                .map(|config| config.channel_id)
+                .collect(),
+        }
+    }
+
+    /// Excludes a channel that is read by game code.
+    #[must_use]
+    pub fn with_known(mut self, channel_id: u8) -> Self {
+        self.channel_ids.retain(|&id| id != channel_id);
+        self
+    }
+}
+
+/// Action for [`UnknownChannelPolicy`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownChannelAction {
+    /// Log a warning and discard the messages.
+    #[default]
+    Discard,
+    /// Log a warning and disconnect the peer.
+    ///
+    /// Useful to get rid of malicious peers or incompatible versions.
+    Disconnect,
+}
+
+/// Channels that are read by the forwarding systems or this crate's features.
+#[derive(SystemParam)]
+pub(crate) struct UnknownChannels<'w> {
+    policy: Res<'w, UnknownChannelPolicy>,
+    channels: Res<'w, RepliconChannels>,
+    remap: Res<'w, ChannelRemap>,
+    clock_sync_channel: Option<Res<'w, ClockSyncChannel>>,
+    asset_stream_channel: Option<Res<'w, AssetStreamChannel>>,
+    reliable_send_channel: Option<Res<'w, ReliableSendChannel>>,
+    control_channel: Option<Res<'w, ControlChannel>>,
+}
+
+impl UnknownChannels<'_> {
+    pub(crate) fn action(&self) -> UnknownChannelAction {
+        self.policy.action
+    }
+
+    /// Receives all messages from unknown channels of the peer and returns their number.
+    ///
+    /// `side` is the side that sends over the channels the local side receives.
+    pub(crate) fn drain<T: MessageTransport>(
+        &self,
+        transport: &mut T,
+        peer: T::Peer,
+        side: ChannelSide,
+    ) -> usize {
+        let mut count = 0;
+        for &channel_id in &self.policy.channel_ids {
+            if self.is_known(side, channel_id) {
+                continue;
+            }
+
+            while transport.receive_message(peer, channel_id).is_some() {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    fn is_known(&self, side: ChannelSide, wire_id: u8) -> bool {
+        let replicon_count = match side {
+            ChannelSide::Server => self.channels.server_channels().len(),
+            ChannelSide::Client => self.channels.client_channels().len(),
+        };
+        if (0..replicon_count as u8)
+            .any(|channel_id| side.wire_id(&self.remap, channel_id) == wire_id)
+        {
+            return true;
+        }
+
+        [
+            self.clock_sync_channel.as_deref().map(|channel| **channel),
+            self.asset_stream_channel
+                .as_deref()
+                .map(|channel| **channel),
+            self.reliable_send_channel
+                .as_deref()
+                .map(|channel| **channel),
+            self.control_channel.as_deref().map(|channel| **channel),
+        ]
+        .contains(&Some(wire_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+
+    #[test]
+    fn known_channels() {
+        let mut world = World::new();
+        let channels = RepliconChannels::default();
+        let first_id = channels.server_channels().len() as u8;
+        world.insert_resource(channels);
+        world.init_resource::<ChannelRemap>();
+        world.insert_resource(UnknownChannelPolicy::new(
+            UnknownChannelAction::Discard,
+            &[],
+        ));
+        world.insert_resource(ControlChannel(first_id));
+        world.insert_resource(ClockSyncChannel(first_id + 1));
+        world.insert_resource(AssetStreamChannel(first_id + 2));
+        world.insert_resource(ReliableSendChannel(first_id + 3));
+
+        let mut state = SystemState::<UnknownChannels>::new(&mut world);
+        let unknown_channels = state.get(&world);
+        for channel_id in 0..=first_id + 3 {
+            assert!(
+                unknown_channels.is_known(ChannelSide::Server, channel_id),
+                "channel {channel_id} should be known"
+            );
+        }
+        assert!(!unknown_channels.is_known(ChannelSide::Server, first_id + 4));
+    }
+}
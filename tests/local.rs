@@ -0,0 +1,1959 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use bevy::{
+    ecs::system::{RunSystemOnce, SystemState},
+    prelude::*,
+};
+use bevy_renet::renet::{
+    Bytes, ChannelConfig, ConnectionConfig, RenetClient, RenetServer, SendType,
+};
+use bevy_replicon::{client::ServerUpdateTick, prelude::*, server::server_tick::ServerTick};
+use bevy_replicon_renet::{
+    connect_now_local, flush_client_messages, server_full, AdmissionControl, AssetProgress,
+    AssetReceived, AssetStreamChannel, AssetStreams, BudgetShare, ChannelDirection, ChannelGroup,
+    ChannelRateLimit, ChannelRemap, ChannelState, ClientChannelReport, ClientIdMap,
+    ClientIdMismatch, ClientKicked, ClientMetadata, ClientNetworkInfo, ClientPendingMessages,
+    ClientRedirected, ClientRejected, ClientSetupSet, ClientTransport, ClientTransports,
+    ClosedChannels, ConfiguredChannels, ConnectionPhase, ConnectionPhases, ConnectionStats,
+    ControlChannel, DisconnectedClients, EchoClientEvents, EventEchoed, FlushPriority,
+    GracefulShutdown, HandshakeExtension, HandshakeExtensions, HandshakeMetadata, IdleDisconnect,
+    IdleTimeout, IncomingSequenceTags, KickClient, LastReceived, MaintenanceMode, ManualFlush,
+    MaxAssetSize, MaxClients, MaxMessageSize, MaxPendingConnections, MessageStatus,
+    MessageTimestamps, MessageTransforms, NetworkActivity, OutgoingSequenceTags, OversizedMessage,
+    OversizedMessageAction, ParallelForwarding, PendingMessages, ReceivedTimestamps,
+    RedirectClient, RedirectConnector, RejectReason, ReliableSend, ReliableSendChannel,
+    RenetChannelsExt, RepliconDisconnectReason, RepliconRenetPlugins, SendOnFixedTick,
+    ServerBandwidth, ServerChannelReport, ServerFull, ServerHasCapacity, ServerMessaging,
+    ServerMetadata, ShutdownReport, TotalMemoryBudget, UnknownChannelAction, UnknownChannelPolicy,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn many_clients() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..CLIENTS_COUNT).map(|_| create_app()).collect();
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    for (client_id, client_app) in client_apps.iter_mut().enumerate() {
+        connect_now_local(&mut server_app, client_app, client_id as u64);
+    }
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), CLIENTS_COUNT);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+    for client_app in &mut client_apps {
+        client_app.world_mut().send_event(DummyClientEvent);
+    }
+
+    update(&mut server_app, &mut client_apps);
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert_eq!(client_events.len(), CLIENTS_COUNT);
+
+    for client_app in &mut client_apps {
+        let mut components = client_app
+            .world_mut()
+            .query_filtered::<(), With<DummyComponent>>();
+        assert_eq!(components.iter(client_app.world()).count(), 1);
+
+        let dummy_events = client_app.world().resource::<Events<DummyEvent>>();
+        assert_eq!(dummy_events.len(), 1);
+    }
+}
+
+#[test]
+fn asset_stream() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let channel_id = server_channels_config.len() as u8;
+    let stream_config = ChannelConfig {
+        channel_id,
+        max_memory_usage_bytes: 64 * 1024,
+        send_type: SendType::ReliableOrdered {
+            resend_time: Duration::from_millis(300),
+        },
+    };
+    server_channels_config.push(stream_config.clone());
+    client_channels_config.push(stream_config);
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    for app in [&mut server_app, &mut client_apps[0]] {
+        app.insert_resource(AssetStreamChannel(channel_id));
+    }
+
+    update(&mut server_app, &mut client_apps);
+
+    let data: Vec<_> = (0..1024 * 1024).map(|index| index as u8).collect();
+    let stream_id = server_app
+        .world_mut()
+        .resource_mut::<AssetStreams>()
+        .send(ClientId::new(0), data.clone());
+
+    let mut received = None;
+    for _ in 0..100 {
+        update(&mut server_app, &mut client_apps);
+        let events = client_apps[0].world().resource::<Events<AssetReceived>>();
+        if let Some(event) = events.iter_current_update_events().next() {
+            received = Some(event.clone());
+            break;
+        }
+    }
+
+    let received = received.expect("stream should be received");
+    assert_eq!(received.stream_id, stream_id);
+    assert_eq!(received.data, data);
+
+    client_apps[0].insert_resource(MaxAssetSize(data.len() - 1));
+    client_apps[0]
+        .world_mut()
+        .resource_mut::<Events<AssetReceived>>()
+        .clear();
+    client_apps[0]
+        .world_mut()
+        .resource_mut::<Events<AssetProgress>>()
+        .clear();
+    let stream_id = server_app
+        .world_mut()
+        .resource_mut::<AssetStreams>()
+        .send(ClientId::new(0), data);
+
+    let mut progress_events = 0;
+    for _ in 0..100 {
+        update(&mut server_app, &mut client_apps);
+        let events = client_apps[0].world().resource::<Events<AssetReceived>>();
+        assert_eq!(
+            events.iter_current_update_events().count(),
+            0,
+            "stream exceeding the limit should be discarded"
+        );
+        let events = client_apps[0].world().resource::<Events<AssetProgress>>();
+        progress_events += events.iter_current_update_events().count();
+    }
+    assert_eq!(progress_events, 0);
+    let streams = server_app.world().resource::<AssetStreams>();
+    assert!(
+        streams.progress(stream_id).is_none(),
+        "stream should be fully sent"
+    );
+}
+
+#[test]
+fn admission_control() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+    server_app.insert_resource(AdmissionControl::new(Duration::ZERO));
+
+    // Measure the first frame time before accepting connections.
+    server_app.update();
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert!(
+        connected_clients.is_empty(),
+        "client should be deferred while overloaded"
+    );
+
+    server_app.world_mut().remove_resource::<AdmissionControl>();
+
+    update(&mut server_app, &mut client_apps);
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 1);
+}
+
+#[test]
+fn max_pending_connections() {
+    let mut server_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                replicate_after_connect: false,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .insert_resource(MaxPendingConnections(1));
+
+    let mut client_apps = [App::new(), App::new()];
+    setup_local(&mut server_app, &mut client_apps);
+
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 1);
+    let accepted_id = connected_clients.iter().next().unwrap().id();
+    let mut rejected_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ClientRejected>>();
+    let rejected: Vec<_> = rejected_events.drain().collect();
+    assert_eq!(rejected.len(), 1);
+    assert_ne!(rejected[0].client_id, accepted_id);
+    assert_eq!(rejected[0].reason, RejectReason::TooManyPending);
+
+    server_app
+        .world_mut()
+        .send_event(StartReplication(accepted_id));
+    server_app.update();
+
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    let _authorized_client = server.new_local_client(2);
+
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(
+        connected_clients.len(),
+        2,
+        "authorized clients shouldn't count as pending"
+    );
+}
+
+#[test]
+fn connection_phases() {
+    let mut server_app = App::new();
+    server_app.add_plugins((
+        MinimalPlugins,
+        RepliconPlugins.set(ServerPlugin {
+            tick_policy: TickPolicy::EveryFrame,
+            replicate_after_connect: false,
+            ..Default::default()
+        }),
+        RepliconRenetPlugins,
+    ));
+
+    let mut client_apps = [App::new()];
+    setup_local(&mut server_app, &mut client_apps);
+
+    server_app.update();
+
+    let client_id = ClientId::new(0);
+    let phases = server_app.world().resource::<ConnectionPhases>();
+    assert_eq!(phases.get(client_id), Some(ConnectionPhase::Authorizing));
+
+    server_app
+        .world_mut()
+        .send_event(StartReplication(client_id));
+    server_app.update();
+
+    let phases = server_app.world().resource::<ConnectionPhases>();
+    assert_eq!(phases.get(client_id), Some(ConnectionPhase::Connected));
+
+    server_app.world_mut().send_event(KickClient {
+        client_id,
+        reason: Bytes::new(),
+    });
+    server_app.update();
+
+    let phases = server_app.world().resource::<ConnectionPhases>();
+    assert_eq!(phases.get(client_id), Some(ConnectionPhase::Disconnecting));
+
+    // Local clients have no transport to remove disconnected connections.
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    server.remove_connection(0);
+    server_app.update();
+
+    let phases = server_app.world().resource::<ConnectionPhases>();
+    assert_eq!(phases.get(client_id), None);
+}
+
+#[test]
+fn max_message_size() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+    for app in client_apps.iter_mut().chain([&mut server_app]) {
+        let mut channels = app.world_mut().resource_mut::<RepliconChannels>();
+        channels.create_client_channel(ChannelKind::Ordered.into());
+    }
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let channel_id = channels.client_channels().len() as u8 - 1;
+    let mut server_channels_config = channels.get_server_configs();
+    let client_channels_config = channels.get_client_configs();
+    // Local clients send over server channels.
+    server_channels_config.push(client_channels_config[channel_id as usize].clone());
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    server_app.insert_resource(MaxMessageSize::default().with_channel(channel_id, 4));
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut replicon_client = client_apps[0].world_mut().resource_mut::<RepliconClient>();
+    replicon_client.send(channel_id, vec![0; 8]);
+    replicon_client.send(channel_id, vec![1; 4]);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut replicon_server = server_app.world_mut().resource_mut::<RepliconServer>();
+    let received: Vec<_> = replicon_server
+        .receive(channel_id)
+        .map(|(_, message)| message)
+        .collect();
+    assert_eq!(received, [Bytes::from_static(&[1; 4])]);
+
+    let mut oversized_events = server_app
+        .world_mut()
+        .resource_mut::<Events<OversizedMessage>>();
+    assert_eq!(
+        oversized_events.drain().collect::<Vec<_>>(),
+        [OversizedMessage {
+            client_id: ClientId::new(0),
+            channel_id,
+            len: 8,
+            limit: 4,
+        }]
+    );
+
+    server_app.insert_resource(
+        MaxMessageSize::default()
+            .with_channel(channel_id, 4)
+            .with_action(OversizedMessageAction::Disconnect),
+    );
+    let mut replicon_client = client_apps[0].world_mut().resource_mut::<RepliconClient>();
+    replicon_client.send(channel_id, vec![0; 8]);
+
+    update(&mut server_app, &mut client_apps);
+
+    let server = server_app.world().resource::<RenetServer>();
+    assert!(!server.is_connected(0));
+}
+
+#[test]
+fn max_clients() {
+    let mut server_app = create_app();
+    server_app.insert_resource(MaxClients(1));
+
+    let mut client_apps = [App::new(), App::new()];
+    setup_local(&mut server_app, &mut client_apps);
+
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 1);
+    let mut rejected_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ClientRejected>>();
+    let rejected: Vec<_> = rejected_events.drain().collect();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].reason, RejectReason::Full);
+
+    server_app.insert_resource(MaxClients(2));
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    let _third_client = server.new_local_client(2);
+
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 2);
+
+    server_app.insert_resource(MaxClients(1));
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(
+        connected_clients.len(),
+        2,
+        "lowering the limit shouldn't disconnect clients"
+    );
+}
+
+#[test]
+fn client_transports() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    connect_now_local(&mut server_app, &mut client_apps[0], 0);
+
+    let client_transports = server_app.world().resource::<ClientTransports>();
+    assert_eq!(
+        client_transports.get(ClientId::new(0)),
+        Some(ClientTransport::Memory)
+    );
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect_local_client(0, &mut client);
+    server_app.update();
+
+    let client_transports = server_app.world().resource::<ClientTransports>();
+    assert_eq!(client_transports.get(ClientId::new(0)), None);
+}
+
+#[test]
+fn message_timestamps() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let event_channel = channels.client_channels().len() as u8 - 1;
+    setup_local(&mut server_app, &mut client_apps);
+    client_apps[0].insert_resource(MessageTimestamps::default().with_channel(event_channel));
+    server_app.insert_resource(MessageTimestamps::default().with_channel(event_channel));
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    update(&mut server_app, &mut client_apps);
+
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+    update(&mut server_app, &mut client_apps);
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert_eq!(client_events.len(), 1);
+
+    let update_tick = **client_apps[0].world().resource::<ServerUpdateTick>();
+    let received_timestamps = server_app.world().resource::<ReceivedTimestamps>();
+    let timestamps = received_timestamps.get(ClientId::new(0), event_channel);
+    assert_eq!(timestamps.len(), 1);
+    assert_eq!(timestamps[0].tick, update_tick);
+    assert_eq!(
+        timestamps[0].sent_at, None,
+        "time should be sent only with clock sync"
+    );
+
+    server_app.update();
+
+    let received_timestamps = server_app.world().resource::<ReceivedTimestamps>();
+    assert!(received_timestamps
+        .get(ClientId::new(0), event_channel)
+        .is_empty());
+}
+
+#[test]
+fn echo_client_events() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let event_channel = channels.client_channels().len() as u8 - 1;
+    let control_channel = ControlChannel::after(channels);
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    server_channels_config.push(control_channel.config());
+    client_channels_config.push(control_channel.config());
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    client_apps[0].insert_resource(control_channel);
+    server_app
+        .insert_resource(control_channel)
+        .insert_resource(EchoClientEvents::default().with_channel(event_channel));
+
+    update(&mut server_app, &mut client_apps);
+
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+    update(&mut server_app, &mut client_apps);
+
+    let server_tick = **server_app.world().resource::<ServerTick>();
+
+    exchange_packets(&mut server_app, &mut client_apps);
+    client_apps[0].update();
+
+    let mut echo_events = client_apps[0]
+        .world_mut()
+        .resource_mut::<Events<EventEchoed>>();
+    let events: Vec<_> = echo_events.drain().collect();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.channel_id, event_channel);
+    assert_eq!(event.tick, server_tick);
+}
+
+#[test]
+fn visibility_budget_share() {
+    const MESSAGES_COUNT: usize = 20;
+    const MESSAGE_SIZE: usize = 100;
+
+    let mut server_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .replicate::<DummyComponent>()
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered)
+        .add_client_event::<DummyClientEvent>(ChannelKind::Ordered);
+    let mut client_apps = [create_app(), create_app()];
+    for app in client_apps.iter_mut().chain([&mut server_app]) {
+        let mut channels = app.world_mut().resource_mut::<RepliconChannels>();
+        channels.create_server_channel(ChannelKind::Unreliable.into());
+    }
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let channel_id = channels.server_channels().len() as u8 - 1;
+    let server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    // Local clients receive over client channels.
+    client_channels_config.push(server_channels_config[channel_id as usize].clone());
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config.clone(),
+        client_channels_config,
+    );
+    server_app.insert_resource(
+        TotalMemoryBudget::new(5000, &server_channels_config).with_share(BudgetShare::Visibility),
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    // Only the first client sees entities, which gives it 4/5 of the budget.
+    for _ in 0..3 {
+        let entity = server_app
+            .world_mut()
+            .spawn((Replicated, DummyComponent))
+            .id();
+        server_app
+            .world_mut()
+            .resource_mut::<ReplicatedClients>()
+            .client_mut(ClientId::new(0))
+            .visibility_mut()
+            .set_visibility(entity, true);
+    }
+
+    update(&mut server_app, &mut client_apps);
+    update(&mut server_app, &mut client_apps);
+
+    let mut replicon_server = server_app.world_mut().resource_mut::<RepliconServer>();
+    for client_id in 0..client_apps.len() {
+        for _ in 0..MESSAGES_COUNT {
+            replicon_server.send(
+                ClientId::new(client_id as u64),
+                channel_id,
+                vec![0; MESSAGE_SIZE],
+            );
+        }
+    }
+
+    server_app.update();
+    exchange_packets(&mut server_app, &mut client_apps);
+
+    let received: Vec<_> = client_apps
+        .iter_mut()
+        .map(|client_app| {
+            let mut client = client_app.world_mut().resource_mut::<RenetClient>();
+            std::iter::from_fn(|| client.receive_message(channel_id)).count()
+        })
+        .collect();
+    assert_eq!(
+        received,
+        [MESSAGES_COUNT, 7],
+        "each client should get 3/4 of its share for events"
+    );
+}
+
+#[test]
+fn close_channel() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+    for app in client_apps.iter_mut().chain([&mut server_app]) {
+        let mut channels = app.world_mut().resource_mut::<RepliconChannels>();
+        channels.create_server_channel(ChannelKind::Ordered.into());
+    }
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let channel_id = channels.server_channels().len() as u8 - 1;
+    let server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    // Local clients receive over client channels.
+    client_channels_config.push(server_channels_config[channel_id as usize].clone());
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config.clone(),
+        client_channels_config,
+    );
+    server_app.insert_resource(ClosedChannels::new(&server_channels_config));
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut replicon_server = server_app.world_mut().resource_mut::<RepliconServer>();
+    replicon_server.send(ClientId::new(0), channel_id, vec![0]);
+    server_app.update();
+
+    let mut replicon_server = server_app.world_mut().resource_mut::<RepliconServer>();
+    replicon_server.send(ClientId::new(0), channel_id, vec![1]);
+    let mut closed_channels = server_app.world_mut().resource_mut::<ClosedChannels>();
+    closed_channels.close(channel_id);
+
+    server_app.update();
+    exchange_packets(&mut server_app, &mut client_apps);
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    let received: Vec<_> = std::iter::from_fn(|| client.receive_message(channel_id))
+        .map(|message| message[0])
+        .collect();
+    assert_eq!(
+        received,
+        [0],
+        "only messages forwarded before closing should be delivered"
+    );
+
+    let closed_channels = server_app.world().resource::<ClosedChannels>();
+    assert_eq!(closed_channels.state(channel_id), ChannelState::Draining);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut closed_channels = server_app.world_mut().resource_mut::<ClosedChannels>();
+    assert_eq!(closed_channels.state(channel_id), ChannelState::Closed);
+
+    closed_channels.reopen(channel_id);
+    let mut replicon_server = server_app.world_mut().resource_mut::<RepliconServer>();
+    replicon_server.send(ClientId::new(0), channel_id, vec![2]);
+
+    server_app.update();
+    exchange_packets(&mut server_app, &mut client_apps);
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    assert_eq!(
+        client.receive_message(channel_id).as_deref(),
+        Some(&[2][..])
+    );
+}
+
+#[cfg(feature = "init_capture")]
+#[test]
+fn init_capture() {
+    use bevy_replicon_renet::InitMessageCaptured;
+
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    update(&mut server_app, &mut client_apps);
+
+    let server_events: Vec<_> = server_app
+        .world_mut()
+        .resource_mut::<Events<InitMessageCaptured>>()
+        .drain()
+        .collect();
+    assert_eq!(server_events.len(), 1);
+    let server_event = &server_events[0];
+    assert_eq!(server_event.peer, ClientId::new(0));
+    assert!(!server_event.is_truncated());
+
+    let client_events: Vec<_> = client_apps[0]
+        .world_mut()
+        .resource_mut::<Events<InitMessageCaptured>>()
+        .drain()
+        .collect();
+    assert_eq!(client_events.len(), 1);
+    let client_event = &client_events[0];
+    assert_eq!(client_event.peer, ClientId::SERVER);
+    assert_eq!(client_event.message, server_event.message);
+
+    let mut components = client_apps[0].world_mut().query::<&DummyComponent>();
+    assert_eq!(
+        components.iter(client_apps[0].world()).count(),
+        1,
+        "message should still be applied"
+    );
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    update(&mut server_app, &mut client_apps);
+
+    let server_events = server_app.world().resource::<Events<InitMessageCaptured>>();
+    assert!(server_events.is_empty(), "should be captured only once");
+    let client_events = client_apps[0]
+        .world()
+        .resource::<Events<InitMessageCaptured>>();
+    assert!(client_events.is_empty(), "should be captured only once");
+}
+
+#[cfg(feature = "diagnostics")]
+#[test]
+fn message_count_diagnostics() {
+    use bevy::diagnostic::{DiagnosticPath, DiagnosticsPlugin, DiagnosticsStore};
+    use bevy_replicon_renet::MessageCountDiagnostics;
+
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+    for app in [&mut server_app, &mut client_apps[0]] {
+        app.add_plugins((DiagnosticsPlugin, MessageCountDiagnostics));
+    }
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+
+    update(&mut server_app, &mut client_apps);
+
+    let measurement = |app: &App, path: &DiagnosticPath| {
+        app.world()
+            .resource::<DiagnosticsStore>()
+            .get(path)
+            .and_then(|diagnostic| diagnostic.value())
+    };
+
+    assert_eq!(
+        measurement(&client_apps[0], &MessageCountDiagnostics::CLIENT_SENT),
+        Some(2.0)
+    );
+    assert_eq!(
+        measurement(&server_app, &MessageCountDiagnostics::SERVER_RECEIVED),
+        Some(2.0)
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    assert_eq!(
+        measurement(&client_apps[0], &MessageCountDiagnostics::CLIENT_SENT),
+        Some(0.0),
+        "counts should be reset every frame"
+    );
+    assert_eq!(
+        measurement(&server_app, &MessageCountDiagnostics::SERVER_RECEIVED),
+        Some(0.0)
+    );
+}
+
+#[test]
+fn prioritized_messages() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let channel_id = server_channels_config.len() as u8;
+    let config = ChannelConfig {
+        channel_id,
+        max_memory_usage_bytes: 64 * 1024,
+        send_type: SendType::ReliableUnordered {
+            resend_time: Duration::from_millis(300),
+        },
+    };
+    server_channels_config.push(config.clone());
+    client_channels_config.push(config);
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut messaging = SystemState::<ServerMessaging>::new(server_app.world_mut());
+    let mut server_messaging = messaging.get_mut(server_app.world_mut());
+    server_messaging.send_prioritized(ClientId::new(0), channel_id, 0, vec![0]);
+    server_messaging.send_prioritized(ClientId::new(0), channel_id, 1, vec![1]);
+    server_messaging.send_prioritized(ClientId::new(0), channel_id, 0, vec![2]);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    let received: Vec<_> = std::iter::from_fn(|| client.receive_message(channel_id))
+        .map(|message| message[0])
+        .collect();
+    assert_eq!(received, [1, 0, 2]);
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let ordered_channel = channels.server_channels().len() as u8 - 1;
+    let mut server_messaging = messaging.get_mut(server_app.world_mut());
+    server_messaging.send_prioritized(ClientId::new(0), ordered_channel, 0, vec![0]);
+    server_messaging.send_prioritized(ClientId::new(0), ordered_channel, 1, vec![1]);
+
+    server_app.update();
+    exchange_packets(&mut server_app, &mut client_apps);
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    let received: Vec<_> = std::iter::from_fn(|| client.receive_message(ordered_channel))
+        .map(|message| message[0])
+        .collect();
+    assert_eq!(received, [0, 1], "ordered channels shouldn't be reordered");
+}
+
+#[test]
+fn pending_messages() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+    server_app.update();
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let event_channel = channels.server_channels().len() as u8 - 1;
+    let max_bytes = channels.default_max_bytes;
+    let pending = server_app.world().resource::<ClientPendingMessages>();
+    let available_memory = pending
+        .available_memory(ClientId::new(0), event_channel)
+        .expect("connected client should be sampled");
+    assert!(
+        available_memory < max_bytes,
+        "unacknowledged event should occupy memory"
+    );
+    assert_eq!(pending.held_back(ClientId::new(0), event_channel), Some(0));
+
+    let pending = client_apps[0].world().resource::<PendingMessages>();
+    assert!(pending.available_memory(0).is_some());
+}
+
+#[test]
+fn channel_remap() {
+    // Simulate a newer server version that registers a new event before the old one.
+    let mut server_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .add_server_event::<NewEvent>(ChannelKind::Ordered)
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered)
+        .add_client_event::<DummyClientEvent>(ChannelKind::Ordered)
+        .insert_resource(
+            ChannelRemap::default()
+                .with_server_channel(2, 3)
+                .with_server_channel(3, 2),
+        );
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+
+    update(&mut server_app, &mut client_apps);
+
+    let dummy_events = client_apps[0].world().resource::<Events<DummyEvent>>();
+    assert_eq!(dummy_events.len(), 1);
+}
+
+#[test]
+fn handshake() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let control_channel = ControlChannel::after(channels);
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    server_channels_config.push(control_channel.config());
+    client_channels_config.push(control_channel.config());
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    for app in [&mut server_app, &mut client_apps[0]] {
+        app.insert_resource(control_channel);
+    }
+    server_app.insert_resource(HandshakeMetadata(b"server".as_slice().into()));
+    client_apps[0].insert_resource(HandshakeMetadata(b"client".as_slice().into()));
+
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert!(
+        connected_clients.is_empty(),
+        "client should be deferred until handshake"
+    );
+
+    exchange_packets(&mut server_app, &mut client_apps);
+    client_apps[0].update();
+    exchange_packets(&mut server_app, &mut client_apps);
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 1);
+    let client_metadata = server_app.world().resource::<ClientMetadata>();
+    assert_eq!(
+        client_metadata
+            .get(ClientId::new(0))
+            .map(|bytes| &bytes[..]),
+        Some(b"client".as_slice())
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    let server_metadata = client_apps[0].world().resource::<ServerMetadata>();
+    assert_eq!(
+        server_metadata.get().map(|bytes| &bytes[..]),
+        Some(b"server".as_slice())
+    );
+}
+
+#[test]
+fn server_bandwidth() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..2).map(|_| create_app()).collect();
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    for _ in 0..3 {
+        update(&mut server_app, &mut client_apps);
+    }
+
+    let bandwidth = *server_app.world().resource::<ServerBandwidth>();
+    assert!(bandwidth.sent_bps > 0.0);
+    assert!(bandwidth.received_bps > 0.0);
+}
+
+#[test]
+fn client_network_info() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    for _ in 0..3 {
+        update(&mut server_app, &mut client_apps);
+    }
+
+    let network_info = *client_apps[0].world().resource::<ClientNetworkInfo>();
+    assert!(network_info.bytes_received_per_second > 0.0);
+
+    let replicon_client = client_apps[0].world().resource::<RepliconClient>();
+    assert_eq!(replicon_client.rtt(), network_info.rtt);
+    assert_eq!(
+        replicon_client.received_bps(),
+        network_info.bytes_received_per_second
+    );
+}
+
+const CLIENTS_COUNT: usize = 64;
+
+#[test]
+fn idle_timeout() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..2).map(|_| create_app()).collect();
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+    update(&mut server_app, &mut client_apps);
+
+    let last_received = server_app.world().resource::<LastReceived>();
+    let active_time = last_received
+        .get(ClientId::new(0))
+        .expect("active client should be tracked");
+    let idle_time = last_received
+        .get(ClientId::new(1))
+        .expect("idle client should be tracked since connection");
+    assert!(
+        active_time > idle_time,
+        "replication acknowledgements shouldn't count as activity"
+    );
+
+    server_app.insert_resource(IdleTimeout(Duration::ZERO));
+    server_app.update();
+
+    let mut idle_events = server_app
+        .world_mut()
+        .resource_mut::<Events<IdleDisconnect>>();
+    let client_ids: Vec<_> = idle_events.drain().map(|event| event.client_id).collect();
+    assert_eq!(client_ids.len(), 2);
+
+    let server = server_app.world().resource::<RenetServer>();
+    for client_id in client_ids {
+        assert!(!server.is_connected(client_id.get()));
+    }
+}
+
+#[test]
+fn tracked_send() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..2).map(|_| create_app()).collect();
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let control_channel = ControlChannel::after(channels);
+    let channel_id = *control_channel + 1;
+    let config = ChannelConfig {
+        channel_id,
+        max_memory_usage_bytes: 64 * 1024,
+        send_type: SendType::ReliableOrdered {
+            resend_time: Duration::from_millis(300),
+        },
+    };
+    server_channels_config.extend([control_channel.config(), config.clone()]);
+    client_channels_config.extend([control_channel.config(), config]);
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    for app in std::iter::once(&mut server_app).chain(&mut client_apps) {
+        app.insert_resource(control_channel)
+            .insert_resource(ReliableSendChannel(channel_id));
+    }
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut reliable_state = SystemState::<ReliableSend>::new(server_app.world_mut());
+    let acked_handle = reliable_state
+        .get_mut(server_app.world_mut())
+        .send_tracked(ClientId::new(0), [0]);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut reliable_send = reliable_state.get_mut(server_app.world_mut());
+    assert_eq!(reliable_send.poll(acked_handle), Some(MessageStatus::Acked));
+    assert_eq!(
+        reliable_send.poll(acked_handle),
+        None,
+        "resolved handle should be forgotten after polling"
+    );
+
+    let lost_handle = reliable_send.send_tracked(ClientId::new(1), [1]);
+    assert_eq!(reliable_send.poll(lost_handle), Some(MessageStatus::Queued));
+
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect(1);
+    server_app.update();
+
+    let mut reliable_send = reliable_state.get_mut(server_app.world_mut());
+    assert_eq!(reliable_send.poll(lost_handle), Some(MessageStatus::Lost));
+}
+
+#[test]
+fn manual_flush() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+    client_apps[0].insert_resource(ManualFlush);
+
+    update(&mut server_app, &mut client_apps);
+
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+    update(&mut server_app, &mut client_apps);
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert!(client_events.is_empty(), "messages should wait for a flush");
+
+    client_apps[0]
+        .world_mut()
+        .run_system_once(flush_client_messages)
+        .unwrap();
+    exchange_packets(&mut server_app, &mut client_apps);
+    server_app.update();
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert_eq!(client_events.len(), 1);
+}
+
+#[test]
+fn broadcast_shared() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..CLIENTS_COUNT).map(|_| create_app()).collect();
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let channel_id = server_channels_config.len() as u8;
+    let config = ChannelConfig {
+        channel_id,
+        max_memory_usage_bytes: 64 * 1024,
+        send_type: SendType::ReliableOrdered {
+            resend_time: Duration::from_millis(300),
+        },
+    };
+    server_channels_config.push(config.clone());
+    client_channels_config.push(config);
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    let payload: Arc<[u8]> = vec![1; 1024].into();
+    let mut messaging = SystemState::<ServerMessaging>::new(server_app.world_mut());
+    messaging
+        .get_mut(server_app.world_mut())
+        .broadcast_shared(channel_id, payload.clone());
+    assert_eq!(
+        Arc::strong_count(&payload),
+        2,
+        "all clients should share a single payload"
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    for client_app in &mut client_apps {
+        let mut client = client_app.world_mut().resource_mut::<RenetClient>();
+        let message = client.receive_message(channel_id).unwrap();
+        assert_eq!(*message, *payload);
+    }
+}
+
+#[test]
+fn server_capacity() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..2).map(|_| create_app()).collect();
+
+    setup_local(&mut server_app, &mut client_apps);
+    server_app.insert_resource(MaxClients(2));
+
+    update(&mut server_app, &mut client_apps);
+
+    assert!(server_app.world_mut().run_system_once(server_full).unwrap());
+    let full_events = server_app.world().resource::<Events<ServerFull>>();
+    assert_eq!(full_events.len(), 1);
+
+    let mut client = client_apps[1].world_mut().resource_mut::<RenetClient>();
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect_local_client(1, &mut client);
+    server_app.update();
+
+    assert!(!server_app.world_mut().run_system_once(server_full).unwrap());
+    let capacity_events = server_app.world().resource::<Events<ServerHasCapacity>>();
+    assert_eq!(capacity_events.len(), 1);
+}
+
+#[test]
+fn channel_rate_limit() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let event_channel = channels.server_channels().len() as u8 - 1;
+    setup_local(&mut server_app, &mut client_apps);
+    server_app.insert_resource(ChannelRateLimit::default().with_channel(event_channel, 1));
+
+    update(&mut server_app, &mut client_apps);
+
+    for _ in 0..3 {
+        server_app.world_mut().send_event(ToClients {
+            mode: SendMode::Broadcast,
+            event: DummyEvent,
+        });
+    }
+    server_app.update();
+
+    let rate_limit = server_app.world().resource::<ChannelRateLimit>();
+    assert_eq!(
+        rate_limit.deferred_messages(ClientId::new(0), event_channel),
+        2,
+        "only the first event should fit into the budget"
+    );
+
+    exchange_packets(&mut server_app, &mut client_apps);
+    client_apps[0].update();
+
+    let events = client_apps[0].world().resource::<Events<DummyEvent>>();
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn channel_report() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let event_channel = channels.server_channels().len() as u8 - 1;
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+    server_app.update();
+    server_app.update();
+
+    let mut report = SystemState::<ServerChannelReport>::new(server_app.world_mut());
+    let server_report = report
+        .get(server_app.world())
+        .channel_report(ClientId::new(0));
+    let channel = server_report
+        .iter()
+        .find(|channel| {
+            channel.direction == ChannelDirection::Send && channel.channel_id == event_channel
+        })
+        .unwrap();
+    assert!(channel.throughput > 0);
+    assert!(
+        channel.used_bytes.unwrap() > 0,
+        "unacknowledged event should occupy memory"
+    );
+
+    exchange_packets(&mut server_app, &mut client_apps);
+    client_apps[0].update();
+    client_apps[0].update();
+
+    let mut report = SystemState::<ClientChannelReport>::new(client_apps[0].world_mut());
+    let client_report = report.get(client_apps[0].world()).channel_report();
+    let channel = client_report
+        .iter()
+        .find(|channel| {
+            channel.direction == ChannelDirection::Receive && channel.channel_id == event_channel
+        })
+        .unwrap();
+    assert!(channel.throughput > 0);
+    assert_eq!(channel.used_bytes, None);
+}
+
+#[test]
+fn channel_group() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let server_channels_config = channels.get_server_configs_for(ChannelGroup::Replication);
+    let client_channels_config = channels.get_client_configs_for(ChannelGroup::Replication);
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    for app in [&mut server_app, &mut client_apps[0]] {
+        app.insert_resource(ChannelGroup::Replication);
+    }
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut components = client_apps[0]
+        .world_mut()
+        .query_filtered::<(), With<DummyComponent>>();
+    assert_eq!(components.iter(client_apps[0].world()).count(), 1);
+
+    let server_events = client_apps[0].world().resource::<Events<DummyEvent>>();
+    assert!(
+        server_events.is_empty(),
+        "events outside of the group should be discarded"
+    );
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert!(client_events.is_empty());
+}
+
+#[test]
+fn unknown_channel() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let unknown_channel = server_channels_config
+        .len()
+        .max(client_channels_config.len()) as u8;
+    let config = ChannelConfig {
+        channel_id: unknown_channel,
+        max_memory_usage_bytes: 64 * 1024,
+        send_type: SendType::ReliableOrdered {
+            resend_time: Duration::from_millis(300),
+        },
+    };
+    server_channels_config.push(config.clone());
+    client_channels_config.push(config);
+
+    let policy =
+        UnknownChannelPolicy::new(UnknownChannelAction::Disconnect, &client_channels_config);
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    server_app.insert_resource(policy);
+
+    update(&mut server_app, &mut client_apps);
+
+    let server = server_app.world().resource::<RenetServer>();
+    assert!(server.is_connected(0));
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    client.send_message(unknown_channel, vec![0]);
+
+    update(&mut server_app, &mut client_apps);
+
+    let server = server_app.world().resource::<RenetServer>();
+    assert!(!server.is_connected(0));
+}
+
+#[test]
+fn send_on_fixed_tick() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+    server_app.insert_resource(Time::<Fixed>::from_seconds(3600.0));
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.insert_resource(SendOnFixedTick);
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+    server_app.update();
+
+    let activity = server_app.world().resource::<NetworkActivity>();
+    assert!(
+        !activity.sent_this_frame,
+        "messages shouldn't be sent without a fixed tick"
+    );
+
+    server_app.world_mut().run_schedule(FixedUpdate);
+    server_app.update();
+
+    let activity = server_app.world().resource::<NetworkActivity>();
+    assert!(activity.sent_this_frame);
+
+    exchange_packets(&mut server_app, &mut client_apps);
+    client_apps[0].update();
+
+    let events = client_apps[0].world().resource::<Events<DummyEvent>>();
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn redirect() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let control_channel = ControlChannel::after(channels);
+    server_channels_config.push(control_channel.config());
+    client_channels_config.push(control_channel.config());
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    client_apps[0]
+        .insert_resource(control_channel)
+        .insert_resource(RedirectConnector::new(|world, addr, _| {
+            world.insert_resource(NewServer(addr));
+        }));
+    server_app.insert_resource(control_channel);
+
+    update(&mut server_app, &mut client_apps);
+
+    let addr = "127.0.0.1:5000".parse().unwrap();
+    server_app.world_mut().send_event(RedirectClient {
+        client_id: ClientId::new(0),
+        addr,
+    });
+
+    update(&mut server_app, &mut client_apps);
+
+    let events = client_apps[0]
+        .world()
+        .resource::<Events<ClientRedirected>>();
+    assert_eq!(events.len(), 1);
+    let client = client_apps[0].world().resource::<RenetClient>();
+    assert!(client.is_disconnected());
+    assert!(!client_apps[0].world().contains_resource::<NewServer>());
+
+    client_apps[0].update();
+
+    let client = client_apps[0].world().resource::<RepliconClient>();
+    assert!(client.is_disconnected());
+    let new_server = client_apps[0].world().resource::<NewServer>();
+    assert_eq!(new_server.0, addr);
+}
+
+#[test]
+fn handshake_extension() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app(), create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let control_channel = ControlChannel::after(channels);
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    server_channels_config.push(control_channel.config());
+    client_channels_config.push(control_channel.config());
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    server_app
+        .world_mut()
+        .resource_mut::<HandshakeExtensions>()
+        .add(ProtocolVersion(1));
+    for (version, client_app) in client_apps.iter_mut().enumerate() {
+        client_app
+            .world_mut()
+            .resource_mut::<HandshakeExtensions>()
+            .add(ProtocolVersion(version as u8 + 1));
+    }
+    for app in std::iter::once(&mut server_app).chain(&mut client_apps) {
+        app.insert_resource(control_channel)
+            .init_resource::<HandshakeMetadata>();
+    }
+
+    update(&mut server_app, &mut client_apps);
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 1);
+    let mut rejected_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ClientRejected>>();
+    let rejected = rejected_events
+        .drain()
+        .next()
+        .expect("client with a different version should be rejected");
+    assert_eq!(rejected.client_id, ClientId::new(1));
+    assert_eq!(rejected.reason, RejectReason::Handshake);
+    let server = server_app.world().resource::<RenetServer>();
+    assert!(!server.is_connected(1));
+
+    update(&mut server_app, &mut client_apps);
+
+    let server_metadata = client_apps[0].world().resource::<ServerMetadata>();
+    assert!(server_metadata.get().is_some());
+}
+
+#[test]
+fn flush_priority() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..2).map(|_| create_app()).collect();
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut flush_priority = server_app.world_mut().resource_mut::<FlushPriority>();
+    flush_priority.set(ClientId::new(1), 1);
+
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+
+    update(&mut server_app, &mut client_apps);
+
+    for client_app in &client_apps {
+        let dummy_events = client_app.world().resource::<Events<DummyEvent>>();
+        assert_eq!(dummy_events.len(), 1, "priority shouldn't affect delivery");
+    }
+
+    let mut client = client_apps[1].world_mut().resource_mut::<RenetClient>();
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect_local_client(1, &mut client);
+    server_app.update();
+
+    let flush_priority = server_app.world().resource::<FlushPriority>();
+    assert_eq!(flush_priority.get(ClientId::new(1)), 0);
+}
+
+#[test]
+fn graceful_shutdown() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    let channel_id = server_channels_config
+        .len()
+        .max(client_channels_config.len()) as u8;
+    let config = ChannelConfig {
+        channel_id,
+        max_memory_usage_bytes: 64 * 1024,
+        send_type: SendType::ReliableOrdered {
+            resend_time: Duration::from_millis(300),
+        },
+    };
+    server_channels_config.push(config.clone());
+    client_channels_config.push(config);
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    server_app.insert_resource(
+        GracefulShutdown::new(Duration::from_secs(60)).with_message(channel_id, vec![1]),
+    );
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().send_event(AppExit::from_code(2));
+    server_app.update();
+
+    assert!(server_app.should_exit().is_none());
+    assert!(server_app.world().contains_resource::<MaintenanceMode>());
+
+    exchange_packets(&mut server_app, &mut client_apps);
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    let message = client
+        .receive_message(channel_id)
+        .expect("client should receive the shutdown message");
+    assert_eq!(message.as_ref(), [1]);
+
+    // Leaving before the grace period ends completes the shutdown early.
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect_local_client(0, &mut client);
+    server_app.update();
+
+    assert!(server_app.should_exit().is_none());
+    let mut report_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ShutdownReport>>();
+    let reports: Vec<_> = report_events.drain().collect();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].is_clean());
+    assert!(
+        reports[0].messages_flushed >= 1,
+        "should include the broadcasted message"
+    );
+
+    server_app.update();
+
+    assert_eq!(server_app.should_exit(), Some(AppExit::from_code(2)));
+}
+
+#[test]
+fn client_id_map() {
+    let mut server_app = create_app();
+    let mut client_apps: Vec<_> = (0..2).map(|_| create_app()).collect();
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut map_state = SystemState::<ClientIdMap>::new(server_app.world_mut());
+    let map = map_state.get(server_app.world());
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.renet_id(ClientId::new(1)), Some(1));
+    assert_eq!(map.client_id(1), Some(ClientId::new(1)));
+    assert_eq!(map.client_id(2), None);
+    assert!(map.mismatches().is_empty());
+
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    server.remove_connection(1);
+    server.add_connection(2);
+
+    let map = map_state.get(server_app.world());
+    assert_eq!(
+        map.mismatches(),
+        [
+            ClientIdMismatch::MissingInRenet(ClientId::new(1)),
+            ClientIdMismatch::MissingInReplicon(2)
+        ]
+    );
+}
+
+#[test]
+fn disconnected_clients() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+    server_app.init_resource::<DisconnectedClients>();
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect_local_client(0, &mut client);
+    server_app.update();
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert!(connected_clients.is_empty());
+
+    let disconnected_clients = server_app.world().resource::<DisconnectedClients>();
+    let disconnected = disconnected_clients
+        .get(ClientId::new(0))
+        .expect("snapshot should be kept for a frame");
+    assert_eq!(
+        disconnected.reason,
+        RepliconDisconnectReason::ClientRequested
+    );
+
+    server_app.update();
+
+    let disconnected_clients = server_app.world().resource::<DisconnectedClients>();
+    assert!(disconnected_clients.is_empty());
+}
+
+#[test]
+fn connection_stats() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app(), create_app()];
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    let stats = server_app.world().resource::<ConnectionStats>();
+    assert_eq!(stats.accepted(), 2);
+    assert_eq!(stats.current(), 2);
+
+    let mut client = client_apps[0].world_mut().resource_mut::<RenetClient>();
+    server_app
+        .world_mut()
+        .resource_mut::<RenetServer>()
+        .disconnect_local_client(0, &mut client);
+    server_app.update();
+
+    let stats = server_app.world().resource::<ConnectionStats>();
+    assert_eq!(stats.current(), 1);
+    assert_eq!(
+        stats.disconnects_by(RepliconDisconnectReason::ClientRequested),
+        1
+    );
+
+    server_app.world_mut().remove_resource::<RenetServer>();
+    server_app.update();
+
+    let stats = server_app.world().resource::<ConnectionStats>();
+    assert_eq!(stats.accepted(), 2);
+    assert_eq!(stats.disconnects(), 2);
+    assert_eq!(stats.current(), 0);
+    assert_eq!(
+        stats.disconnects_by(RepliconDisconnectReason::ServerShutdown),
+        1
+    );
+}
+
+#[test]
+fn kick() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let control_channel = ControlChannel::after(channels);
+    let mut server_channels_config = channels.get_server_configs();
+    let mut client_channels_config = channels.get_client_configs();
+    server_channels_config.push(control_channel.config());
+    client_channels_config.push(control_channel.config());
+
+    setup_local_with(
+        &mut server_app,
+        &mut client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+    client_apps[0].insert_resource(control_channel);
+    server_app.insert_resource(control_channel);
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().send_event(KickClient {
+        client_id: ClientId::new(0),
+        reason: Bytes::from_static(b"cheating"),
+    });
+
+    update(&mut server_app, &mut client_apps);
+
+    let mut kicked_events = client_apps[0]
+        .world_mut()
+        .resource_mut::<Events<ClientKicked>>();
+    let reasons: Vec<_> = kicked_events.drain().map(|event| event.reason).collect();
+    assert_eq!(reasons, [Bytes::from_static(b"cheating")]);
+
+    let server = server_app.world().resource::<RenetServer>();
+    assert!(
+        !server.is_connected(0),
+        "client should be disconnected after acknowledging the reason"
+    );
+}
+
+#[test]
+fn background_forwarding() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+    for app in std::iter::once(&mut server_app).chain(&mut client_apps) {
+        app.world_mut()
+            .resource_mut::<MessageTransforms>()
+            .add_outgoing(OutgoingSequenceTags::default())
+            .add_incoming(IncomingSequenceTags::default());
+    }
+    server_app.insert_resource(ParallelForwarding::default().with_background(true));
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+    client_apps[0].world_mut().send_event(DummyClientEvent);
+
+    server_app.update();
+    exchange_packets(&mut server_app, &mut client_apps);
+    client_apps[0].update();
+    exchange_packets(&mut server_app, &mut client_apps);
+
+    let dummy_events = client_apps[0].world().resource::<Events<DummyEvent>>();
+    assert!(
+        dummy_events.is_empty(),
+        "server messages should be sent a frame later"
+    );
+
+    server_app.update();
+    exchange_packets(&mut server_app, &mut client_apps);
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert!(
+        client_events.is_empty(),
+        "client messages should be received a frame later"
+    );
+
+    client_apps[0].update();
+    server_app.update();
+
+    let dummy_events = client_apps[0].world().resource::<Events<DummyEvent>>();
+    assert_eq!(dummy_events.len(), 1);
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyClientEvent>>>();
+    assert_eq!(client_events.len(), 1);
+}
+
+#[test]
+fn client_setup_set() {
+    let mut server_app = create_app();
+    let mut client_apps = [create_app()];
+
+    server_app
+        .init_resource::<SetUpClients>()
+        .add_systems(PreUpdate, spawn_players.in_set(ClientSetupSet));
+
+    setup_local(&mut server_app, &mut client_apps);
+
+    update(&mut server_app, &mut client_apps);
+
+    let set_up_clients = server_app.world().resource::<SetUpClients>();
+    assert_eq!(set_up_clients.0, [ClientId::new(0)]);
+
+    let mut components = client_apps[0]
+        .world_mut()
+        .query_filtered::<(), With<DummyComponent>>();
+    assert_eq!(
+        components.iter(client_apps[0].world()).count(),
+        1,
+        "entity should be included in the first replication message"
+    );
+}
+
+fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        RepliconPlugins.set(ServerPlugin {
+            tick_policy: TickPolicy::EveryFrame,
+            ..Default::default()
+        }),
+        RepliconRenetPlugins,
+    ))
+    .replicate::<DummyComponent>()
+    .add_server_event::<DummyEvent>(ChannelKind::Ordered)
+    .add_client_event::<DummyClientEvent>(ChannelKind::Ordered);
+
+    app
+}
+
+/// Inserts a server and a local client for each client app with the default channel configs.
+///
+/// Client IDs match indices of the client apps.
+fn setup_local(server_app: &mut App, client_apps: &mut [App]) {
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let configured = ConfiguredChannels::new(channels);
+    let server_channels_config = channels.get_server_configs();
+    let client_channels_config = channels.get_client_configs();
+    server_app.insert_resource(configured);
+    for client_app in &mut *client_apps {
+        client_app.insert_resource(configured);
+    }
+    setup_local_with(
+        server_app,
+        client_apps,
+        server_channels_config,
+        client_channels_config,
+    );
+}
+
+/// Like [`setup_local`], but with custom channel configs.
+fn setup_local_with(
+    server_app: &mut App,
+    client_apps: &mut [App],
+    server_channels_config: Vec<ChannelConfig>,
+    client_channels_config: Vec<ChannelConfig>,
+) {
+    let mut server = RenetServer::new(ConnectionConfig {
+        server_channels_config,
+        client_channels_config,
+        ..Default::default()
+    });
+    for (client_id, client_app) in client_apps.iter_mut().enumerate() {
+        let client = server.new_local_client(client_id as u64);
+        client_app.insert_resource(client);
+    }
+    server_app.insert_resource(server);
+}
+
+/// Updates the server and all clients, passing packets between them in both directions.
+fn update(server_app: &mut App, client_apps: &mut [App]) {
+    server_app.update();
+    exchange_packets(server_app, client_apps);
+    for client_app in &mut *client_apps {
+        client_app.update();
+    }
+    exchange_packets(server_app, client_apps);
+    server_app.update();
+}
+
+fn exchange_packets(server_app: &mut App, client_apps: &mut [App]) {
+    let mut server = server_app.world_mut().resource_mut::<RenetServer>();
+    for (client_id, client_app) in client_apps.iter_mut().enumerate() {
+        let mut client = client_app.world_mut().resource_mut::<RenetClient>();
+        server
+            .process_local_client(client_id as u64, &mut client)
+            .expect("local client should be connected");
+    }
+}
+
+#[derive(Resource)]
+struct NewServer(SocketAddr);
+
+/// Accepts only peers with the same version.
+struct ProtocolVersion(u8);
+
+impl HandshakeExtension for ProtocolVersion {
+    fn write(&self) -> Vec<u8> {
+        vec![self.0]
+    }
+
+    fn read(&self, _client_id: ClientId, data: &[u8]) -> bool {
+        data == [self.0]
+    }
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent;
+
+#[derive(Deserialize, Event, Serialize)]
+struct DummyEvent;
+
+#[derive(Deserialize, Event, Serialize)]
+struct DummyClientEvent;
+
+#[derive(Deserialize, Event, Serialize)]
+struct NewEvent;
+
+/// Clients for which a player was spawned before they were added to [`ReplicatedClients`].
+#[derive(Resource, Default)]
+struct SetUpClients(Vec<ClientId>);
+
+fn spawn_players(
+    mut commands: Commands,
+    mut server_events: EventReader<ServerEvent>,
+    replicated_clients: Res<ReplicatedClients>,
+    mut set_up_clients: ResMut<SetUpClients>,
+) {
+    for event in server_events.read() {
+        if let ServerEvent::ClientConnected { client_id } = *event {
+            commands.spawn((Replicated, DummyComponent));
+            if replicated_clients.get_client(client_id).is_none() {
+                set_up_clients.0.push(client_id);
+            }
+        }
+    }
+}
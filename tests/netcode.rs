@@ -1,18 +1,28 @@
 use std::{
+    io,
     net::{Ipv4Addr, SocketAddr, UdpSocket},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
-use bevy::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
 use bevy_renet::{
     netcode::{
-        ClientAuthentication, NetcodeClientTransport, NetcodeServerTransport, ServerAuthentication,
-        ServerConfig,
+        ClientAuthentication, NetcodeClientTransport, NetcodeDisconnectReason, NetcodeError,
+        NetcodeServerTransport, NetcodeTransportError, ServerAuthentication, ServerConfig,
+        NETCODE_USER_DATA_BYTES,
     },
     renet::{ConnectionConfig, RenetClient, RenetServer},
 };
 use bevy_replicon::prelude::*;
-use bevy_replicon_renet::{RenetChannelsExt, RepliconRenetPlugins};
+use bevy_replicon_renet::{
+    connect_now, netcode_transport_active, AssignedClientId, AuthorizeByUserData, ClientAddrs,
+    ClientCleanupSet, ClientDisconnected, ClientGraceExpired, ClientReconnected, ClientRejected,
+    ClientTransport, ClientTransportError, ClientTransports, DisconnectGrace, LobbyMode,
+    MultiSocketServerTransport, NetworkActivity, ReconnectingClients, RejectReason,
+    RenetChannelsExt, RepliconDisconnectReason, RepliconRenetPlugins, SelectionStrategy,
+    ServerCandidates, ServerProbeFailed, ServerSelected, ServerTransportError, StatusHistory,
+    StrictSend,
+};
 use serde::{Deserialize, Serialize};
 
 #[test]
@@ -67,6 +77,429 @@ fn connect_disconnect() {
     assert!(!server_app.world().resource::<RepliconServer>().is_running());
 }
 
+#[test]
+fn assigned_client_id() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    const ASSIGNED_ID: ClientId = ClientId::new(42);
+    let mut assigned_id = client_app.world_mut().resource_mut::<AssignedClientId>();
+    assigned_id.set(ASSIGNED_ID);
+    assigned_id.set(ClientId::new(43));
+
+    client_app.update();
+
+    let client = client_app.world().resource::<RepliconClient>();
+    assert_eq!(client.id(), Some(ASSIGNED_ID));
+    let assigned_id = client_app.world().resource::<AssignedClientId>();
+    assert_eq!(assigned_id.transport_id(), Some(ClientId::new(CLIENT_ID)));
+
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+
+    client_app.update();
+
+    let assigned_id = client_app.world().resource::<AssignedClientId>();
+    assert!(assigned_id.get().is_none());
+}
+
+#[test]
+fn transport_condition() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+        assert!(!app
+            .world_mut()
+            .run_system_once(netcode_transport_active)
+            .unwrap());
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    for app in [&mut server_app, &mut client_app] {
+        assert!(app
+            .world_mut()
+            .run_system_once(netcode_transport_active)
+            .unwrap());
+    }
+}
+
+#[test]
+fn client_addrs() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    let client_addrs = server_app.world().resource::<ClientAddrs>();
+    let addr = client_addrs
+        .get(ClientId::new(CLIENT_ID))
+        .expect("address should be known after connection");
+    assert!(addr.ip().is_loopback());
+
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+
+    client_app.update();
+    server_app.update();
+
+    let client_addrs = server_app.world().resource::<ClientAddrs>();
+    assert!(client_addrs.get(ClientId::new(CLIENT_ID)).is_none());
+}
+
+#[test]
+fn client_transport() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    let client_transports = server_app.world().resource::<ClientTransports>();
+    assert_eq!(
+        client_transports.get(ClientId::new(CLIENT_ID)),
+        Some(ClientTransport::Netcode)
+    );
+}
+
+#[test]
+fn multi_socket() {
+    let mut server_app = App::new();
+    let mut client_apps = [App::new(), App::new()];
+    for app in std::iter::once(&mut server_app).chain(&mut client_apps) {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let server = RenetServer::new(ConnectionConfig {
+        server_channels_config: channels.get_server_configs(),
+        client_channels_config: channels.get_client_configs(),
+        ..Default::default()
+    });
+    let sockets: Vec<_> = (0..2)
+        .map(|_| UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("localhost should be bindable"))
+        .collect();
+    let public_addresses = sockets
+        .iter()
+        .map(|socket| socket.local_addr().unwrap())
+        .collect();
+    let server_config = ServerConfig {
+        current_time: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap(),
+        max_clients: 2,
+        protocol_id: PROTOCOL_ID,
+        public_addresses,
+        authentication: ServerAuthentication::Unsecure,
+    };
+    let transport = MultiSocketServerTransport::new(server_config, sockets).unwrap();
+    let ports: Vec<_> = transport
+        .local_addrs()
+        .unwrap()
+        .iter()
+        .map(|addr| addr.port())
+        .collect();
+    server_app
+        .insert_resource(server)
+        .insert_resource(transport);
+
+    for (client_id, (client_app, &port)) in client_apps.iter_mut().zip(&ports).enumerate() {
+        setup_client(client_app, client_id as u64, port, None);
+    }
+
+    for (client_id, client_app) in client_apps.iter_mut().enumerate() {
+        connect_now(
+            &mut server_app,
+            client_app,
+            client_id as u64,
+            MAX_CONNECT_TICKS,
+        );
+    }
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert_eq!(connected_clients.len(), 2);
+
+    let transport = server_app.world().resource::<MultiSocketServerTransport>();
+    for (client_id, &port) in ports.iter().enumerate() {
+        let local_addr = transport.client_local_addr(client_id as u64).unwrap();
+        assert_eq!(local_addr.port(), port);
+    }
+}
+
+#[test]
+fn server_transport_error() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+
+    setup_server(&mut app, 1);
+
+    app.world_mut()
+        .send_event(NetcodeTransportError::IO(io::Error::other(
+            "interface is down",
+        )));
+    app.world_mut()
+        .send_event(NetcodeTransportError::IO(io::ErrorKind::TimedOut.into()));
+
+    app.update();
+
+    let errors = app.world().resource::<Events<ServerTransportError>>();
+    assert_eq!(errors.len(), 1, "transient errors should be ignored");
+}
+
+#[test]
+fn client_transport_error() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    let port = setup_server(&mut server_app, 1);
+    setup_client(&mut client_app, CLIENT_ID, port, None);
+
+    client_app
+        .world_mut()
+        .send_event(NetcodeTransportError::IO(io::Error::other(
+            "interface is down",
+        )));
+    client_app
+        .world_mut()
+        .send_event(NetcodeTransportError::IO(io::ErrorKind::TimedOut.into()));
+    client_app
+        .world_mut()
+        .send_event(NetcodeTransportError::Netcode(NetcodeError::Disconnected(
+            NetcodeDisconnectReason::DisconnectedByServer,
+        )));
+
+    client_app.update();
+
+    let errors = client_app
+        .world()
+        .resource::<Events<ClientTransportError>>();
+    assert_eq!(
+        errors.len(),
+        1,
+        "transient and disconnect errors should be ignored"
+    );
+}
+
+#[test]
+fn authorize_by_user_data() {
+    const AUTHORIZED_ID: u64 = 1;
+    const UNAUTHORIZED_ID: u64 = 2;
+    const INVALID_TOKEN: u16 = 1;
+
+    let mut server_app = App::new();
+    let mut client_apps = [App::new(), App::new()];
+    for app in std::iter::once(&mut server_app).chain(&mut client_apps) {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+    server_app
+        .init_resource::<RejectedClients>()
+        .insert_resource(AuthorizeByUserData::new(|_client_id, user_data| {
+            if user_data.starts_with(b"token") {
+                Ok(())
+            } else {
+                Err(INVALID_TOKEN)
+            }
+        }))
+        .add_systems(Update, collect_rejections);
+
+    let port = setup_server(&mut server_app, 2);
+    let mut user_data = [0; NETCODE_USER_DATA_BYTES];
+    user_data[..5].copy_from_slice(b"token");
+    setup_client(&mut client_apps[0], AUTHORIZED_ID, port, Some(user_data));
+    setup_client(&mut client_apps[1], UNAUTHORIZED_ID, port, None);
+
+    loop {
+        for client_app in &mut client_apps {
+            client_app.update();
+        }
+        server_app.update();
+        let connected_clients = server_app.world().resource::<ConnectedClients>();
+        let rejected_clients = server_app.world().resource::<RejectedClients>();
+        if !connected_clients.is_empty() && !rejected_clients.0.is_empty() {
+            break;
+        }
+    }
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    let connected_ids: Vec<_> = connected_clients.iter().map(|client| client.id()).collect();
+    assert_eq!(connected_ids, [ClientId::new(AUTHORIZED_ID)]);
+
+    let rejected_clients = server_app.world().resource::<RejectedClients>();
+    assert_eq!(
+        rejected_clients.0,
+        [ClientRejected {
+            client_id: ClientId::new(UNAUTHORIZED_ID),
+            reason: RejectReason::Unauthorized(Some(INVALID_TOKEN)),
+        }]
+    );
+}
+
+#[test]
+fn disconnect_reason() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+
+    client_app.update();
+    server_app.update();
+
+    let history = client_app.world().resource::<StatusHistory>();
+    let transition = history.iter().last().unwrap();
+    assert_eq!(
+        transition.disconnect_reason,
+        Some(RepliconDisconnectReason::ClientRequested)
+    );
+
+    let mut disconnect_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ClientDisconnected>>();
+    let event = disconnect_events.drain().next().unwrap();
+    assert_eq!(
+        event.reason,
+        RepliconDisconnectReason::TransportError,
+        "netcode server doesn't report why the connection was closed"
+    );
+}
+
+#[test]
+fn server_shutdown() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    server_app.world_mut().remove_resource::<RenetServer>();
+    server_app.update();
+
+    let mut disconnect_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ClientDisconnected>>();
+    let event = disconnect_events.drain().next().unwrap();
+    assert_eq!(event.client_id, ClientId::new(CLIENT_ID));
+    assert_eq!(event.reason, RepliconDisconnectReason::ServerShutdown);
+}
+
+#[test]
+fn client_cleanup() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    server_app
+        .init_resource::<CleanedClients>()
+        .add_systems(PreUpdate, cleanup_clients.in_set(ClientCleanupSet));
+
+    setup(&mut server_app, &mut client_app);
+
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+
+    client_app.update();
+    server_app.update();
+
+    let cleaned_clients = server_app.world().resource::<CleanedClients>();
+    assert_eq!(cleaned_clients.0, [ClientId::new(CLIENT_ID)]);
+
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert!(connected_clients.is_empty());
+}
+
+#[test]
+fn send_to_disconnected() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ));
+    }
+
+    setup(&mut server_app, &mut client_app);
+
+    // Simulate a message that was queued after the client disconnected in the same frame.
+    server_app.add_systems(Update, |mut replicon_server: ResMut<RepliconServer>| {
+        replicon_server.send(ClientId::new(CLIENT_ID), 0, vec![0]);
+    });
+
+    let mut renet_server = server_app.world_mut().resource_mut::<RenetServer>();
+    renet_server.disconnect(CLIENT_ID);
+
+    server_app.update();
+
+    let activity = server_app.world().resource::<NetworkActivity>();
+    assert!(!activity.sent_this_frame);
+}
+
+#[test]
+#[should_panic(expected = "should be sent only to connected clients")]
+fn strict_send_to_disconnected() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ));
+    }
+
+    server_app.init_resource::<StrictSend>();
+
+    setup(&mut server_app, &mut client_app);
+
+    // Simulate a message that was queued after the client disconnected in the same frame.
+    server_app.add_systems(Update, |mut replicon_server: ResMut<RepliconServer>| {
+        replicon_server.send(ClientId::new(CLIENT_ID), 0, vec![0]);
+    });
+
+    let mut renet_server = server_app.world_mut().resource_mut::<RenetServer>();
+    renet_server.disconnect(CLIENT_ID);
+
+    server_app.update();
+}
+
 #[test]
 fn replication() {
     let mut server_app = App::new();
@@ -92,6 +525,279 @@ fn replication() {
     assert_eq!(client_app.world().entities().len(), 1);
 }
 
+#[test]
+fn reconnect() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .replicate::<DummyComponent>()
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered);
+    }
+
+    let port = setup_server(&mut server_app, 1);
+    setup_client(&mut client_app, CLIENT_ID, port, None);
+    connect_now(
+        &mut server_app,
+        &mut client_app,
+        CLIENT_ID,
+        MAX_CONNECT_TICKS,
+    );
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+
+    server_app.update();
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+    client_app.update();
+    server_app.update();
+
+    assert!(client_app
+        .world()
+        .resource::<RepliconClient>()
+        .is_disconnected());
+    assert!(
+        client_app.world().entities().is_empty(),
+        "messages from the previous session shouldn't be applied"
+    );
+
+    setup_client(&mut client_app, CLIENT_ID, port, None);
+    connect_now(
+        &mut server_app,
+        &mut client_app,
+        CLIENT_ID,
+        MAX_CONNECT_TICKS,
+    );
+
+    server_app.update();
+    client_app.update();
+
+    assert_eq!(client_app.world().entities().len(), 1);
+
+    let dummy_events = client_app.world().resource::<Events<DummyEvent>>();
+    assert!(
+        dummy_events.is_empty(),
+        "events from the previous session shouldn't be received"
+    );
+}
+
+#[test]
+fn disconnect_grace() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    server_app
+        .insert_resource(DisconnectGrace(Duration::from_secs(60)))
+        .init_resource::<ReconnectedClients>()
+        .add_systems(Update, collect_reconnections);
+
+    let port = setup_server(&mut server_app, 1);
+    setup_client(&mut client_app, CLIENT_ID, port, None);
+    connect_now(
+        &mut server_app,
+        &mut client_app,
+        CLIENT_ID,
+        MAX_CONNECT_TICKS,
+    );
+
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+
+    client_app.update();
+    server_app.update();
+
+    let client_id = ClientId::new(CLIENT_ID);
+    let reconnecting = server_app.world().resource::<ReconnectingClients>();
+    assert!(reconnecting.contains(client_id));
+
+    setup_client(&mut client_app, CLIENT_ID, port, None);
+    connect_now(
+        &mut server_app,
+        &mut client_app,
+        CLIENT_ID,
+        MAX_CONNECT_TICKS,
+    );
+
+    let reconnecting = server_app.world().resource::<ReconnectingClients>();
+    assert!(reconnecting.is_empty());
+
+    let reconnected_clients = server_app.world().resource::<ReconnectedClients>();
+    assert_eq!(reconnected_clients.0, [client_id]);
+
+    server_app.insert_resource(DisconnectGrace(Duration::ZERO));
+    client_app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .disconnect();
+
+    client_app.update();
+    server_app.update();
+    server_app.update();
+
+    let reconnecting = server_app.world().resource::<ReconnectingClients>();
+    assert!(reconnecting.is_empty());
+
+    let mut expired_events = server_app
+        .world_mut()
+        .resource_mut::<Events<ClientGraceExpired>>();
+    assert_eq!(
+        expired_events.drain().collect::<Vec<_>>(),
+        [ClientGraceExpired { client_id }]
+    );
+}
+
+#[test]
+fn server_candidates() {
+    let mut server_apps = [App::new(), App::new()];
+    let mut client_app = App::new();
+    for app in server_apps.iter_mut().chain([&mut client_app]) {
+        app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+    }
+
+    let ports = server_apps.each_mut().map(|app| setup_server(app, 1));
+    let mut candidates = ServerCandidates::new(SelectionStrategy::LowestRtt);
+    for port in ports {
+        let (client, transport) = create_client(&client_app, port);
+        let addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
+        candidates = candidates.with_candidate(addr, client, transport);
+    }
+    client_app.insert_resource(candidates);
+
+    while client_app.world().contains_resource::<ServerCandidates>() {
+        client_app.update();
+        for server_app in &mut server_apps {
+            server_app.update();
+        }
+    }
+
+    let mut selected_events = client_app
+        .world_mut()
+        .resource_mut::<Events<ServerSelected>>();
+    let event = selected_events.drain().next().unwrap();
+    assert_eq!(event.probes.len(), 2);
+    assert!(event
+        .probes
+        .iter()
+        .all(|probe| probe.connect_time.is_some() && probe.rtt.is_some()));
+
+    let selected_port = event.addr.port();
+    let selected_index = ports
+        .iter()
+        .position(|&port| port == selected_port)
+        .unwrap();
+    for _ in 0..2 {
+        client_app.update();
+        for server_app in &mut server_apps {
+            server_app.update();
+        }
+    }
+
+    assert!(client_app.world().resource::<RenetClient>().is_connected());
+    for (index, server_app) in server_apps.iter().enumerate() {
+        let connected_clients = server_app.world().resource::<ConnectedClients>();
+        let expected = if index == selected_index { 1 } else { 0 };
+        assert_eq!(
+            connected_clients.len(),
+            expected,
+            "only the selected server should keep the client"
+        );
+    }
+}
+
+#[test]
+fn server_candidates_timeout() {
+    let mut client_app = App::new();
+    client_app.add_plugins((MinimalPlugins, RepliconPlugins, RepliconRenetPlugins));
+
+    // Nothing listens on this port since the socket is dropped.
+    let port = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let (client, transport) = create_client(&client_app, port);
+    let addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
+    client_app.insert_resource(
+        ServerCandidates::new(SelectionStrategy::FirstToRespond)
+            .with_probe_timeout(Duration::from_millis(100))
+            .with_candidate(addr, client, transport),
+    );
+
+    while client_app.world().contains_resource::<ServerCandidates>() {
+        client_app.update();
+    }
+
+    assert!(!client_app.world().contains_resource::<RenetClient>());
+    let mut failed_events = client_app
+        .world_mut()
+        .resource_mut::<Events<ServerProbeFailed>>();
+    let event = failed_events.drain().next().unwrap();
+    assert_eq!(event.probes.len(), 1);
+    assert_eq!(event.probes[0].connect_time, None);
+}
+
+#[test]
+fn lobby() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RepliconRenetPlugins,
+        ))
+        .replicate::<DummyComponent>()
+        .add_client_event::<DummyEvent>(ChannelKind::Ordered);
+    }
+
+    server_app.init_resource::<LobbyMode>();
+    setup(&mut server_app, &mut client_app);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    client_app.world_mut().send_event(DummyEvent);
+
+    client_app.update();
+    server_app.update();
+    client_app.update();
+
+    assert!(
+        client_app.world().entities().is_empty(),
+        "replication should be withheld in lobby"
+    );
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyEvent>>>();
+    assert_eq!(client_events.len(), 1);
+
+    server_app.world_mut().remove_resource::<LobbyMode>();
+
+    server_app.update();
+    client_app.update();
+
+    assert_eq!(client_app.world().entities().len(), 1);
+}
+
 #[test]
 fn server_event() {
     let mut server_app = App::new();
@@ -120,6 +826,9 @@ fn server_event() {
 
     let dummy_events = client_app.world().resource::<Events<DummyEvent>>();
     assert_eq!(dummy_events.len(), 1);
+
+    let activity = client_app.world().resource::<NetworkActivity>();
+    assert!(activity.received_this_frame);
 }
 
 #[test]
@@ -151,14 +860,25 @@ fn client_event() {
     assert_eq!(client_events.len(), 1);
 }
 
+const CLIENT_ID: u64 = 1;
+
+/// Netcode resends handshake packets immediately after each state change, so on loopback
+/// the connection is established in a few ticks. The limit turns a broken connection
+/// into a failure instead of a hang.
+const MAX_CONNECT_TICKS: usize = 1000;
+
 fn setup(server_app: &mut App, client_app: &mut App) {
-    const CLIENT_ID: u64 = 1;
     let port = setup_server(server_app, 1);
-    setup_client(client_app, CLIENT_ID, port);
-    wait_for_connection(server_app, client_app);
+    setup_client(client_app, CLIENT_ID, port, None);
+    connect_now(server_app, client_app, CLIENT_ID, MAX_CONNECT_TICKS);
 }
 
-fn setup_client(app: &mut App, client_id: u64, port: u16) {
+fn setup_client(
+    app: &mut App,
+    client_id: u64,
+    port: u16,
+    user_data: Option<[u8; NETCODE_USER_DATA_BYTES]>,
+) {
     let channels = app.world().resource::<RepliconChannels>();
 
     let server_channels_config = channels.get_server_configs();
@@ -169,11 +889,24 @@ fn setup_client(app: &mut App, client_id: u64, port: u16) {
         client_channels_config,
         ..Default::default()
     });
-    let transport = create_client_transport(client_id, port);
+    let transport = create_client_transport(client_id, port, user_data);
 
     app.insert_resource(client).insert_resource(transport);
 }
 
+fn create_client(app: &App, port: u16) -> (RenetClient, NetcodeClientTransport) {
+    let channels = app.world().resource::<RepliconChannels>();
+
+    let client = RenetClient::new(ConnectionConfig {
+        server_channels_config: channels.get_server_configs(),
+        client_channels_config: channels.get_client_configs(),
+        ..Default::default()
+    });
+    let transport = create_client_transport(CLIENT_ID, port, None);
+
+    (client, transport)
+}
+
 fn setup_server(app: &mut App, max_clients: usize) -> u16 {
     let channels = app.world().resource::<RepliconChannels>();
 
@@ -215,7 +948,11 @@ fn create_server_transport(max_clients: usize) -> NetcodeServerTransport {
     NetcodeServerTransport::new(server_config, socket).unwrap()
 }
 
-fn create_client_transport(client_id: u64, port: u16) -> NetcodeClientTransport {
+fn create_client_transport(
+    client_id: u64,
+    port: u16,
+    user_data: Option<[u8; NETCODE_USER_DATA_BYTES]>,
+) -> NetcodeClientTransport {
     let current_time = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap();
@@ -226,21 +963,57 @@ fn create_client_transport(client_id: u64, port: u16) -> NetcodeClientTransport
         client_id,
         protocol_id: PROTOCOL_ID,
         server_addr,
-        user_data: None,
+        user_data,
     };
 
     NetcodeClientTransport::new(current_time, authentication, socket).unwrap()
 }
 
-fn wait_for_connection(server_app: &mut App, client_app: &mut App) {
-    loop {
-        client_app.update();
-        server_app.update();
-        if client_app.world().resource::<RenetClient>().is_connected() {
-            break;
+#[derive(Deserialize, Event, Serialize)]
+struct DummyEvent;
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent;
+
+/// Disconnected clients that were present in [`ConnectedClients`] during cleanup.
+#[derive(Resource, Default)]
+struct CleanedClients(Vec<ClientId>);
+
+fn cleanup_clients(
+    mut server_events: EventReader<ServerEvent>,
+    connected_clients: Res<ConnectedClients>,
+    mut cleaned_clients: ResMut<CleanedClients>,
+) {
+    for event in server_events.read() {
+        if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+            if connected_clients
+                .iter()
+                .any(|client| client.id() == client_id)
+            {
+                cleaned_clients.0.push(client_id);
+            }
         }
     }
 }
 
-#[derive(Deserialize, Event, Serialize)]
-struct DummyEvent;
+#[derive(Resource, Default)]
+struct ReconnectedClients(Vec<ClientId>);
+
+fn collect_reconnections(
+    mut reconnect_events: EventReader<ClientReconnected>,
+    mut reconnected_clients: ResMut<ReconnectedClients>,
+) {
+    reconnected_clients
+        .0
+        .extend(reconnect_events.read().map(|event| event.client_id));
+}
+
+#[derive(Resource, Default)]
+struct RejectedClients(Vec<ClientRejected>);
+
+fn collect_rejections(
+    mut rejected_events: EventReader<ClientRejected>,
+    mut rejected_clients: ResMut<RejectedClients>,
+) {
+    rejected_clients.0.extend(rejected_events.read().copied());
+}